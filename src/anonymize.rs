@@ -0,0 +1,265 @@
+use chrono::TimeDelta;
+use sha2::{Digest, Sha256};
+
+use crate::headers::annotation_list::AnnotationList;
+use crate::headers::edf_header::EDFHeader;
+
+/// Describes how an identifying subfield of the header is replaced when anonymizing a file
+#[derive(Debug, Default, Clone, PartialEq)]
+pub enum IdentifierReplacement {
+    /// The subfield is replaced by the unknown value `X`
+    #[default]
+    Unknown,
+
+    /// The subfield is replaced by a pseudonym derived from its value and the salt of the options. The same value
+    /// always results in the same pseudonym when using the same salt, which allows linking files of the same patient.
+    /// Pseudonyms consist of 16 hexadecimal digits (64 bits of the salted hash), where two different values only share
+    /// the same pseudonym with a probability of about 50% once there are roughly 5 billion distinct values
+    Pseudonym,
+
+    /// The subfield is kept as it is
+    Keep,
+}
+
+/// Describes how the start date of a recording is shifted when anonymizing a file. All onsets are relative to the
+/// start of the recording, therefore the relative timing of the data-records and annotations is kept in any case.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub enum DateShift {
+    /// The start date is kept as it is
+    #[default]
+    None,
+
+    /// The start date is shifted by the given amount of days
+    Days(i64),
+
+    /// The start date is shifted back by 1 up to the given amount of days. The amount of days is derived from the
+    /// salt of the options and the patient code (or the patient name in case there is no code), which shifts all files
+    /// of the same patient by the same amount of days. Using a random salt results in a random shift
+    Consistent { max_days: u32 },
+}
+
+/// The options used to anonymize a file using `EDFFile::anonymize`. The default options remove every identifying subfield
+/// and the additional subfields of the patient and recording identification, while keeping the start date and annotations.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnonymizeOptions {
+    /// The replacement of the patient code (e.g. the hospital administration code of the patient)
+    pub patient_code: IdentifierReplacement,
+
+    /// The replacement of the patient name. In plain EDF and BDF files this is the entire patient identification
+    pub patient_name: IdentifierReplacement,
+
+    /// Whether or not the birthdate of the patient is removed
+    pub remove_birthdate: bool,
+
+    /// The replacement of the hospital administration code of the recording. In plain EDF and BDF files this is the
+    /// entire recording identification
+    pub admin_code: IdentifierReplacement,
+
+    /// The replacement of the technician of the recording
+    pub technician: IdentifierReplacement,
+
+    /// Whether or not the additional subfields of the patient and recording identification are removed
+    pub remove_additional: bool,
+
+    /// The shift of the start date of the recording
+    pub date_shift: DateShift,
+
+    /// The patterns of texts to redact within all annotations (e.g. names or medical record numbers). Patterns are
+    /// matched case-insensitive, where `#` matches any digit and `?` matches any character
+    pub redact_patterns: Vec<String>,
+
+    /// The text replacing every redacted part of an annotation
+    pub redaction: String,
+
+    /// The secret used to derive pseudonyms and consistent date shifts. Anonymizing fails with `EDFError::MissingSalt`
+    /// in case pseudonyms or consistent date shifts are used without a salt, as those could be reversed otherwise
+    pub salt: String,
+}
+
+impl Default for AnonymizeOptions {
+    fn default() -> Self {
+        Self {
+            patient_code: IdentifierReplacement::Unknown,
+            patient_name: IdentifierReplacement::Unknown,
+            remove_birthdate: true,
+            admin_code: IdentifierReplacement::Unknown,
+            technician: IdentifierReplacement::Unknown,
+            remove_additional: true,
+            date_shift: DateShift::None,
+            redact_patterns: Vec::new(),
+            redaction: "XXX".to_string(),
+            salt: String::new(),
+        }
+    }
+}
+
+/// A single change made while anonymizing a file. The original values are not part of the change, which allows sharing
+/// the report together with the anonymized file.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AnonymizationChange {
+    /// A subfield of the header was replaced, where the field is named after its struct field (e.g. `patient_id.code`)
+    HeaderField { field: &'static str, replacement: Option<String> },
+
+    /// The start date of the recording was shifted by the given amount of days
+    StartDate { days: i64 },
+
+    /// Parts of an annotation were redacted, where the record is the index of the data-record containing the annotation
+    Annotation { record: usize, onset: f64, redactions: usize, replacement: String },
+}
+
+/// The audit report of all changes made while anonymizing a file
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct AnonymizationReport {
+    pub changes: Vec<AnonymizationChange>,
+}
+
+impl AnonymizationReport {
+    /// Returns whether or not anything was changed
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl AnonymizeOptions {
+    /// Returns whether or not the options derive any value from the salt (pseudonyms or consistent date shifts)
+    pub(crate) fn requires_salt(&self) -> bool {
+        matches!(self.date_shift, DateShift::Consistent { .. })
+            || [&self.patient_code, &self.patient_name, &self.admin_code, &self.technician]
+                .into_iter()
+                .any(|replacement| *replacement == IdentifierReplacement::Pseudonym)
+    }
+
+    /// Anonymizes the patient and recording identification as well as the start date of the given header and adds all
+    /// changes made to the given report
+    pub(crate) fn anonymize_header(&self, header: &mut EDFHeader, report: &mut AnonymizationReport) {
+        // The date shift has to be derived before the identifying subfields are replaced
+        let days = match &self.date_shift {
+            DateShift::None => 0,
+            DateShift::Days(days) => *days,
+            DateShift::Consistent { max_days } => {
+                let patient = header.patient_id.code.clone().or(header.patient_id.name.clone()).unwrap_or_default();
+                let hash = self.hash(&patient);
+                let value = u64::from_be_bytes(hash[..8].try_into().unwrap());
+                -((value % (*max_days).max(1) as u64) as i64 + 1)
+            }
+        };
+
+        let patient = &mut header.patient_id;
+        self.replace(&mut patient.code, &self.patient_code, "patient_id.code", report);
+        self.replace(&mut patient.name, &self.patient_name, "patient_id.name", report);
+        if self.remove_birthdate && patient.date.take().is_some() {
+            report.changes.push(AnonymizationChange::HeaderField { field: "patient_id.date", replacement: None });
+        }
+        if self.remove_additional && !patient.additional.is_empty() {
+            patient.additional.clear();
+            report.changes.push(AnonymizationChange::HeaderField { field: "patient_id.additional", replacement: None });
+        }
+
+        let recording = &mut header.recording_id;
+        self.replace(&mut recording.admin_code, &self.admin_code, "recording_id.admin_code", report);
+        self.replace(&mut recording.technician, &self.technician, "recording_id.technician", report);
+        if self.remove_additional && !recording.additional.is_empty() {
+            recording.additional.clear();
+            report.changes.push(AnonymizationChange::HeaderField { field: "recording_id.additional", replacement: None });
+        }
+
+        if days != 0 {
+            let shift = TimeDelta::days(days);
+            recording.startdate = recording.startdate.map(|date| date + shift);
+            let start_date = header.get_start_date() + shift;
+            header.with_start_date(start_date);
+            report.changes.push(AnonymizationChange::StartDate { days });
+        }
+    }
+
+    /// Redacts all parts of the texts of the given annotation lists matching any of the redact patterns and adds all
+    /// changes made to the given report. Returns whether or not anything was redacted.
+    pub(crate) fn redact_annotations(&self, record: usize, tals: &mut [AnnotationList], report: &mut AnonymizationReport) -> bool {
+        let mut redacted = false;
+        for tal in tals.iter_mut() {
+            for annotation in tal.annotations.iter_mut().filter(|annotation| !annotation.is_empty()) {
+                let (replacement, redactions) = self.redact(annotation);
+                if redactions > 0 {
+                    *annotation = replacement.clone();
+                    redacted = true;
+                    report.changes.push(AnonymizationChange::Annotation { record, onset: tal.onset, redactions, replacement });
+                }
+            }
+        }
+
+        redacted
+    }
+
+    /// Replaces all parts of the given text matching any of the redact patterns. Returns the redacted text and the
+    /// amount of redacted parts
+    fn redact(&self, text: &str) -> (String, usize) {
+        let chars = text.chars().collect::<Vec<_>>();
+        let patterns = self
+            .redact_patterns
+            .iter()
+            .filter(|pattern| !pattern.is_empty())
+            .map(|pattern| pattern.chars().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        let mut result = String::with_capacity(text.len());
+        let mut redactions = 0;
+        let mut idx = 0;
+        while idx < chars.len() {
+            match patterns.iter().find(|pattern| matches_at(pattern, &chars[idx..])) {
+                Some(pattern) => {
+                    result.push_str(&self.redaction);
+                    redactions += 1;
+                    idx += pattern.len();
+                }
+                None => {
+                    result.push(chars[idx]);
+                    idx += 1;
+                }
+            }
+        }
+
+        (result, redactions)
+    }
+
+    /// Replaces the given subfield according to the given replacement and adds the change to the given report
+    fn replace(
+        &self,
+        value: &mut Option<String>,
+        replacement: &IdentifierReplacement,
+        field: &'static str,
+        report: &mut AnonymizationReport,
+    ) {
+        let replaced = match replacement {
+            IdentifierReplacement::Keep => return,
+            IdentifierReplacement::Unknown => None,
+            IdentifierReplacement::Pseudonym => value.as_ref().map(|value| {
+                self.hash(value)[..8].iter().map(|byte| format!("{:02X}", byte)).collect::<String>()
+            }),
+        };
+
+        if *value != replaced {
+            *value = replaced.clone();
+            report.changes.push(AnonymizationChange::HeaderField { field, replacement: replaced });
+        }
+    }
+
+    /// Hashes the given value together with the salt
+    fn hash(&self, value: &str) -> Vec<u8> {
+        let mut hasher = Sha256::new();
+        hasher.update(self.salt.as_bytes());
+        hasher.update([0]);
+        hasher.update(value.as_bytes());
+        hasher.finalize().to_vec()
+    }
+}
+
+/// Returns whether or not the given text starts with the given pattern, where `#` matches any digit and `?` matches
+/// any character. Letters are compared case-insensitive.
+fn matches_at(pattern: &[char], text: &[char]) -> bool {
+    pattern.len() <= text.len()
+        && pattern.iter().zip(text).all(|(pattern, char)| match pattern {
+            '#' => char.is_ascii_digit(),
+            '?' => true,
+            _ => pattern.to_lowercase().eq(char.to_lowercase()),
+        })
+}
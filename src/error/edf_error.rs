@@ -32,7 +32,16 @@ pub enum EDFError {
     ItemNotFound,
     IndexOutOfBounds,
     InvalidRecordSignals,
-    MismatchedSampleBits
+    MismatchedSampleBits,
+    InvalidSession,
+    SessionMismatch,
+    Cancelled,
+    UnsupportedConversion,
+    PendingChanges,
+    UnalignedGap,
+    RecordsOutOfOrder,
+    IncompatibleFiles,
+    MissingSalt,
 }
 
 impl Error for EDFError {}
@@ -0,0 +1,45 @@
+use crate::headers::signal_header::SignalHeader;
+
+/// Selects the signals of a file to extract using `EDFFile::extract_signals`
+#[derive(Debug, Clone, PartialEq)]
+pub enum SignalSelector {
+    /// Selects the signal at the given index
+    Index(usize),
+
+    /// Selects every signal with a label matching the given pattern, where `*` matches any sequence of characters
+    /// (e.g. `EEG *` selects all signals with a label starting with "EEG ")
+    Label(String),
+}
+
+impl SignalSelector {
+    /// Returns whether or not the given signal at the given index is selected
+    pub fn matches(&self, index: usize, signal: &SignalHeader) -> bool {
+        match self {
+            SignalSelector::Index(selected) => *selected == index,
+            SignalSelector::Label(pattern) => matches_pattern(pattern, &signal.label),
+        }
+    }
+}
+
+/// Returns whether or not the given value matches the given pattern, where `*` matches any sequence of characters
+fn matches_pattern(pattern: &str, value: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut remaining) = value.strip_prefix(first) else {
+        return false;
+    };
+
+    // Without any wildcard, the pattern has to match the entire value
+    let parts = parts.collect::<Vec<_>>();
+    let Some((last, middle)) = parts.split_last() else {
+        return remaining.is_empty();
+    };
+    for part in middle {
+        match remaining.find(part) {
+            Some(idx) => remaining = &remaining[idx + part.len()..],
+            None => return false,
+        }
+    }
+
+    remaining.len() >= last.len() && remaining.ends_with(last)
+}
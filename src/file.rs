@@ -1,20 +1,36 @@
 use std::fs::{File, OpenOptions};
-use std::io::{BufRead, BufReader, Cursor, Seek, SeekFrom, Write};
-use std::iter::repeat_n;
+use std::io::{BufRead, BufReader, BufWriter, Cursor, Read, Seek, SeekFrom, Write};
 use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 #[cfg(unix)]
 use std::os::unix::fs::FileExt;
 
+use chrono::TimeDelta;
+
 use crate::EDFSpecifications;
+use crate::anonymize::{AnonymizationReport, AnonymizeOptions};
 use crate::error::edf_error::EDFError;
+use crate::extract::SignalSelector;
+use crate::gaps::{GapFillOptions, GapStrategy, common_duration_ns};
 use crate::headers::annotation_list::AnnotationList;
+use crate::headers::deserialize_options::{DeserializeOptions, DeserializeWarning};
 use crate::headers::edf_header::EDFHeader;
 use crate::headers::signal_header::SignalHeader;
-use crate::record::{Record, Samples, SpanningRecord};
-use crate::save::{SaveInstruction, SaveValue, normalize_instructions};
+use crate::order::RecordOrderReport;
+use crate::progress::{CancellationToken, Progress, ProgressHandler, ProgressOperation};
+use crate::record::{PatchOptions, Record, Samples, SpanningRecord};
+use crate::repair::{PartialRecordStrategy, RepairFix, RepairReport};
+use crate::resample::{ResampleMethod, gcd};
+use crate::rescale::{ClippingStatistics, RescaleMode};
+use crate::save::{ResolvedItems, ResolvedLayout, ResolvedValue, SaveInstruction, SavePlan, SaveValue, normalize_instructions, resolve_index};
+use crate::session::{SessionReader, SessionWriter, get_session_path};
+use crate::split::{SplitMode, part_path};
 use crate::utils::take_vec;
+use crate::validate::{ValidationReport, validate_file};
+
+/// The size of the chunks used to overwrite the truncated end of the file with NUL bytes
+const TRUNCATE_CHUNK_BYTES: usize = 64 * 1024;
 
 /// The desired strategy to delete data-records with. This option only has an effect on EDF+ files and
 /// not on regular EDF files. It determines whether or not to shift the timestamps of data-records
@@ -23,11 +39,9 @@ use crate::utils::take_vec;
 pub enum RecordDeleteStrategy {
     /// In case a record was deleted in the middle of a recording, the timestamps of all
     /// records following the deleted one will be shifted forward by the duration of 1 data-record.
-    /// Therefore EDF+ files will keep the `is_continuous` state they had before deleting.
-    ///
-    /// # Note
-    /// This is not yet implemented and currently removes the record without adjusting the timestamps
-    /// of following records while keeping `is_continuous` unchanged.
+    /// This includes the onsets of the Time-keeping annotations as well as the onsets of all other
+    /// annotations within those records. Therefore EDF+ files will keep the `is_continuous` state they
+    /// had before deleting.
     Continuous,
 
     /// In case a record was deleted in the middle of a recording, the timestamps of all
@@ -57,6 +71,148 @@ pub enum SaveMode {
     Recording,
 }
 
+/// The strategy used to write pending changes to disk when calling `save()`. This option only affects how the
+/// changes are being written and not the resulting file, which is the same for every strategy.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub enum SaveStrategy {
+    /// All changes are written directly into the existing file. Data-records are shifted within the file
+    /// in case their position changes. This requires the least amount of disk space and is the fastest
+    /// strategy for small changes. Note that in case the process crashes or the disk is full while saving,
+    /// the file might be left in a corrupted state. Resampling signals using `ResampleMethod::Linear` or
+    /// `ResampleMethod::Polyphase` requires the initial samples of neighbouring data-records, which could already be
+    /// overwritten, therefore such changes are always saved using the `Atomic` strategy.
+    #[default]
+    InPlace,
+
+    /// The entire edited file is written into a temporary file next to the existing file (with the additional
+    /// extension `.tmp`). Once the temporary file has been written and synced to disk entirely, it atomically
+    /// replaces the existing file. This ensures the file on disk is always either in the state before or after
+    /// saving, even if the process crashes or the disk is full while saving. This requires additional disk space
+    /// for an entire copy of the file. In case saving fails, the pending changes are kept and saving can be retried.
+    Atomic,
+}
+
+/// The state of the file at the time `save()` was called, which is required to map the initial
+/// data-records on disk to their new state
+struct SaveContext {
+    instructions: Vec<SaveInstruction>,
+    record_count: usize,
+    signal_instructions: Vec<SaveInstruction>,
+    initial_record_count: usize,
+    initial_signals: Vec<SignalHeader>,
+    initial_record_duration: f64,
+    initial_header_size: u64,
+    initial_record_bytes: usize,
+    initial_record_position: Option<u64>,
+    initial_is_continuous: bool,
+    initial_specification: EDFSpecifications,
+    shift_onsets: bool,
+    generate_time_keeping: bool,
+}
+
+/// A pending edit of either a data-record or a signal
+#[derive(Debug, Clone, PartialEq)]
+enum Edit {
+    Record(SaveInstruction),
+
+    /// A signal edit together with the options used to patch the samples of the data-records when it was made
+    Signal(SaveInstruction, PatchOptions),
+}
+
+/// An applied edit along with the state before applying it, which is required to revert it
+struct EditHistoryItem {
+    edit: Edit,
+    updated_signals: Option<Vec<SignalHeader>>,
+    updated_specification: Option<EDFSpecifications>,
+    records: Option<Vec<Record>>,
+}
+
+/// The positions of the header and all data-records within the file before and after saving. Item 0 refers
+/// to the header while all following items refer to the data-records in their final order.
+struct InPlaceLayout<'a> {
+    resolved: ResolvedLayout<'a>,
+    record_count: usize,
+    initial_record_count: usize,
+    initial_header_size: u64,
+    initial_record_bytes: u64,
+    header_size: u64,
+    record_bytes: u64,
+}
+
+impl InPlaceLayout<'_> {
+    /// Returns the offset the given item has to be written to
+    fn target_offset(&self, item: usize) -> u64 {
+        match item {
+            0 => 0,
+            _ => self.header_size + (item as u64 - 1) * self.record_bytes,
+        }
+    }
+
+    /// Returns the offset the data-record with the given initial index is stored at before saving
+    fn source_offset(&self, initial_idx: usize) -> u64 {
+        self.initial_header_size + initial_idx as u64 * self.initial_record_bytes
+    }
+
+    /// Returns the last subsequent item whose initial data would be overwritten when writing the given item.
+    /// As the initial data-records keep their order, all items in between are potentially affected as well.
+    fn last_overwritten_item(&self, item: usize) -> Option<usize> {
+        if self.initial_record_bytes == 0 || self.initial_record_count == 0 || self.record_count == 0 {
+            return None;
+        }
+
+        let start = self.target_offset(item);
+        let end = self.target_offset(item + 1);
+        if end <= self.initial_header_size {
+            return None;
+        }
+
+        let first = (start.saturating_sub(self.initial_header_size) / self.initial_record_bytes) as usize;
+        let last = ((end - self.initial_header_size - 1) / self.initial_record_bytes) as usize;
+        if first >= self.initial_record_count {
+            return None;
+        }
+
+        self.resolved
+            .last_edited_index_in(first, last.min(self.initial_record_count - 1))
+            .map(|idx| idx + 1)
+            .filter(|dependency| *dependency > item)
+    }
+}
+
+/// A gap between two data-records of a discontinuous file, which is filled with the given amount of data-records
+/// preceding the data-record at index `record`
+struct FilledGap {
+    record: usize,
+    onset: f64,
+    count: usize,
+}
+
+/// The onset and the Time-keeping annotation of every data-record of a file as well as all other annotations together
+/// with the index of the data-record they are stored in
+struct RecordTiming {
+    onsets: Vec<f64>,
+    time_keeping: Vec<Option<AnnotationList>>,
+    annotations: Vec<(usize, AnnotationList)>,
+}
+
+/// A continuous part of a file being re-blocked, consisting of data-records without any gaps in between
+struct ReblockRun {
+    onset: f64,
+    first_record: usize,
+    initial_count: usize,
+    count: usize,
+}
+
+/// The layout of a re-blocked file, which is determined before writing anything
+struct ReblockPlan {
+    header: EDFHeader,
+    runs: Vec<ReblockRun>,
+    ratio: f64,
+    initial_record_count: usize,
+    record_onsets: Vec<f64>,
+    record_annotations: Vec<Vec<Vec<AnnotationList>>>,
+}
+
 pub struct EDFFile {
     pub header: EDFHeader,
     path: PathBuf,
@@ -65,17 +221,34 @@ pub struct EDFFile {
     gap_read_offset_ns: u128,
     instructions: Vec<SaveInstruction>,
     signal_instructions: Vec<SaveInstruction>,
+    normalized_instructions: Option<Vec<SaveInstruction>>,
     record_counter: usize,
     signal_counter: usize,
     record_delete_strategy: RecordDeleteStrategy,
     save_mode: SaveMode,
+    save_strategy: SaveStrategy,
+    resample_method: ResampleMethod,
+    rescale_mode: RescaleMode,
+    clipping_statistics: Vec<ClippingStatistics>,
+    deserialize_warnings: Vec<DeserializeWarning>,
+    undo_history: Vec<EditHistoryItem>,
+    redo_history: Vec<Edit>,
+    progress_handler: Option<ProgressHandler>,
+    cancellation_token: Option<CancellationToken>,
 }
 
 impl EDFFile {
     pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, EDFError> {
+        Self::open_with_options(path, &DeserializeOptions::strict())
+    }
+
+    /// Opens the file like `open`, deserializing its header using the given options. In lenient mode, common violations
+    /// of the specification in the header are fixed instead of resulting in an error. All fixed violations are available
+    /// using `get_deserialize_warnings()` and are written to disk when saving the file.
+    pub fn open_with_options<P: AsRef<Path>>(path: P, options: &DeserializeOptions) -> Result<Self, EDFError> {
         let file = File::open(&path).map_err(EDFError::FileReadError)?;
         let mut reader = BufReader::new(file);
-        let header = EDFHeader::deserialize(&mut reader)?;
+        let (header, deserialize_warnings) = EDFHeader::deserialize_with_options(&mut reader, options)?;
 
         Ok(Self {
             record_counter: header.record_count.unwrap_or(0),
@@ -85,13 +258,31 @@ impl EDFFile {
             gap_read_offset_ns: 0,
             signal_instructions: Vec::new(),
             instructions: Vec::new(),
+            normalized_instructions: None,
             header,
             reader,
             record_delete_strategy: RecordDeleteStrategy::default(),
             save_mode: SaveMode::default(),
+            save_strategy: SaveStrategy::default(),
+            resample_method: ResampleMethod::default(),
+            rescale_mode: RescaleMode::default(),
+            clipping_statistics: Vec::new(),
+            deserialize_warnings,
+            undo_history: Vec::new(),
+            redo_history: Vec::new(),
+            progress_handler: None,
+            cancellation_token: None,
         })
     }
 
+    /// Validates the file at the given path against the specification it claims to follow and returns every violation
+    /// found, instead of failing on the first one like `open`. This checks the format of every header field, the size of the
+    /// header and the file, the samples of every data-record on disk and, for EDF+ and BDF+ files, the annotations and the
+    /// Time-keeping annotation of every data-record. See `ViolationKind` for all checks.
+    pub fn validate<P: AsRef<Path>>(path: P) -> Result<ValidationReport, EDFError> {
+        validate_file(path.as_ref())
+    }
+
     pub fn new<P: AsRef<Path>>(path: P) -> Result<Self, EDFError> {
         // Ensure the provided file does not exist yet and create the empty file
         if path.as_ref().exists() {
@@ -113,8 +304,18 @@ impl EDFFile {
             record_counter: 0,
             signal_instructions: Vec::new(),
             instructions: vec![SaveInstruction::WriteHeader],
+            normalized_instructions: None,
             record_delete_strategy: RecordDeleteStrategy::default(),
             save_mode: SaveMode::default(),
+            save_strategy: SaveStrategy::default(),
+            resample_method: ResampleMethod::default(),
+            rescale_mode: RescaleMode::default(),
+            clipping_statistics: Vec::new(),
+            deserialize_warnings: Vec::new(),
+            undo_history: Vec::new(),
+            redo_history: Vec::new(),
+            progress_handler: None,
+            cancellation_token: None,
         })
     }
 
@@ -125,115 +326,386 @@ impl EDFFile {
         self.instructions.insert(0, SaveInstruction::WriteHeader);
     }
 
-    pub fn insert_signal(&mut self, index: usize, signal: SignalHeader) -> Result<(), EDFError> {
-        let instruction = SaveInstruction::Insert(index, SaveValue::Signal(signal.clone()));
-        self.header.modify_signals().insert(index, signal);
+    /// Updates the strategy used to remove data-records on the next call of the `save()` function.
+    /// See `RecordDeleteStrategy` for more details.
+    pub fn set_record_delete_strategy(&mut self, strategy: RecordDeleteStrategy) {
+        self.record_delete_strategy = strategy;
+    }
+
+    pub fn get_record_delete_strategy(&self) -> &RecordDeleteStrategy {
+        &self.record_delete_strategy
+    }
 
-        // Patch all records in pending instructions
-        self.patch_records_with_instruction(instruction.clone())?;
+    /// Updates the strategy used to write the pending changes to disk on the next call of the `save()` function.
+    /// See `SaveStrategy` for more details.
+    pub fn set_save_strategy(&mut self, strategy: SaveStrategy) {
+        self.save_strategy = strategy;
+    }
 
-        // Add the instruction
-        self.signal_counter += 1;
-        self.signal_instructions.push(instruction);
+    pub fn get_save_strategy(&self) -> &SaveStrategy {
+        &self.save_strategy
+    }
 
-        Ok(())
+    /// Updates the method used to convert the samples of signals whose samples count is changed using `update_signal()`.
+    /// The method in effect when updating the signal is applied to pending data-records right away and to all data-records
+    /// stored on disk when saving, even if the method is changed in between. See `ResampleMethod` for more details.
+    pub fn set_resample_method(&mut self, method: ResampleMethod) {
+        self.resample_method = method;
     }
 
-    pub fn update_signal(&mut self, index: usize, signal: SignalHeader) -> Result<(), EDFError> {
-        let instruction = SaveInstruction::Update(index, SaveValue::Signal(signal.clone()));
-        self.header.modify_signals()[index] = signal;
+    pub fn get_resample_method(&self) -> &ResampleMethod {
+        &self.resample_method
+    }
 
-        // Patch all records in pending instructions
-        self.patch_records_with_instruction(instruction.clone())?;
+    /// Updates the way the samples of signals whose physical or digital range is changed using `update_signal()` are
+    /// treated. The mode in effect when updating the signal is applied to pending data-records right away and to all
+    /// data-records stored on disk when saving, even if the mode is changed in between. See `RescaleMode` for more details.
+    pub fn set_rescale_mode(&mut self, mode: RescaleMode) {
+        self.rescale_mode = mode;
+    }
 
-        // Add the instruction
-        self.signal_instructions.push(instruction);
+    pub fn get_rescale_mode(&self) -> &RescaleMode {
+        &self.rescale_mode
+    }
 
-        Ok(())
+    /// Returns the amount of clipped samples of every signal while rescaling the data-records stored on disk during the
+    /// most recent save or export. Data-records which are pending are rescaled when the signal is updated and are therefore
+    /// not included. See `RescaleMode::KeepPhysical` for more details.
+    pub fn get_clipping_statistics(&self) -> &Vec<ClippingStatistics> {
+        &self.clipping_statistics
+    }
+
+    /// Returns every violation of the specification which was fixed while opening the file in lenient mode. See
+    /// `DeserializeOptions` for more details.
+    pub fn get_deserialize_warnings(&self) -> &Vec<DeserializeWarning> {
+        &self.deserialize_warnings
+    }
+
+    /// Sets the function being called with the current progress of long running operations like saving the file.
+    /// See `ProgressOperation` for all operations reporting their progress.
+    pub fn set_progress_handler<F: FnMut(&Progress) + Send + 'static>(&mut self, handler: F) {
+        self.progress_handler = Some(Box::new(handler));
+    }
+
+    pub fn clear_progress_handler(&mut self) {
+        self.progress_handler = None;
+    }
+
+    /// Sets the token used to cancel long running operations. Note that saving with the `SaveStrategy::InPlace`
+    /// strategy can only be cancelled before anything was written to disk, as the file would be left in a corrupted
+    /// state otherwise. Use the `SaveStrategy::Atomic` strategy to be able to cancel saving at any time.
+    pub fn set_cancellation_token(&mut self, token: CancellationToken) {
+        self.cancellation_token = Some(token);
+    }
+
+    pub fn clear_cancellation_token(&mut self) {
+        self.cancellation_token = None;
+    }
+
+    /// Returns an error in case the current operation was requested to be cancelled
+    fn check_cancelled(&self) -> Result<(), EDFError> {
+        match &self.cancellation_token {
+            Some(token) if token.is_cancelled() => Err(EDFError::Cancelled),
+            _ => Ok(()),
+        }
+    }
+
+    fn report_progress(&mut self, operation: ProgressOperation, processed: u64, total: u64) {
+        if let Some(handler) = &mut self.progress_handler {
+            handler(&Progress {
+                operation,
+                processed,
+                total,
+            });
+        }
+    }
+
+    pub fn insert_signal(&mut self, index: usize, signal: SignalHeader) -> Result<(), EDFError> {
+        if self.signal_counter < index {
+            return Err(EDFError::IndexOutOfBounds);
+        }
+        self.apply_edit(Edit::Signal(SaveInstruction::Insert(index, SaveValue::Signal(signal)), self.get_patch_options()))
+    }
+
+    pub fn update_signal(&mut self, index: usize, signal: SignalHeader) -> Result<(), EDFError> {
+        if self.signal_counter <= index {
+            return Err(EDFError::IndexOutOfBounds);
+        }
+        self.apply_edit(Edit::Signal(SaveInstruction::Update(index, SaveValue::Signal(signal)), self.get_patch_options()))
     }
 
     pub fn remove_signal(&mut self, index: usize) -> Result<(), EDFError> {
         if self.signal_counter <= index {
             return Err(EDFError::IndexOutOfBounds);
         }
-        let instruction = SaveInstruction::Remove(index);
-        self.header.modify_signals().remove(index);
+        self.apply_edit(Edit::Signal(SaveInstruction::Remove(index), self.get_patch_options()))
+    }
+
+    /// Moves the signal at index `from` to index `to` while keeping the samples and annotations of every data-record.
+    /// The index `to` refers to the position of the signal after moving it, therefore all signals in between are
+    /// shifted by one position towards `from`.
+    pub fn move_signal(&mut self, from: usize, to: usize) -> Result<(), EDFError> {
+        if self.signal_counter <= from || self.signal_counter <= to {
+            return Err(EDFError::IndexOutOfBounds);
+        }
+        self.apply_edit(Edit::Signal(SaveInstruction::Move(from, to), self.get_patch_options()))
+    }
+
+    /// Converts the file to the given specification, which changes the size of every sample between 16 bit (EDF and EDF+)
+    /// and 24 bit (BDF and BDF+). The signals are converted using `SignalHeader::convert_specification` and the samples of
+    /// every data-record are re-quantized to keep their physical values. The amount of samples clipped while converting the
+    /// data-records stored on disk is available using `get_clipping_statistics()` after saving. Converting between plain and
+    /// extended specifications (e.g. EDF to EDF+) is not supported. Note that changing the specification of an existing file
+    /// using `EDFHeader::with_specification()` does not convert the data-records.
+    pub fn convert_specification(&mut self, specification: EDFSpecifications) -> Result<(), EDFError> {
+        if self.header.get_specification().is_plus() != specification.is_plus() {
+            return Err(EDFError::UnsupportedConversion);
+        }
+        self.apply_edit(Edit::Signal(SaveInstruction::Convert(specification), self.get_patch_options()))
+    }
+
+    /// Upgrades a plain EDF (or BDF) file to a continuous EDF+ (or BDF+) file. An annotation signal of the given size
+    /// (see `SignalHeader::new_annotation`) is appended to the signals, which has to be large enough to store the
+    /// Time-keeping annotation list of every data-record. While saving, the Time-keeping annotation list of every data-record
+    /// is generated from the index of the data-record multiplied by the data-record duration and the free text patient and
+    /// recording identifications are parsed into their EDF+ subfields (see `PatientId::from_legacy` and `RecordingId::from_legacy`).
+    /// This adds two edits to the undo history, one converting the specification and one inserting the annotation signal.
+    pub fn convert_to_plus(&mut self, annotation_size: usize) -> Result<(), EDFError> {
+        let specification = match self.header.get_specification() {
+            EDFSpecifications::EDF => EDFSpecifications::EDFPlus,
+            EDFSpecifications::BDF => EDFSpecifications::BDFPlus,
+            _ => return Err(EDFError::UnsupportedConversion),
+        };
+
+        self.apply_edit(Edit::Signal(SaveInstruction::Convert(specification.clone()), self.get_patch_options()))?;
+        self.insert_signal(self.header.get_signals().len(), SignalHeader::new_annotation(annotation_size, specification))
+    }
+
+    /// Applies the given edit to the pending changes and adds it to the undo history
+    fn apply_edit(&mut self, edit: Edit) -> Result<(), EDFError> {
+        let mut item = EditHistoryItem {
+            edit: edit.clone(),
+            updated_signals: None,
+            updated_specification: None,
+            records: None,
+        };
+
+        match edit {
+            Edit::Record(instruction) => {
+                match instruction {
+                    SaveInstruction::Insert(..) | SaveInstruction::Append(_) => self.record_counter += 1,
+                    SaveInstruction::Remove(_) => self.record_counter -= 1,
+                    _ => {}
+                }
+                self.instructions.push(instruction);
+            }
+            Edit::Signal(instruction, options) => {
+                item.updated_signals = self.header.updated_signals.clone();
+                item.updated_specification = self.header.updated_specification.clone();
+                let previous_signals = self.header.get_signals().clone();
+                match &instruction {
+                    SaveInstruction::Insert(idx, SaveValue::Signal(signal)) => {
+                        self.header.modify_signals().insert(*idx, signal.clone());
+                        self.signal_counter += 1;
+                    }
+                    SaveInstruction::Update(idx, SaveValue::Signal(signal)) => {
+                        self.header.modify_signals()[*idx] = signal.clone();
+                    }
+                    SaveInstruction::Remove(idx) => {
+                        self.header.modify_signals().remove(*idx);
+                        self.signal_counter -= 1;
+                    }
+                    SaveInstruction::Move(from, to) => {
+                        let signals = self.header.modify_signals();
+                        let signal = signals.remove(*from);
+                        signals.insert(*to, signal);
+                    }
+                    SaveInstruction::Convert(specification) => {
+                        let previous_specification = self.header.get_specification();
+                        for signal in self.header.modify_signals() {
+                            *signal = signal.convert_specification(&previous_specification, specification);
+                        }
+                        self.header.updated_specification = Some(specification.clone());
+                    }
+                    _ => {}
+                }
+
+                // Updating, removing or converting signals potentially discards samples of pending records, therefore
+                // the records have to be kept to be able to revert the edit
+                if !matches!(instruction, SaveInstruction::Insert(..) | SaveInstruction::Move(..)) {
+                    item.records = Some(self.pending_records_mut().map(|record| record.clone()).collect());
+                }
+
+                // Patch all records in pending instructions
+                self.patch_records_with_instruction(instruction.clone(), &previous_signals, &options)?;
+
+                // Records stored on disk are patched while saving, therefore the options in effect when the edit was made
+                // have to be kept for the instruction in case they differ from the ones of the preceding instructions
+                if matches!(instruction, SaveInstruction::Update(..)) && self.get_instruction_patch_options() != options {
+                    self.signal_instructions.push(SaveInstruction::Patch(options));
+                }
 
-        // Patch all records in pending instructions
-        self.patch_records_with_instruction(instruction.clone())?;
+                // Add the instruction
+                self.signal_instructions.push(instruction);
+            }
+        }
 
-        // Add the instruction
-        self.signal_counter -= 1;
-        self.signal_instructions.push(instruction);
+        self.undo_history.push(item);
+        self.redo_history.clear();
+        self.normalized_instructions = None;
 
         Ok(())
     }
 
-    fn patch_records_with_instruction(
-        &mut self,
-        instruction: SaveInstruction,
-    ) -> Result<(), EDFError> {
-        let instruction_listed = vec![instruction];
-        for record in self.instructions.iter_mut().filter_map(|i| match i {
+    fn pending_records_mut(&mut self) -> impl Iterator<Item = &mut Record> {
+        self.instructions.iter_mut().filter_map(|i| match i {
             SaveInstruction::Append(SaveValue::Record(record))
             | SaveInstruction::Insert(_, SaveValue::Record(record))
             | SaveInstruction::Update(_, SaveValue::Record(record)) => Some(record),
             _ => None,
-        }) {
-            record.patch_record(&instruction_listed)?;
+        })
+    }
+
+    /// Patches all pending records with the given signal instruction using the given options. The given signals have
+    /// to be the ones the records matched before applying the instruction.
+    fn patch_records_with_instruction(
+        &mut self,
+        instruction: SaveInstruction,
+        signals: &[SignalHeader],
+        options: &PatchOptions,
+    ) -> Result<(), EDFError> {
+        let instruction_listed = vec![SaveInstruction::Patch(options.clone()), instruction];
+        for record in self.pending_records_mut() {
+            record.patch_record(&instruction_listed, signals)?;
         }
 
         Ok(())
     }
 
+    /// Returns the patch options which apply to the next signal instruction, i.e. the ones of the last PATCH instruction
+    /// or the default options if there is none
+    fn get_instruction_patch_options(&self) -> PatchOptions {
+        self.signal_instructions
+            .iter()
+            .rev()
+            .find_map(|i| match i {
+                SaveInstruction::Patch(options) => Some(options.clone()),
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+
+    fn get_patch_options(&self) -> PatchOptions {
+        PatchOptions {
+            resample_method: self.resample_method.clone(),
+            rescale_mode: self.rescale_mode.clone(),
+        }
+    }
+
     pub fn insert_record(&mut self, index: usize, record: Record) -> Result<(), EDFError> {
         if !record.matches_signals(self.header.get_signals()) {
             return Err(EDFError::InvalidRecordSignals);
         }
-
-        self.record_counter += 1;
-        self.instructions
-            .push(SaveInstruction::Insert(index, SaveValue::Record(record)));
-
-        Ok(())
+        self.apply_edit(Edit::Record(SaveInstruction::Insert(index, SaveValue::Record(record))))
     }
 
     pub fn update_record(&mut self, index: usize, record: Record) -> Result<(), EDFError> {
         if !record.matches_signals(self.header.get_signals()) {
             return Err(EDFError::InvalidRecordSignals);
         }
-
-        self.instructions
-            .push(SaveInstruction::Update(index, SaveValue::Record(record)));
-
-        Ok(())
+        self.apply_edit(Edit::Record(SaveInstruction::Update(index, SaveValue::Record(record))))
     }
 
     pub fn append_record(&mut self, record: Record) -> Result<(), EDFError> {
         if !record.matches_signals(self.header.get_signals()) {
             return Err(EDFError::InvalidRecordSignals);
         }
-
-        self.record_counter += 1;
-        self.instructions
-            .push(SaveInstruction::Append(SaveValue::Record(record)));
-
-        Ok(())
+        self.apply_edit(Edit::Record(SaveInstruction::Append(SaveValue::Record(record))))
     }
 
     /// Removes the record at the given index. If the file is an EDF+ file, it will adjust the offset
-    /// of data-records after the given record in case `record_delete_strategy` is `ShiftOffsets` (Therefore keeping continuous
+    /// of data-records after the given record in case `record_delete_strategy` is `Continuous` (Therefore keeping continuous
     /// EDF+ files continuous). Otherwise it will remove the data-record without shifting the offset of subsequent
     /// data-records (Therefore making continuous EDF+ files discontinuous).
     pub fn remove_record(&mut self, index: usize) -> Result<(), EDFError> {
         if self.record_counter <= index {
             return Err(EDFError::IndexOutOfBounds);
         }
+        self.apply_edit(Edit::Record(SaveInstruction::Remove(index)))
+    }
+
+    /// Reverts the most recent pending edit of a record or signal. Edits which were already saved can not be
+    /// reverted anymore. Returns `false` in case there was no edit to be reverted.
+    pub fn undo(&mut self) -> Result<bool, EDFError> {
+        let Some(item) = self.undo_history.pop() else {
+            return Ok(false);
+        };
+
+        match &item.edit {
+            Edit::Record(instruction) => {
+                self.instructions.pop();
+                match instruction {
+                    SaveInstruction::Insert(..) | SaveInstruction::Append(_) => self.record_counter -= 1,
+                    SaveInstruction::Remove(_) => self.record_counter += 1,
+                    _ => {}
+                }
+            }
+            Edit::Signal(instruction, options) => {
+                self.signal_instructions.pop();
+                if matches!(self.signal_instructions.last(), Some(SaveInstruction::Patch(_))) {
+                    self.signal_instructions.pop();
+                }
+                let signals = self.header.get_signals().clone();
+                self.header.updated_signals = item.updated_signals;
+                self.header.updated_specification = item.updated_specification;
+                match instruction {
+                    SaveInstruction::Insert(idx, _) => {
+                        self.signal_counter -= 1;
+                        self.patch_records_with_instruction(SaveInstruction::Remove(*idx), &signals, options)?;
+                    }
+                    SaveInstruction::Move(from, to) => {
+                        self.patch_records_with_instruction(SaveInstruction::Move(*to, *from), &signals, options)?;
+                    }
+                    SaveInstruction::Remove(_) => self.signal_counter += 1,
+                    _ => {}
+                }
+
+                // Restore the pending records which could not be patched back without losing their samples
+                if let Some(records) = item.records {
+                    for (record, initial) in self.pending_records_mut().zip(records) {
+                        *record = initial;
+                    }
+                }
+            }
+        }
 
-        self.record_counter -= 1;
-        self.instructions.push(SaveInstruction::Remove(index));
+        self.redo_history.push(item.edit);
+        self.normalized_instructions = None;
 
-        Ok(())
+        Ok(true)
+    }
+
+    /// Re-applies the most recently reverted edit. Any new edit after calling `undo()` discards all edits
+    /// which could be re-applied. Returns `false` in case there was no edit to be re-applied.
+    pub fn redo(&mut self) -> Result<bool, EDFError> {
+        let Some(edit) = self.redo_history.pop() else {
+            return Ok(false);
+        };
+
+        // Applying the edit would otherwise discard the remaining edits to be re-applied
+        let redo_history = std::mem::take(&mut self.redo_history);
+        let result = self.apply_edit(edit);
+        self.redo_history = redo_history;
+
+        result.map(|_| true)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo_history.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo_history.is_empty()
     }
 
     fn records_match_signals(&self) -> bool {
@@ -249,21 +721,37 @@ impl EDFFile {
             .any(|record| !record.matches_signals(self.header.get_signals()))
     }
 
+    /// Applies all pending changes to the file on disk. Depending on the configured `SaveStrategy`, the
+    /// changes are either written directly into the existing file or into a temporary file which replaces
+    /// the existing file once it has been written entirely. See `SaveStrategy` for more details.
     pub fn save(&mut self) -> Result<(), EDFError> {
-        let mut file = OpenOptions::new()
-            .read(true)
-            .write(true)
-            .create(true)
-            .open(&self.path)
-            .map_err(EDFError::FileWriteError)?;
+        match self.get_effective_save_strategy() {
+            SaveStrategy::InPlace => self.save_in_place(),
+            SaveStrategy::Atomic => self.save_atomic(),
+        }
+    }
+
+    /// Returns the strategy `save()` uses for the pending changes, which is `Atomic` in case data-records stored on
+    /// disk are resampled using their neighbouring data-records (see `SaveStrategy::InPlace`)
+    fn get_effective_save_strategy(&self) -> SaveStrategy {
+        let signal_instructions = normalize_instructions(&self.signal_instructions, self.header.signal_count);
+        match requires_neighbours(&signal_instructions) {
+            true => SaveStrategy::Atomic,
+            false => self.save_strategy.clone(),
+        }
+    }
 
-        let initial_filesize = file.metadata().map_err(EDFError::FileWriteError)?.len();
+    /// Updates the header to match the state after saving and collects everything required to map the
+    /// data-records on disk to their new state. Returns `None` in case there is nothing to be saved.
+    fn prepare_save(&mut self, initial_filesize: u64) -> Result<Option<SaveContext>, EDFError> {
         let initial_signal_count = self.header.signal_count;
-        let initial_record_count = self.header.record_count.unwrap_or(0);
+        let initial_record_count = self.get_initial_record_count(initial_filesize);
         let initial_signals = self.header.signals.clone();
         let initial_record_duration = self.header.record_duration;
         let initial_header_size = self.header.header_bytes as u64;
         let initial_record_bytes = self.header.get_initial_record_bytes();
+        let initial_is_continuous = self.header.is_continuous;
+        let initial_specification = self.header.specification.clone();
 
         // Update all header values to match the new state
 
@@ -272,25 +760,33 @@ impl EDFFile {
             self.header.record_count = Some(self.record_counter);
         }
 
-        // Set the new signals and update the signal count
+        // Set the new signals and specification and update the signal count
         if let Some(updated) = self.header.updated_signals.take() {
             self.header.signals = updated;
         }
+        if let Some(updated) = self.header.updated_specification.take() {
+            self.header.specification = updated;
+        }
         self.header.signal_count = self.header.signals.len();
 
+        // Upgraded plain files are continuous and have their free text identifications parsed into EDF+ subfields
+        let generate_time_keeping = !initial_specification.is_plus() && self.header.specification.is_plus();
+        if generate_time_keeping {
+            self.header.is_continuous = true;
+            self.header.upgrade_ids_to_plus();
+        }
+
         // In case there are no signals, remove all records as they will all have a length of 0 bytes
         if self.header.signal_count == 0 {
             self.header.record_count = self.header.record_count.map(|_| 0);
         }
 
-        // Calculate new header and record sizes
+        // Calculate new header size
         self.header.header_bytes = self.header.calculate_header_bytes();
-        let new_record_bytes = self.header.data_record_bytes();
 
         // Ensure WriteHeader is at max once (and at index 0) and automatically add it if the header changed and it is not yet present
-        let header_size_diff = self.header.header_bytes as i64 - initial_header_size as i64;
         let header_changed =
-            *self.header.get_initial_header_sha256() != self.header.get_sha256()?;
+            self.header.needs_rewrite() || *self.header.get_initial_header_sha256() != self.header.get_sha256()?;
         let header_instruct_positions = self
             .instructions
             .iter()
@@ -301,360 +797,1665 @@ impl EDFFile {
             for i in header_instruct_positions.iter().rev() {
                 self.instructions.remove(*i);
             }
-            self.instructions.insert(0, SaveInstruction::WriteHeader);
-        } else if header_instruct_positions.len() >= 1 {
-            for i in header_instruct_positions.iter().skip(1).rev() {
-                self.instructions.remove(*i);
+            self.instructions.insert(0, SaveInstruction::WriteHeader);
+        } else if header_instruct_positions.len() >= 1 {
+            for i in header_instruct_positions.iter().skip(1).rev() {
+                self.instructions.remove(*i);
+            }
+        } else if header_instruct_positions.is_empty() && header_changed {
+            self.instructions.insert(0, SaveInstruction::WriteHeader);
+        }
+
+        // Try to get the current read position to go back to after saving
+        let initial_read_position = self
+            .reader
+            .stream_position()
+            .map_err(EDFError::FileWriteError)?;
+        let initial_record_position = if initial_record_bytes == 0 {
+            None
+        } else {
+            initial_read_position
+                .checked_sub(initial_header_size)
+                .map(|pos| pos / initial_record_bytes as u64)
+        };
+
+        // If there are no instructions at all, nothing has to be done and the input remains the same
+        if self.instructions.is_empty() && self.signal_instructions.is_empty() {
+            return Ok(None);
+        }
+
+        // Transform the list of instructions into a simplified sorted list of instructions
+        let mut instructions = normalize_instructions(&self.instructions, initial_record_count);
+        let signal_instructions =
+            normalize_instructions(&self.signal_instructions, initial_signal_count);
+
+        // Ensure all records have the correct signal layout before writing anything to disk
+        if !self.records_match_signals() {
+            return Err(EDFError::InvalidRecordSignals);
+        }
+
+        // If there were instructions, but all cancelled out, nothing has to be done either and the
+        // input remains the same again. e.g. Insert at index 1 followed by Delete at index 1.
+        if instructions.is_empty() && signal_instructions.is_empty() {
+            self.instructions.clear();
+            self.signal_instructions.clear();
+            self.normalized_instructions = None;
+            self.undo_history.clear();
+            self.redo_history.clear();
+            return Ok(None);
+        }
+
+        // Depending on the delete strategy, update EDF+ files to be discontinuous after deleting a record. Upgraded files
+        // always remain continuous, as their Time-keeping annotations are generated from the new data-record indices
+        let is_plus = self.header.specification.is_plus() && !generate_time_keeping;
+        let removes_middle_record = instructions.iter().any(|i| matches!(i, SaveInstruction::Remove(idx) if *idx > 0 && *idx < self.record_counter - 1));
+        if is_plus
+            && self.header.is_continuous
+            && removes_middle_record
+            && self.record_delete_strategy == RecordDeleteStrategy::Discontinuous
+        {
+            self.header.is_continuous = false;
+
+            // The header has to be written in case this is its only change
+            if !instructions.contains(&SaveInstruction::WriteHeader) {
+                instructions.insert(0, SaveInstruction::WriteHeader);
+            }
+        }
+
+        // Depending on the delete strategy, the onsets of all records following a deleted record have to be shifted
+        let shift_onsets = is_plus
+            && self.record_delete_strategy == RecordDeleteStrategy::Continuous
+            && instructions.iter().any(|i| matches!(i, SaveInstruction::Remove(_)));
+
+        // The amount of data-records after saving
+        let record_count = if self.header.signal_count == 0 {
+            0
+        } else {
+            instructions.iter().fold(initial_record_count, |count, i| match i {
+                SaveInstruction::Insert(..) => count + 1,
+                SaveInstruction::Remove(_) => count - 1,
+                _ => count,
+            })
+        };
+
+        Ok(Some(SaveContext {
+            instructions,
+            record_count,
+            signal_instructions,
+            initial_record_count,
+            initial_signals,
+            initial_record_duration,
+            initial_header_size,
+            initial_record_bytes,
+            initial_record_position,
+            initial_is_continuous,
+            initial_specification,
+            shift_onsets,
+            generate_time_keeping,
+        }))
+    }
+
+    fn save_in_place(&mut self) -> Result<(), EDFError> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&self.path)
+            .map_err(EDFError::FileWriteError)?;
+
+        // Saving can only be cancelled before anything is written, as the file would be corrupted otherwise
+        self.check_cancelled()?;
+
+        let filesize = file.metadata().map_err(EDFError::FileWriteError)?.len();
+        let Some(context) = self.prepare_save(filesize)? else {
+            return Ok(());
+        };
+
+        self.clipping_statistics = vec![ClippingStatistics::default(); self.header.signal_count];
+        let resolved = ResolvedLayout::new(&context.instructions, context.initial_record_count);
+        let layout = InPlaceLayout {
+            record_count: context.record_count,
+            resolved,
+            initial_record_count: context.initial_record_count,
+            initial_header_size: context.initial_header_size,
+            initial_record_bytes: context.initial_record_bytes as u64,
+            header_size: self.header.header_bytes as u64,
+            record_bytes: self.header.data_record_bytes() as u64,
+        };
+        let write_header = context.instructions.contains(&SaveInstruction::WriteHeader);
+
+        // Every item (the header at item 0 followed by all data-records) is written to its new position one after
+        // another. Items moving towards the end of the file would overwrite the initial data of subsequent items
+        // before those were read. Therefore all items depending on each other are grouped into a single run which
+        // is written back-to-front. This ensures every item is read before its initial data gets overwritten while
+        // only ever requiring a single data-record to be kept in memory, regardless of the file size.
+        let mut buffer = vec![0; context.initial_record_bytes];
+        let mut processed = 0;
+        let mut item = 0;
+        while item <= layout.record_count {
+            let mut run_end = item;
+            let mut current = item;
+            while current <= run_end {
+                if let Some(dependency) = layout.last_overwritten_item(current) {
+                    run_end = run_end.max(dependency);
+                }
+                current += 1;
+            }
+
+            for current in (item..=run_end).rev() {
+                if current == 0 {
+                    if write_header {
+                        seek_write_all_at(&mut file, &self.header.serialize()?, 0)
+                            .map_err(EDFError::FileWriteError)?;
+                    }
+                    continue;
+                }
+
+                self.write_in_place_record(&mut file, &context, &layout, &mut buffer, current)?;
+                processed += 1;
+                self.report_progress(ProgressOperation::Save, processed, layout.record_count as u64);
+            }
+
+            item = run_end + 1;
+        }
+
+        // In case the file size was reduced, truncate the file (and replace the content to be truncated with NUL bytes before)
+        let new_file_size = layout.target_offset(layout.record_count + 1);
+        if new_file_size < filesize {
+            let zeros = vec![0; TRUNCATE_CHUNK_BYTES];
+            let mut position = new_file_size;
+            while position < filesize {
+                let length = (filesize - position).min(TRUNCATE_CHUNK_BYTES as u64) as usize;
+                seek_write_all_at(&mut file, &zeros[..length], position).map_err(EDFError::FileWriteError)?;
+                position += length as u64;
+            }
+            file.set_len(new_file_size).map_err(EDFError::FileWriteError)?;
+        }
+
+        // Flush the write buffer and get the new file length
+        file.flush().map_err(EDFError::FileWriteError)?;
+        let new_file_size = file.metadata().map_err(EDFError::FileWriteError)?.len();
+
+        self.finish_save(&context, new_file_size)
+    }
+
+    /// Writes the data-record of the given item to its new position within the file. Records which are stored
+    /// on disk are only read and written in case they are moved or their content changes.
+    fn write_in_place_record(
+        &mut self,
+        file: &mut File,
+        context: &SaveContext,
+        layout: &InPlaceLayout,
+        buffer: &mut Vec<u8>,
+        item: usize
+    ) -> Result<(), EDFError> {
+        let target_offset = layout.target_offset(item);
+        let data = match layout.resolved.get(item - 1) {
+            Some(ResolvedValue::Pending(SaveValue::Record(record))) => self.serialize_pending_record(context, record, item - 1)?,
+            Some(ResolvedValue::Initial(idx)) => {
+                let removed_counter = layout.resolved.removed_count(item - 1);
+                let source_offset = layout.source_offset(idx);
+                let shift_record = context.shift_onsets && removed_counter > 0;
+
+                // Records which neither move nor change do not have to be touched at all
+                if source_offset == target_offset && context.signal_instructions.is_empty() && !shift_record {
+                    return Ok(());
+                }
+
+                seek_read_exact_at(file, buffer, source_offset)
+                    .map_err(EDFError::FileWriteError)?;
+                // Saving in place falls back to saving atomically whenever neighbours are required to resample records
+                self.patch_initial_record(context, buffer.clone(), idx, removed_counter, item - 1, (None, None))?
+            }
+            _ => return Err(EDFError::InvalidRecordSignals),
+        };
+
+        seek_write_all_at(file, &data, target_offset).map_err(EDFError::FileWriteError)
+    }
+
+    fn save_atomic(&mut self) -> Result<(), EDFError> {
+        let path = self.path.clone();
+        let Some(context) = self.write_atomic(&path)? else {
+            return Ok(());
+        };
+
+        // The file on disk already contains all pending changes, which therefore have to be cleared before re-opening
+        // the reader can fail. Otherwise saving again would apply the same changes twice
+        self.clear_saved_changes()?;
+
+        // The reader still references the replaced file and therefore has to be re-opened
+        let new_file_size = self.reopen_reader()?;
+        self.restore_reader_position(&context, new_file_size)
+    }
+
+    /// Writes the file including all pending changes to the given path without modifying the file it was
+    /// opened from. The file at the given path must not exist yet. Afterwards, the `EDFFile` points to the
+    /// newly written file, meaning all subsequent reads and saves are performed on the new file. This way
+    /// the original file always remains untouched. To write the edited file without switching over to it,
+    /// use `export_to` instead.
+    pub fn save_as<P: AsRef<Path>>(&mut self, path: P) -> Result<(), EDFError> {
+        let path = path.as_ref();
+        if path.exists() {
+            return Err(EDFError::FileAlreadyExists);
+        }
+
+        match self.write_atomic(path)? {
+            Some(context) => {
+                self.path = path.to_path_buf();
+                self.clear_saved_changes()?;
+                let new_file_size = self.reopen_reader()?;
+                self.restore_reader_position(&context, new_file_size)
+            }
+            None => {
+                // There are no pending changes, therefore the file on disk can simply be copied
+                let position = self.reader.stream_position().map_err(EDFError::FileReadError)?;
+                std::fs::copy(&self.path, path).map_err(EDFError::FileWriteError)?;
+                self.path = path.to_path_buf();
+                self.reopen_reader()?;
+                self.reader
+                    .seek(SeekFrom::Start(position))
+                    .map_err(EDFError::FileReadError)?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Writes the entire file including all pending changes to the given writer. Neither the file on disk nor
+    /// the pending changes are modified, therefore `save()` can still be called afterwards. Returns the amount
+    /// of bytes written.
+    pub fn export_to<W: Write>(&mut self, writer: &mut W) -> Result<u64, EDFError> {
+        self.inspect_save(|edf, context| match context {
+            Some(context) => edf.write_edited(context, writer, ProgressOperation::Export),
+            None => {
+                // There are no pending changes, therefore the file on disk can simply be copied
+                File::open(&edf.path)
+                    .and_then(|mut file| std::io::copy(&mut file, writer))
+                    .map_err(EDFError::FileWriteError)
+            }
+        })
+    }
+
+    /// Re-blocks all data-records of the file into data-records of the given duration. The samples count of every signal is
+    /// recomputed to keep its sampling rate, which requires the new duration to result in a whole number of samples for
+    /// every signal. Each continuous part of the file has to be a whole multiple of the new duration as well and the new
+    /// duration has to fit into the 8 characters of its header field, otherwise `EDFError::InvalidRecordDuration` is
+    /// returned. Annotations are moved to the data-record containing their onset and the Time-keeping annotation of every
+    /// data-record is regenerated for EDF+ and BDF+ files. The annotation signals are enlarged in case the annotations of a
+    /// new data-record would not fit. The re-blocked file is written into a temporary file, which then atomically replaces
+    /// the file. This requires all pending changes to be saved first.
+    pub fn reblock(&mut self, record_duration: f64) -> Result<(), EDFError> {
+        self.ensure_no_pending_changes()?;
+        let plan = self.plan_reblock(record_duration)?;
+
+        // Write the re-blocked data-records into a temporary file replacing the current file
+        self.replace_file(plan.header.clone(), |edf, header, writer| {
+            let written = edf.write_reblocked(plan, header, Some(ProgressOperation::Reblock), |_, _, record| {
+                writer.write_all(&record.serialize()?).map_err(EDFError::FileWriteError)
+            })?;
+
+            match written == header.record_count.unwrap_or(0) {
+                true => Ok(()),
+                false => Err(EDFError::InvalidRecordDuration),
+            }
+        })
+    }
+
+    /// Determines the header and the data-records of the file re-blocked to the given duration without writing anything.
+    /// See `reblock()` for details.
+    fn plan_reblock(&mut self, record_duration: f64) -> Result<ReblockPlan, EDFError> {
+        let initial_duration = self.header.record_duration;
+        if !record_duration.is_finite()
+            || record_duration <= 0.0
+            || initial_duration <= 0.0
+            || !fits_record_duration_field(record_duration)
+        {
+            return Err(EDFError::InvalidRecordDuration);
+        }
+
+        // Collect the onset and annotations of every data-record first, as those determine the layout of the new
+        // data-records as well as the size of the annotation signals
+        let filesize = self.reader.get_ref().metadata().map_err(EDFError::FileReadError)?.len();
+        let initial_record_count = self.get_initial_record_count(filesize);
+        let is_plus = self.header.specification.is_plus();
+        let mut runs = Vec::<ReblockRun>::new();
+        let mut annotations = Vec::new();
+        for idx in 0..initial_record_count {
+            self.check_cancelled()?;
+            let mut record = self.read_initial_record(idx)?;
+            let onset = record.get_start_offset();
+            if let Some(tals) = record.annotations.first_mut() {
+                tals.retain(|tal| !tal.is_time_keeping());
+            }
+
+            match runs.last_mut() {
+                Some(run) if self.header.is_continuous || (run.onset + run.initial_count as f64 * initial_duration - onset).abs() < 1e-6 => {
+                    run.initial_count += 1;
+                }
+                _ => runs.push(ReblockRun { onset, first_record: idx, initial_count: 1, count: 0 }),
+            }
+            annotations.push(record.annotations);
+        }
+
+        // Each continuous part of the file has to be split into a whole number of new data-records
+        let ratio = record_duration / initial_duration;
+        let mut record_onsets = Vec::new();
+        for run in runs.iter_mut() {
+            run.count = whole_number(run.initial_count as f64 / ratio).ok_or(EDFError::InvalidRecordDuration)?;
+            record_onsets.extend((0..run.count).map(|i| round_nanos(run.onset + i as f64 * record_duration)));
+        }
+        let record_count = record_onsets.len();
+
+        // Move every annotation list to the new data-record containing its onset
+        let mut record_annotations = vec![vec![Vec::new(); annotations.first().map(Vec::len).unwrap_or(0)]; record_count];
+        let mut first_record = 0;
+        for run in &runs {
+            for initial_annotations in &mut annotations[run.first_record..run.first_record + run.initial_count] {
+                for (signal_idx, tals) in initial_annotations.iter_mut().enumerate() {
+                    for tal in take_vec(tals) {
+                        let offset = ((tal.onset - run.onset) / record_duration + 1e-9).floor().max(0.0) as usize;
+                        record_annotations[first_record + offset.min(run.count - 1)][signal_idx].push(tal);
+                    }
+                }
+            }
+            first_record += run.count;
+        }
+
+        // Determine the new header with the samples count of every signal matching the new duration
+        let mut header = self.header.clone();
+        header.record_duration = record_duration;
+        header.record_count = Some(record_count);
+        let mut annotation_idx = 0;
+        for signal in header.signals.iter_mut() {
+            if !signal.is_annotation() {
+                signal.samples_count = whole_number(signal.samples_count as f64 * ratio).ok_or(EDFError::InvalidRecordDuration)?;
+                continue;
+            }
+
+            // Annotation signals keep their size relative to the duration, unless the moved annotations require more space
+            let char_bytes = signal.annotation_char_bytes();
+            let required_bytes = record_annotations
+                .iter()
+                .zip(&record_onsets)
+                .map(|(tals, onset)| {
+                    let time_keeping_bytes = match annotation_idx == 0 && is_plus {
+                        true => AnnotationList::new_time_keeping(*onset).serialize().len(),
+                        false => 0,
+                    };
+                    time_keeping_bytes + tals[annotation_idx].iter().map(|tal| tal.serialize().len()).sum::<usize>()
+                })
+                .max()
+                .unwrap_or(0);
+            signal.samples_count = required_bytes.div_ceil(char_bytes).max((signal.samples_count as f64 * ratio).ceil() as usize);
+            annotation_idx += 1;
+        }
+
+        Ok(ReblockPlan {
+            header,
+            runs,
+            ratio,
+            initial_record_count,
+            record_onsets,
+            record_annotations,
+        })
+    }
+
+    /// Reads the data-records stored on disk and re-blocks them according to the given plan. Every re-blocked data-record is
+    /// created using the given header and passed to the given function along with its index. The progress of the given
+    /// operation is reported based on the data-records read, while no progress is reported in case there is no operation
+    /// (e.g. as the given function reports the progress itself). Returns the amount of re-blocked data-records.
+    fn write_reblocked<F>(
+        &mut self,
+        plan: ReblockPlan,
+        header: &EDFHeader,
+        operation: Option<ProgressOperation>,
+        mut write: F
+    ) -> Result<usize, EDFError>
+    where
+        F: FnMut(&mut Self, usize, Record) -> Result<(), EDFError>,
+    {
+        let is_plus = header.specification.is_plus();
+        let mut buffers = Vec::new();
+        let mut record_iter = plan.record_annotations.into_iter();
+        let mut record_idx = 0;
+        for run in &plan.runs {
+            let mut written = 0;
+            for initial_idx in run.first_record..run.first_record + run.initial_count {
+                self.check_cancelled()?;
+                let record = self.read_initial_record(initial_idx)?;
+                if buffers.is_empty() {
+                    buffers = record.raw_signal_samples.iter().map(|samples| samples.range(0..0)).collect();
+                }
+                for (buffer, samples) in buffers.iter_mut().zip(&record.raw_signal_samples) {
+                    buffer.extend(samples)?;
+                }
+
+                // Write all new data-records which are entirely contained in the data-records read so far
+                let completed = (((initial_idx - run.first_record + 1) as f64 / plan.ratio + 1e-6).floor() as usize).min(run.count);
+                while written < completed {
+                    let mut record = header.create_record();
+                    for (samples, buffer) in record.raw_signal_samples.iter_mut().zip(buffers.iter_mut()) {
+                        *samples = buffer.take_front(samples.len());
+                    }
+                    record.annotations = record_iter.next().unwrap_or_default();
+                    if is_plus && !record.annotations.is_empty() {
+                        record.set_time_keeping(plan.record_onsets[record_idx])?;
+                    }
+
+                    write(self, record_idx, record)?;
+                    written += 1;
+                    record_idx += 1;
+                }
+
+                if let Some(operation) = &operation {
+                    self.report_progress(operation.clone(), initial_idx as u64 + 1, plan.initial_record_count as u64);
+                }
+            }
+        }
+
+        Ok(record_idx)
+    }
+
+    /// Turns a discontinuous EDF+ (or BDF+) file into a continuous one by inserting data-records filling every gap between
+    /// two consecutive data-records. Gaps are detected using the Time-keeping annotations of the data-records. The samples
+    /// of the added data-records are set to the configured fill value and every gap can be marked with an annotation. Gaps
+    /// which are not a whole multiple of the data-record duration are treated according to the configured strategy. The
+    /// filled file is written into a temporary file, which then atomically replaces the file. This requires all pending
+    /// changes to be saved first. Continuous files are left untouched.
+    pub fn fill_gaps(&mut self, options: &GapFillOptions) -> Result<(), EDFError> {
+        self.ensure_no_pending_changes()?;
+        if !self.header.specification.is_plus() || self.header.is_continuous {
+            return Ok(());
+        }
+        if self.header.record_duration <= 0.0 {
+            return Err(EDFError::InvalidRecordDuration);
+        }
+        let Some(annotation_idx) = self.header.signals.iter().position(|signal| signal.is_annotation()) else {
+            return Err(EDFError::MissingAnnotations);
+        };
+
+        // Gaps are determined from the end of the preceding data-record, therefore the order of the data-records has to
+        // be checked before determining a data-record duration for re-blocking
+        let mut onsets = self.read_initial_onsets()?;
+        let initial_record_count = onsets.len();
+        let mut record_duration = self.header.record_duration;
+        if onsets.windows(2).any(|onsets| onsets[1] - onsets[0] - record_duration < -1e-6) {
+            return Err(EDFError::RecordsOutOfOrder);
+        }
+
+        // Determine the layout of the file re-blocked to a data-record duration all gaps are a whole multiple of. The file
+        // is re-blocked while filling the gaps, as nothing may be written before all gaps are known to be fillable
+        let mut reblock_plan = None;
+        if options.strategy == GapStrategy::Reblock {
+            let to_ns = |seconds: f64| (seconds * 1_000_000_000.0).round().max(0.0) as u64;
+            let gaps_ns = onsets
+                .windows(2)
+                .map(|onsets| to_ns(onsets[1] - onsets[0] - record_duration))
+                .filter(|gap| *gap > 0)
+                .collect::<Vec<_>>();
+            let duration_ns = common_duration_ns(to_ns(record_duration), &gaps_ns);
+            if duration_ns != to_ns(record_duration) {
+                let plan = self.plan_reblock(duration_ns as f64 / 1_000_000_000.0)?;
+                onsets = plan.record_onsets.clone();
+                record_duration = plan.header.record_duration;
+                reblock_plan = Some(plan);
+            }
+        }
+
+        // Determine the amount of data-records required to fill every gap and the shift of every data-record
+        // caused by padded gaps
+        let mut gaps = Vec::new();
+        let mut shifts = vec![0.0; onsets.len()];
+        for idx in 1..onsets.len() {
+            shifts[idx] = shifts[idx - 1];
+            let gap_onset = onsets[idx - 1] + shifts[idx - 1] + record_duration;
+            let gap = onsets[idx] + shifts[idx] - gap_onset;
+            if gap < -1e-6 {
+                return Err(EDFError::RecordsOutOfOrder);
+            }
+            if gap <= 1e-6 {
+                continue;
+            }
+
+            let count = match whole_number(gap / record_duration) {
+                Some(count) => count,
+                None if options.strategy == GapStrategy::Pad => {
+                    let count = (gap / record_duration).ceil() as usize;
+                    shifts[idx] += count as f64 * record_duration - gap;
+                    count
+                }
+                None => return Err(EDFError::UnalignedGap),
+            };
+            gaps.push(FilledGap { record: idx, onset: gap_onset, count });
+        }
+
+        // Files without any gaps only have to be marked as continuous, which only requires the header to be written
+        if gaps.is_empty() {
+            self.header.is_continuous = true;
+            return self.save();
+        }
+
+        // Enlarge the first annotation signal in case the annotations of the added data-records would not fit
+        let mut header = match &reblock_plan {
+            Some(plan) => plan.header.clone(),
+            None => self.header.clone(),
+        };
+        header.is_continuous = true;
+        header.record_count = Some(onsets.len() + gaps.iter().map(|gap| gap.count).sum::<usize>());
+        let gap_annotations = gaps
+            .iter()
+            .map(|gap| gap_annotations(gap, record_duration, options.annotation.as_deref()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let signal = &mut header.signals[annotation_idx];
+        let required_bytes = gap_annotations
+            .iter()
+            .map(|tals| tals.iter().map(|tal| tal.serialize().len()).sum::<usize>())
+            .max()
+            .unwrap_or(0);
+        signal.samples_count = signal.samples_count.max(required_bytes.div_ceil(signal.annotation_char_bytes()));
+        let annotation_samples_count = signal.samples_count;
+
+        self.replace_file(header, |edf, header, writer| {
+            let mut gaps = gaps.iter().zip(gap_annotations).peekable();
+            let mut write = |_: &mut Self, idx: usize, mut record: Record| {
+                // Write all data-records filling the gap preceding the current data-record
+                if let Some((gap, annotations)) = gaps.next_if(|(gap, _)| gap.record == idx) {
+                    let mut annotations = Some(annotations);
+                    for i in 0..gap.count {
+                        let mut record = header.create_record();
+                        let signals = header.signals.iter().filter(|signal| !signal.is_annotation());
+                        for (samples, signal) in record.raw_signal_samples.iter_mut().zip(signals) {
+                            let value = options.fill_value.digital_value(signal);
+                            *samples = match samples {
+                                Samples::Values16Bit(samples) => Samples::Values16Bit(vec![value as i16; samples.len()]),
+                                Samples::Values24Bit(samples) => Samples::Values24Bit(vec![value; samples.len()]),
+                            };
+                        }
+                        record.annotations[0] = match annotations.take() {
+                            Some(tals) => tals,
+                            None => vec![AnnotationList::new_time_keeping(round_nanos(gap.onset + i as f64 * record_duration))],
+                        };
+                        writer.write_all(&record.serialize()?).map_err(EDFError::FileWriteError)?;
+                    }
+                }
+
+                if shifts[idx] != 0.0 {
+                    record.shift_onsets(shifts[idx]);
+                }
+                record.update_samples_count(annotation_idx, annotation_samples_count)?;
+                writer.write_all(&record.serialize()?).map_err(EDFError::FileWriteError)
+            };
+
+            match reblock_plan {
+                Some(plan) => {
+                    edf.write_reblocked(plan, header, Some(ProgressOperation::FillGaps), write)?;
+                }
+                None => {
+                    for idx in 0..initial_record_count {
+                        edf.check_cancelled()?;
+                        let record = edf.read_initial_record(idx)?;
+                        write(edf, idx, record)?;
+                        edf.report_progress(ProgressOperation::FillGaps, idx as u64 + 1, initial_record_count as u64);
+                    }
+                }
+            }
+
+            Ok(())
+        })
+    }
+
+    /// Splits the file into multiple parts at the boundaries described by the given mode. Every part is written into a new
+    /// file next to the given path, which is named after the given path with the number of the part appended (see `SplitMode`
+    /// for details). The start date and time of every part (including the start date of the `RecordingId`) is moved to the
+    /// start of its first data-record and all Time-keeping annotations are rebased accordingly. Every other annotation is
+    /// moved into the first annotation signal of the part its onset falls into. As the start time of a header only has a
+    /// resolution of seconds, parts of EDF+ and BDF+ files may start with a sub-second Time-keeping onset, while parts of
+    /// plain files not starting at a whole second result in `EDFError::InvalidStartTime`. This requires all pending changes
+    /// to be saved first. Returns the created parts in chronological order.
+    pub fn split<P: AsRef<Path>>(&mut self, mode: &SplitMode, path: P) -> Result<Vec<EDFFile>, EDFError> {
+        self.ensure_no_pending_changes()?;
+        let position = self.reader.stream_position().map_err(EDFError::FileReadError)?;
+        let timing = self.read_initial_timing()?;
+        let record_count = timing.onsets.len();
+
+        let starts = mode.part_starts(&timing.onsets, &timing.annotations, self.header.record_duration)?;
+        let paths = (1..=starts.len()).map(|part| part_path(path.as_ref(), part)).collect::<Vec<_>>();
+        if paths.iter().any(|path| path.exists()) {
+            return Err(EDFError::FileAlreadyExists);
+        }
+
+        // Annotations stay within their data-record in case it is part of the part their onset falls into, otherwise
+        // they are moved into the first data-record of that part
+        let part_of = |idx: usize| starts.partition_point(|start| *start <= idx).max(1) - 1;
+        let mut moved = vec![Vec::new(); record_count];
+        for (idx, tal) in timing.annotations.iter().cloned() {
+            let part = starts.partition_point(|start| timing.onsets[*start] <= tal.onset + 1e-9).max(1) - 1;
+            let target = if part_of(idx) == part { idx } else { starts[part] };
+            moved[target].push(tal);
+        }
+
+        let mut parts = Vec::with_capacity(starts.len());
+        let mut processed = 0;
+        for (part, part_path) in paths.iter().enumerate() {
+            let records = (starts[part]..starts.get(part + 1).copied().unwrap_or(record_count)).collect::<Vec<_>>();
+            let result = Self::part_header(&self.header, &timing, &records, &moved).and_then(|(header, offset)| {
+                self.create_file(part_path, header, |edf, header, writer| {
+                    edf.write_part(header, writer, &records, offset, &mut moved, |edf| {
+                        processed += 1;
+                        edf.report_progress(ProgressOperation::Split, processed, record_count as u64);
+                    })
+                })
+            });
+            match result {
+                Ok(edf) => parts.push(edf),
+                Err(err) => {
+                    self.reader.seek(SeekFrom::Start(position)).map_err(EDFError::FileReadError)?;
+                    return Err(err);
+                }
+            }
+        }
+
+        self.reader.seek(SeekFrom::Start(position)).map_err(EDFError::FileReadError)?;
+        Ok(parts)
+    }
+
+    /// Crops the recording to the window between the given start and end time, both relative to the start of the recording.
+    /// Data-records entirely outside the window are dropped, while the data-records containing a boundary are trimmed to it.
+    /// As every signal has to be cut at a sample boundary, the start is moved back and the end is moved forward to the nearest
+    /// point in time at which every signal starts a new sample. In case a boundary is not aligned with a data-record, the file
+    /// is re-blocked to the largest data-record duration both boundaries are aligned with while cropping, which fails with
+    /// `EDFError::InvalidRecordDuration` in case that duration can not be represented (see `reblock`). The start date
+    /// and time of the header are moved to the start of the window and all annotations are rebased accordingly, where
+    /// annotations outside the window are removed. As the start time of a header only has a resolution of seconds, EDF+ and
+    /// BDF+ files may start with a sub-second Time-keeping onset, while plain files cropped to a window not starting at a whole
+    /// second result in `EDFError::InvalidStartTime`. The cropped file is written into a temporary file, which then atomically
+    /// replaces the file. This requires all pending changes to be saved first.
+    pub fn crop(&mut self, start: Duration, end: Duration) -> Result<(), EDFError> {
+        self.ensure_no_pending_changes()?;
+        if start >= end {
+            return Err(EDFError::InvalidReadRange);
+        }
+        let record_duration_ns = (self.header.record_duration * 1_000_000_000.0).round() as u64;
+        if record_duration_ns == 0 {
+            return Err(EDFError::InvalidRecordDuration);
+        }
+
+        // Boundaries can only be placed at points in time at which every signal starts a new sample
+        let sample_periods = self
+            .header
+            .signals
+            .iter()
+            .filter(|signal| !signal.is_annotation())
+            .fold(record_duration_ns as usize, |periods, signal| gcd(periods, signal.samples_count)) as u64;
+        let period_ns = record_duration_ns / sample_periods;
+        let to_ns = |seconds: f64| (seconds * 1_000_000_000.0).round().max(0.0) as u64;
+        let onsets = self.read_initial_onsets()?.into_iter().map(to_ns).collect::<Vec<_>>();
+        let (start_ns, end_ns) = (start.as_nanos() as u64, end.as_nanos() as u64);
+        let start_periods = onsets
+            .iter()
+            .find(|onset| **onset <= start_ns && start_ns < **onset + record_duration_ns)
+            .map(|onset| (onset, (start_ns - onset) / period_ns));
+        let end_periods = onsets
+            .iter()
+            .find(|onset| **onset < end_ns && end_ns <= **onset + record_duration_ns)
+            .map(|onset| (onset, (end_ns - onset).div_ceil(period_ns)));
+        let start_ns = start_periods.map(|(onset, periods)| onset + periods * period_ns).unwrap_or(start_ns);
+        let end_ns = end_periods.map(|(onset, periods)| onset + periods * period_ns).unwrap_or(end_ns);
+
+        // Make sure the cropped file can be represented before modifying anything
+        let Some(first_onset) = onsets
+            .iter()
+            .filter(|onset| **onset < end_ns && **onset + record_duration_ns > start_ns)
+            .map(|onset| (*onset).max(start_ns))
+            .min()
+        else {
+            return Err(EDFError::InvalidReadRange);
+        };
+        if !self.header.specification.is_plus() && first_onset % 1_000_000_000 != 0 {
+            return Err(EDFError::InvalidStartTime);
+        }
+
+        // The file has to be re-blocked in case a boundary is located within a data-record, which is done while cropping
+        // the file. The layout of the re-blocked data-records is determined first to ensure it can be represented
+        let periods = [start_periods, end_periods]
+            .into_iter()
+            .flatten()
+            .fold(sample_periods, |periods, (_, boundary)| gcd(periods as usize, boundary as usize) as u64);
+        let reblock_plan = match periods != sample_periods {
+            true => Some(self.plan_reblock((periods * period_ns) as f64 / 1_000_000_000.0)?),
+            false => None,
+        };
+
+        // Keep all data-records within the window together with the annotations with an onset within the window
+        let (timing, initial_header) = match &reblock_plan {
+            Some(plan) => {
+                let is_plus = plan.header.specification.is_plus();
+                let timing = RecordTiming {
+                    onsets: plan.record_onsets.clone(),
+                    time_keeping: plan
+                        .record_onsets
+                        .iter()
+                        .map(|onset| is_plus.then(|| AnnotationList::new_time_keeping(*onset)))
+                        .collect(),
+                    annotations: plan
+                        .record_annotations
+                        .iter()
+                        .enumerate()
+                        .flat_map(|(idx, tals)| tals.iter().flatten().map(move |tal| (idx, tal.clone())))
+                        .collect(),
+                };
+                (timing, &plan.header)
+            }
+            None => (self.read_initial_timing()?, &self.header),
+        };
+        let record_duration_ns = (initial_header.record_duration * 1_000_000_000.0).round() as u64;
+        let records = (0..timing.onsets.len())
+            .filter(|idx| {
+                let onset = to_ns(timing.onsets[*idx]);
+                onset >= start_ns && onset + record_duration_ns <= end_ns
+            })
+            .collect::<Vec<_>>();
+        let Some(first_record) = records.first().copied() else {
+            return Err(EDFError::InvalidReadRange);
+        };
+
+        // Annotations stored in a dropped data-record are moved into the last data-record starting before their onset
+        let mut moved = vec![Vec::new(); timing.onsets.len()];
+        for (idx, tal) in timing.annotations.iter().cloned() {
+            let onset = to_ns(tal.onset);
+            if onset < start_ns || onset > end_ns {
+                continue;
+            }
+            let target = match records.contains(&idx) {
+                true => idx,
+                false => records
+                    .iter()
+                    .rev()
+                    .find(|idx| to_ns(timing.onsets[**idx]) <= onset)
+                    .copied()
+                    .unwrap_or(first_record),
+            };
+            moved[target].push(tal);
+        }
+
+        let (header, offset) = Self::part_header(initial_header, &timing, &records, &moved)?;
+        let record_count = records.len() as u64;
+        self.replace_file(header, |edf, header, writer| {
+            let mut processed = 0;
+            let Some(plan) = reblock_plan else {
+                return edf.write_part(header, writer, &records, offset, &mut moved, |edf| {
+                    processed += 1;
+                    edf.report_progress(ProgressOperation::Crop, processed, record_count);
+                });
+            };
+
+            // Only the re-blocked data-records within the window are written, which the progress is reported against
+            edf.write_reblocked(plan, header, None, |edf, idx, mut record| {
+                if records.binary_search(&idx).is_err() {
+                    return Ok(());
+                }
+                Self::patch_part_record(header, &mut record, &mut moved[idx], offset)?;
+                writer.write_all(&record.serialize()?).map_err(EDFError::FileWriteError)?;
+                processed += 1;
+                edf.report_progress(ProgressOperation::Crop, processed, record_count);
+                Ok(())
+            })?;
+
+            Ok(())
+        })
+    }
+
+    /// Writes the selected signals together with all annotation signals into a new file at the given path, which must not
+    /// exist yet. The signals keep their order within the file, regardless of the order of the selectors, while all other
+    /// header fields are carried over. The data-records are copied directly from disk without decoding their samples,
+    /// which requires all pending changes to be saved first. A selector not matching any signal results in
+    /// `EDFError::ItemNotFound`. Returns the created file.
+    pub fn extract_signals<P: AsRef<Path>>(&mut self, selectors: &[SignalSelector], path: P) -> Result<EDFFile, EDFError> {
+        self.ensure_no_pending_changes()?;
+        let signals = &self.header.signals;
+        if selectors.iter().any(|selector| !signals.iter().enumerate().any(|(idx, signal)| selector.matches(idx, signal))) {
+            return Err(EDFError::ItemNotFound);
+        }
+
+        // Determine the byte range of every signal within a data-record which has to be copied
+        let sample_bytes = self.header.specification.sample_bytes();
+        let mut header = self.header.clone();
+        let mut ranges = Vec::new();
+        let mut signal_offset = 0;
+        header.signals.clear();
+        for (idx, signal) in signals.iter().enumerate() {
+            let signal_bytes = signal.samples_count * sample_bytes;
+            if signal.is_annotation() || selectors.iter().any(|selector| selector.matches(idx, signal)) {
+                header.signals.push(signal.clone());
+                ranges.push(signal_offset..signal_offset + signal_bytes);
+            }
+            signal_offset += signal_bytes;
+        }
+
+        let position = self.reader.stream_position().map_err(EDFError::FileReadError)?;
+        let filesize = self.reader.get_ref().metadata().map_err(EDFError::FileReadError)?.len();
+        let record_count = self.get_initial_record_count(filesize);
+        header.record_count = header.record_count.map(|_| record_count);
+        let result = self.create_file(path.as_ref(), header, |edf, _, writer| {
+            let mut buffer = vec![0; edf.header.get_initial_record_bytes()];
+            edf.reader
+                .seek(SeekFrom::Start(edf.header.header_bytes as u64))
+                .map_err(EDFError::FileReadError)?;
+            for idx in 0..record_count {
+                edf.check_cancelled()?;
+                edf.reader.read_exact(&mut buffer).map_err(EDFError::FileReadError)?;
+                for range in &ranges {
+                    writer.write_all(&buffer[range.clone()]).map_err(EDFError::FileWriteError)?;
+                }
+                edf.report_progress(ProgressOperation::Extract, idx as u64 + 1, record_count as u64);
+            }
+
+            Ok(())
+        });
+
+        self.reader.seek(SeekFrom::Start(position)).map_err(EDFError::FileReadError)?;
+        result
+    }
+
+    /// Removes the protected health information of the file according to the given options. The identifying subfields of
+    /// the patient and recording identification are replaced, the start date is shifted and all annotation texts are scanned
+    /// for the configured patterns to redact. Annotation signals are enlarged in case a redacted annotation would not fit
+    /// anymore. The anonymized file is written into a temporary file, which then atomically replaces the file. This requires
+    /// all pending changes to be saved first. Pseudonyms and consistent date shifts require a salt, otherwise
+    /// `EDFError::MissingSalt` is returned. Returns the audit report of every change made.
+    pub fn anonymize(&mut self, options: &AnonymizeOptions) -> Result<AnonymizationReport, EDFError> {
+        self.ensure_no_pending_changes()?;
+        if options.salt.is_empty() && options.requires_salt() {
+            return Err(EDFError::MissingSalt);
+        }
+        let mut report = AnonymizationReport::default();
+        let mut header = self.header.clone();
+        options.anonymize_header(&mut header, &mut report);
+
+        // Redact the annotations of all data-records first, as those determine the size of the annotation signals
+        let filesize = self.reader.get_ref().metadata().map_err(EDFError::FileReadError)?.len();
+        let record_count = self.get_initial_record_count(filesize);
+        let annotation_signals = header
+            .signals
+            .iter()
+            .enumerate()
+            .filter(|(_, signal)| signal.is_annotation())
+            .map(|(idx, _)| idx)
+            .collect::<Vec<_>>();
+        let mut redacted = Vec::new();
+        if !options.redact_patterns.is_empty() && !annotation_signals.is_empty() {
+            for idx in 0..record_count {
+                self.check_cancelled()?;
+                let mut record = self.read_initial_record(idx)?;
+                let mut changed = false;
+                for tals in record.annotations.iter_mut() {
+                    changed |= options.redact_annotations(idx, tals, &mut report);
+                }
+                if changed {
+                    for (signal_idx, tals) in annotation_signals.iter().zip(&record.annotations) {
+                        let signal = &mut header.signals[*signal_idx];
+                        let required_bytes = tals.iter().map(|tal| tal.serialize().len()).sum::<usize>();
+                        signal.samples_count = signal.samples_count.max(required_bytes.div_ceil(signal.annotation_char_bytes()));
+                    }
+                    redacted.push((idx, record.annotations));
+                }
+            }
+        }
+
+        let mut redacted = redacted.into_iter().peekable();
+        self.replace_file(header, |edf, header, writer| {
+            for idx in 0..record_count {
+                edf.check_cancelled()?;
+                let mut record = edf.read_initial_record(idx)?;
+                if let Some((_, annotations)) = redacted.next_if(|(record_idx, _)| *record_idx == idx) {
+                    record.annotations = annotations;
+                }
+                for signal_idx in &annotation_signals {
+                    record.update_samples_count(*signal_idx, header.signals[*signal_idx].samples_count)?;
+                }
+                writer.write_all(&record.serialize()?).map_err(EDFError::FileWriteError)?;
+                edf.report_progress(ProgressOperation::Anonymize, idx as u64 + 1, record_count as u64);
+            }
+
+            Ok(())
+        })?;
+
+        Ok(report)
+    }
+
+    /// Repairs files which were not written completely, e.g. because the recording crashed. The size of the header is fixed
+    /// to match the amount of signals, the amount of data-records is inferred from the size of the file and a data-record
+    /// which was only partially written is handled according to the given strategy. For EDF+ and BDF+ files, the reserved
+    /// field is fixed, missing Time-keeping annotations are regenerated from the onset of the preceding data-record and
+    /// files with gaps are marked as discontinuous. The repaired file is written into a temporary file, which then atomically
+    /// replaces the file. This requires all pending changes to be saved first. Returns the report of every fix made, where
+    /// the file is left untouched in case it is already intact.
+    pub fn repair(&mut self, partial_record: PartialRecordStrategy) -> Result<RepairReport, EDFError> {
+        self.ensure_no_pending_changes()?;
+        let mut report = RepairReport::default();
+
+        // The signal headers are located at fixed positions, therefore the data-records always follow right after them. The
+        // header of the file is only replaced once the repaired file was written
+        let header_bytes = self.header.calculate_header_bytes();
+        if self.header.header_bytes != header_bytes {
+            report.fixes.push(RepairFix::HeaderBytes { stored: self.header.header_bytes, actual: header_bytes });
+        }
+        let reserved = self.header.serialize_reserved();
+        if self.header.reserved.trim_ascii_end() != reserved {
+            report.fixes.push(RepairFix::Reserved { stored: self.header.reserved.trim_ascii_end().to_string(), actual: reserved.to_string() });
+        }
+
+        // Infer the amount of data-records from the size of the file
+        let filesize = self.reader.get_ref().metadata().map_err(EDFError::FileReadError)?.len();
+        let record_bytes = self.header.data_record_bytes() as u64;
+        let data_bytes = filesize.saturating_sub(header_bytes as u64);
+        let complete = data_bytes.checked_div(record_bytes).unwrap_or(0) as usize;
+        let partial_bytes = data_bytes.checked_rem(record_bytes).unwrap_or(0) as usize;
+        let record_count = match partial_record {
+            PartialRecordStrategy::Pad if partial_bytes > 0 => complete + 1,
+            _ => complete,
+        };
+        if partial_bytes > 0 {
+            report.fixes.push(RepairFix::PartialRecord { record: complete, bytes: partial_bytes, strategy: partial_record.clone() });
+        }
+        if self.header.record_count != Some(record_count) {
+            report.fixes.push(RepairFix::RecordCount { stored: self.header.record_count, actual: record_count });
+        }
+
+        // Regenerate missing Time-keeping annotations and detect gaps between the data-records
+        let mut header = self.header.clone();
+        header.record_count = Some(record_count);
+        let mut time_keeping = Vec::new();
+        let annotation_idx = header.signals.iter().position(|signal| signal.is_annotation());
+        if header.specification.is_plus() {
+            let Some(annotation_idx) = annotation_idx else {
+                return Err(EDFError::MissingAnnotations);
+            };
+
+            let mut previous_onset = None::<f64>;
+            let mut has_gaps = false;
+            let mut required_bytes = 0;
+            for idx in 0..record_count {
+                self.check_cancelled()?;
+                let record = self.read_repaired_record(idx, header_bytes)?;
+                let onset = match record.annotations[0].iter().find(|tal| tal.is_time_keeping()) {
+                    Some(tal) => tal.onset,
+                    None => {
+                        let onset = previous_onset.map(|onset| round_nanos(onset + header.record_duration)).unwrap_or(0.0);
+                        let annotation_bytes = record.annotations[0].iter().map(|tal| tal.serialize().len()).sum::<usize>();
+                        required_bytes = required_bytes.max(AnnotationList::new_time_keeping(onset).serialize().len() + annotation_bytes);
+                        report.fixes.push(RepairFix::TimeKeeping { record: idx, onset });
+                        time_keeping.push((idx, onset));
+                        onset
+                    }
+                };
+                if let Some(previous_onset) = previous_onset {
+                    has_gaps |= (onset - previous_onset - header.record_duration).abs() > 1e-6;
+                }
+                previous_onset = Some(onset);
+            }
+
+            if header.is_continuous && has_gaps {
+                header.is_continuous = false;
+                report.fixes.push(RepairFix::Discontinuous);
+            }
+            let signal = &mut header.signals[annotation_idx];
+            signal.samples_count = signal.samples_count.max(required_bytes.div_ceil(signal.annotation_char_bytes()));
+        }
+
+        if report.is_empty() {
+            return Ok(report);
+        }
+
+        let mut time_keeping = time_keeping.into_iter().peekable();
+        self.replace_file(header, |edf, header, writer| {
+            for idx in 0..record_count {
+                edf.check_cancelled()?;
+                let mut record = edf.read_repaired_record(idx, header_bytes)?;
+                if let Some((_, onset)) = time_keeping.next_if(|(record_idx, _)| *record_idx == idx) {
+                    record.set_time_keeping(onset)?;
+                }
+                if let Some(annotation_idx) = annotation_idx {
+                    record.update_samples_count(annotation_idx, header.signals[annotation_idx].samples_count)?;
+                }
+                writer.write_all(&record.serialize()?).map_err(EDFError::FileWriteError)?;
+                edf.report_progress(ProgressOperation::Repair, idx as u64 + 1, record_count as u64);
+            }
+
+            Ok(())
+        })?;
+
+        Ok(report)
+    }
+
+    /// Reads the data-record at the given index as it is stored on disk, where the data-records start after the given amount
+    /// of header bytes. In case the data-record is only partially written, its missing bytes are filled with zeros while all
+    /// annotation signals which were not written completely are cleared.
+    fn read_repaired_record(&mut self, index: usize, header_bytes: usize) -> Result<Record, EDFError> {
+        let record_bytes = self.header.data_record_bytes();
+        let offset = header_bytes as u64 + index as u64 * record_bytes as u64;
+        self.reader.seek(SeekFrom::Start(offset)).map_err(EDFError::FileReadError)?;
+        let mut buffer = Vec::with_capacity(record_bytes);
+        (&mut self.reader)
+            .take(record_bytes as u64)
+            .read_to_end(&mut buffer)
+            .map_err(EDFError::FileReadError)?;
+
+        let written = buffer.len();
+        buffer.resize(record_bytes, 0);
+        let sample_bytes = self.header.specification.sample_bytes();
+        let mut signal_offset = 0;
+        for signal in &self.header.signals {
+            let signal_end = signal_offset + signal.samples_count * sample_bytes;
+            if signal.is_annotation() && signal_end > written {
+                buffer[signal_offset..signal_end].fill(0);
+            }
+            signal_offset = signal_end;
+        }
+
+        Self::read_record_data(
+            &mut Cursor::new(buffer),
+            index as u64,
+            &self.header.signals,
+            self.header.record_duration,
+            &self.header.specification
+        )
+    }
+
+    /// Collects the onset and the Time-keeping annotation of every data-record stored on disk as well as all other
+    /// annotations together with the index of the data-record they are stored in
+    fn read_initial_timing(&mut self) -> Result<RecordTiming, EDFError> {
+        let filesize = self.reader.get_ref().metadata().map_err(EDFError::FileReadError)?.len();
+        let record_count = self.get_initial_record_count(filesize);
+        let mut timing = RecordTiming {
+            onsets: Vec::with_capacity(record_count),
+            time_keeping: Vec::with_capacity(record_count),
+            annotations: Vec::new(),
+        };
+        for idx in 0..record_count {
+            self.check_cancelled()?;
+            let record = self.read_initial_record(idx)?;
+            timing.onsets.push(record.get_start_offset());
+            timing
+                .time_keeping
+                .push(record.annotations.first().and_then(|tals| tals.iter().find(|tal| tal.is_time_keeping())).cloned());
+            timing.annotations.extend(
+                record.annotations
+                    .into_iter()
+                    .flatten()
+                    .filter(|tal| !tal.is_time_keeping())
+                    .map(|tal| (idx, tal)),
+            );
+        }
+
+        Ok(timing)
+    }
+
+    /// Determines the header of a part of the file with the given header consisting of the given data-records, where the
+    /// given annotations are stored in the first annotation signal of the data-record at the same index. The start of the
+    /// header is moved to the whole second the first data-record starts in. Returns the header and the amount of seconds
+    /// the start was moved by.
+    fn part_header(
+        header: &EDFHeader,
+        timing: &RecordTiming,
+        records: &[usize],
+        moved: &[Vec<AnnotationList>]
+    ) -> Result<(EDFHeader, f64), EDFError> {
+        let first_onset = records.first().map(|idx| timing.onsets[*idx]).unwrap_or(0.0);
+        let offset = match header.specification.is_plus() {
+            true => (first_onset + 1e-9).floor(),
+            false => whole_number(first_onset).ok_or(EDFError::InvalidStartTime)? as f64,
+        };
+
+        // Move the start of the part to its first data-record
+        let initial_header = header;
+        let mut header = initial_header.clone();
+        let start = initial_header.start_date().and_time(initial_header.start_time) + TimeDelta::seconds(offset as i64);
+        header.with_start_date(start.date()).with_start_time(start.time());
+        if header.recording_id.startdate.is_some() {
+            header.recording_id.startdate = Some(start.date());
+        }
+        header.record_count = Some(records.len());
+
+        // Enlarge the first annotation signal in case the moved annotations would not fit
+        if let Some(signal) = header.signals.iter_mut().find(|signal| signal.is_annotation()) {
+            let required_bytes = records
+                .iter()
+                .map(|idx| {
+                    timing.time_keeping[*idx]
+                        .iter()
+                        .chain(&moved[*idx])
+                        .map(|tal| AnnotationList { onset: round_nanos(tal.onset - offset), ..tal.clone() }.serialize().len())
+                        .sum::<usize>()
+                })
+                .max()
+                .unwrap_or(0);
+            signal.samples_count = signal.samples_count.max(required_bytes.div_ceil(signal.annotation_char_bytes()));
+        }
+
+        Ok((header, offset))
+    }
+
+    /// Writes the given data-records stored on disk using the layout of the given header. All annotations except for the
+    /// Time-keeping annotation are replaced by the given annotations of the data-record, which are taken from `moved`.
+    /// All onsets are shifted back by the given offset. The given function is called after every written data-record.
+    fn write_part<F>(
+        &mut self,
+        header: &EDFHeader,
+        writer: &mut BufWriter<File>,
+        records: &[usize],
+        offset: f64,
+        moved: &mut [Vec<AnnotationList>],
+        mut on_written: F,
+    ) -> Result<(), EDFError>
+    where
+        F: FnMut(&mut Self),
+    {
+        for idx in records {
+            self.check_cancelled()?;
+            let mut record = self.read_initial_record(*idx)?;
+            Self::patch_part_record(header, &mut record, &mut moved[*idx], offset)?;
+            writer.write_all(&record.serialize()?).map_err(EDFError::FileWriteError)?;
+            on_written(self);
+        }
+
+        Ok(())
+    }
+
+    /// Replaces all annotations except for the Time-keeping annotation of the given data-record by the given annotations
+    /// and shifts all onsets back by the given offset to match the layout of the given header of a part of the file
+    fn patch_part_record(
+        header: &EDFHeader,
+        record: &mut Record,
+        moved: &mut Vec<AnnotationList>,
+        offset: f64
+    ) -> Result<(), EDFError> {
+        let Some((annotation_idx, signal)) = header.signals.iter().enumerate().find(|(_, signal)| signal.is_annotation()) else {
+            return Ok(());
+        };
+
+        record.annotations.iter_mut().for_each(|tals| tals.retain(|tal| tal.is_time_keeping()));
+        record.annotations[0].append(moved);
+        record.shift_onsets(-offset);
+        record.update_samples_count(annotation_idx, signal.samples_count)
+    }
+
+    /// Writes a new version of the file using the given function into a temporary file next to the file, which then
+    /// atomically replaces the file. The given header is written first and is the header of the file afterwards, while
+    /// the function has to write all data-records. In case anything fails, the file remains untouched.
+    fn replace_file<F>(&mut self, mut header: EDFHeader, f: F) -> Result<(), EDFError>
+    where
+        F: FnOnce(&mut Self, &EDFHeader, &mut BufWriter<File>) -> Result<(), EDFError>,
+    {
+        header.signal_count = header.signals.len();
+        header.header_bytes = header.calculate_header_bytes();
+
+        let temp_path = get_temp_path(&self.path);
+        let result = File::create(&temp_path)
+            .map_err(EDFError::FileWriteError)
+            .and_then(|file| {
+                if let Ok(metadata) = std::fs::metadata(&self.path) {
+                    file.set_permissions(metadata.permissions()).map_err(EDFError::FileWriteError)?;
+                }
+
+                let mut writer = BufWriter::new(file);
+                writer.write_all(&header.serialize()?).map_err(EDFError::FileWriteError)?;
+                f(self, &header, &mut writer)?;
+                let file = writer
+                    .into_inner()
+                    .map_err(|err| EDFError::FileWriteError(err.into_error()))?;
+                file.sync_all().map_err(EDFError::FileWriteError)
+            })
+            .and_then(|_| std::fs::rename(&temp_path, &self.path).map_err(EDFError::FileWriteError));
+        if let Err(err) = result {
+            _ = std::fs::remove_file(&temp_path);
+            return Err(err);
+        }
+        sync_parent_dir(&self.path);
+
+        // Switch over to the new file and start reading at its first data-record
+        self.header = header;
+        self.header.update_initial_record_bytes();
+        self.header.update_initial_header_sha256()?;
+        self.record_counter = self.header.record_count.unwrap_or(0);
+        self.signal_counter = self.header.signal_count;
+        self.record_read_offset_ns = 0;
+        self.gap_read_offset_ns = 0;
+        self.reopen_reader()?;
+        self.seek_to_record(0)
+    }
+
+    /// Creates a new file at the given path, which must not exist yet. The given header is written first, while the given
+    /// function has to write all data-records. In case anything fails, the partially written file is removed again.
+    /// Returns the created file.
+    fn create_file<F>(&mut self, path: &Path, header: EDFHeader, f: F) -> Result<EDFFile, EDFError>
+    where
+        F: FnOnce(&mut Self, &EDFHeader, &mut BufWriter<File>) -> Result<(), EDFError>,
+    {
+        create_new_file(path, header, |header, writer| f(self, header, writer))
+    }
+
+    /// Reads the onset of every data-record stored on disk (see `Record::get_start_offset`)
+    fn read_initial_onsets(&mut self) -> Result<Vec<f64>, EDFError> {
+        let filesize = self.reader.get_ref().metadata().map_err(EDFError::FileReadError)?.len();
+        (0..self.get_initial_record_count(filesize))
+            .map(|idx| {
+                self.check_cancelled()?;
+                Ok(self.read_initial_record(idx)?.get_start_offset())
+            })
+            .collect()
+    }
+
+    /// Reads the onset of every data-record including all pending changes (see `Record::get_start_offset`)
+    fn read_edited_onsets(&mut self) -> Result<Vec<f64>, EDFError> {
+        (0..self.record_counter)
+            .map(|idx| {
+                self.check_cancelled()?;
+                let record = self.read_record_at(idx)?.ok_or(EDFError::ItemNotFound)?;
+                Ok(record.get_start_offset())
+            })
+            .collect()
+    }
+
+    /// Returns `EDFError::PendingChanges` in case there are any changes which were not saved yet
+    fn ensure_no_pending_changes(&self) -> Result<(), EDFError> {
+        match self.instructions.is_empty() && self.signal_instructions.is_empty() {
+            true => Ok(()),
+            false => Err(EDFError::PendingChanges),
+        }
+    }
+
+    /// Reads the data-record at the given index as it is stored on disk, ignoring all pending changes
+    fn read_initial_record(&mut self, index: usize) -> Result<Record, EDFError> {
+        let offset = self.header.header_bytes as u64 + index as u64 * self.header.get_initial_record_bytes() as u64;
+        self.reader.seek(SeekFrom::Start(offset)).map_err(EDFError::FileReadError)?;
+        Self::read_record_data(
+            &mut self.reader,
+            index as u64,
+            &self.header.signals,
+            self.header.record_duration,
+            &self.header.specification
+        )
+    }
+
+    /// Checks whether or not the data-records (including all pending changes) are stored in chronological order by comparing
+    /// their onsets (see `Record::get_start_offset`). Discontinuous EDF+ and BDF+ files are allowed to store their data-records
+    /// out of order, while reading them by duration (e.g. using `read_nanos`) expects them to be chronological.
+    pub fn check_record_order(&mut self) -> Result<RecordOrderReport, EDFError> {
+        let onsets = self.read_edited_onsets()?;
+        Ok(RecordOrderReport::new(&onsets, self.header.record_duration))
+    }
+
+    /// Reorders all data-records (including all pending changes) by their onset, where data-records with the same onset keep
+    /// their relative order. Every data-record which is not at its chronological position yet is updated with the data-record
+    /// belonging to that position. Those updates are regular pending changes, which have to be saved using `save()` and
+    /// require the updated data-records to be kept in memory until then. The required memory therefore grows with the amount
+    /// of displaced data-records and reaches the size of the entire data-record section for files which are out of order
+    /// as a whole, which should be checked using `check_record_order` before sorting large files. Returns the amount of
+    /// updated data-records.
+    pub fn sort_records(&mut self) -> Result<usize, EDFError> {
+        let position = self.reader.stream_position().map_err(EDFError::FileReadError)?;
+        let onsets = self.read_edited_onsets()?;
+        let mut order = (0..onsets.len()).collect::<Vec<_>>();
+        order.sort_by(|a, b| onsets[*a].total_cmp(&onsets[*b]));
+
+        // Read all records before updating any of them, as the updates would change the records being read otherwise
+        let displaced = order
+            .into_iter()
+            .enumerate()
+            .filter(|(target_idx, idx)| target_idx != idx)
+            .collect::<Vec<_>>();
+        let records = displaced
+            .iter()
+            .map(|(_, idx)| self.read_record_at(*idx)?.ok_or(EDFError::ItemNotFound))
+            .collect::<Result<Vec<_>, _>>()?;
+        for ((target_idx, _), record) in displaced.iter().zip(records) {
+            self.update_record(*target_idx, record)?;
+        }
+
+        self.reader.seek(SeekFrom::Start(position)).map_err(EDFError::FileReadError)?;
+        Ok(displaced.len())
+    }
+
+    /// Determines what calling `save()` would do without writing anything to disk. The returned plan contains the
+    /// normalized instructions and e.g. the amount of data-records which have to be moved within the file. This can
+    /// be used to warn users before potentially long running save operations. Neither the file on disk nor the
+    /// pending changes are modified.
+    pub fn plan_save(&mut self) -> Result<SavePlan, EDFError> {
+        let strategy = self.get_effective_save_strategy();
+        self.inspect_save(|edf, context| {
+            let initial_file_size = edf
+                .reader
+                .get_ref()
+                .metadata()
+                .map_err(EDFError::FileReadError)?
+                .len();
+            let Some(context) = context else {
+                return Ok(SavePlan {
+                    initial_file_size,
+                    file_size: initial_file_size,
+                    ..Default::default()
+                });
+            };
+
+            let header_size = edf.header.header_bytes as u64;
+            let record_bytes = edf.header.data_record_bytes() as u64;
+            let rewrites_header = context.instructions.contains(&SaveInstruction::WriteHeader);
+            let mut plan = SavePlan {
+                record_instructions: context.instructions.clone(),
+                signal_instructions: context.signal_instructions.clone(),
+                rewrites_header,
+                becomes_discontinuous: context.initial_is_continuous && !edf.header.is_continuous,
+                initial_file_size,
+                file_size: header_size,
+                written_bytes: if rewrites_header { header_size } else { 0 },
+                ..Default::default()
+            };
+
+            // In case there are no signals, there are no records to be written either
+            if edf.header.signal_count == 0 {
+                plan.written_bytes = header_size;
+                return Ok(plan);
+            }
+
+            // Determine which records have to be written, the same way the in-place save does
+            let mut items = ResolvedItems::new(&context.instructions, context.initial_record_count);
+            let mut record_idx = 0;
+            while let Some(item) = items.next() {
+                let target_offset = header_size + record_idx * record_bytes;
+                record_idx += 1;
+
+                match item {
+                    ResolvedValue::Initial(idx) => {
+                        let source_offset = context.initial_header_size + idx as u64 * context.initial_record_bytes as u64;
+                        let shift_record = context.shift_onsets && items.removed_count() > 0;
+                        if source_offset != target_offset {
+                            plan.moved_records += 1;
+                        } else if context.signal_instructions.is_empty() && !shift_record {
+                            continue;
+                        }
+                        plan.rewritten_records += 1;
+                    }
+                    ResolvedValue::Pending(_) => plan.pending_records += 1,
+                }
+                plan.written_bytes += record_bytes;
             }
-        } else if header_instruct_positions.is_empty() && header_changed {
-            self.instructions.insert(0, SaveInstruction::WriteHeader);
-        }
+            plan.file_size = header_size + record_idx * record_bytes;
 
-        // Try to get the current read position to go back to after saving
-        let initial_read_position = self
+            // The atomic save always writes the entire file
+            if strategy == SaveStrategy::Atomic {
+                plan.written_bytes = plan.file_size;
+            }
+
+            Ok(plan)
+        })
+    }
+
+    /// Prepares saving the file and passes the prepared state to the given function without saving anything.
+    /// The header and all pending changes are restored afterwards, regardless of the result.
+    fn inspect_save<T, F>(&mut self, f: F) -> Result<T, EDFError>
+    where
+        F: FnOnce(&mut Self, Option<&SaveContext>) -> Result<T, EDFError>,
+    {
+        let initial_filesize = self
             .reader
-            .stream_position()
-            .map_err(EDFError::FileWriteError)?;
-        let initial_record_position = if initial_record_bytes == 0 {
-            None
-        } else {
-            initial_read_position
-                .checked_sub(initial_header_size)
-                .map(|pos| pos % initial_record_bytes as u64)
+            .get_ref()
+            .metadata()
+            .map_err(EDFError::FileReadError)?
+            .len();
+
+        // Preparing the save updates the header and instructions, which have to be restored afterwards
+        let initial_header = self.header.clone();
+        let initial_instructions = self.instructions.clone();
+        let initial_signal_instructions = self.signal_instructions.clone();
+        let undo_history = std::mem::take(&mut self.undo_history);
+        let redo_history = std::mem::take(&mut self.redo_history);
+        let result = match self.prepare_save(initial_filesize) {
+            Ok(context) => f(self, context.as_ref()),
+            Err(err) => Err(err),
         };
+        self.header = initial_header;
+        self.instructions = initial_instructions;
+        self.signal_instructions = initial_signal_instructions;
+        self.normalized_instructions = None;
+        self.undo_history = undo_history;
+        self.redo_history = redo_history;
+
+        result
+    }
 
-        // If there are no instructions at all, nothing has to be done and the input remains the same
-        if self.instructions.is_empty() && self.signal_instructions.is_empty() {
-            return Ok(());
-        }
-
-        // Transform the list of instructions into a simplified sorted list of instructions
-        let instructions = normalize_instructions(&self.instructions, initial_record_count);
-        let signal_instructions =
-            normalize_instructions(&self.signal_instructions, initial_signal_count);
+    /// Writes the entire edited file into a temporary file next to the given path, which then atomically replaces
+    /// the file at the given path. In case anything fails, the pending changes are kept. Returns `None` in case
+    /// there is nothing to be saved.
+    fn write_atomic(&mut self, path: &Path) -> Result<Option<SaveContext>, EDFError> {
+        let initial_filesize = std::fs::metadata(&self.path)
+            .map_err(EDFError::FileReadError)?
+            .len();
+
+        // Keep the state before saving to be able to restore it in case anything fails. This way the
+        // pending changes are not lost and saving can simply be retried
+        let initial_header = self.header.clone();
+        let initial_instructions = self.instructions.clone();
+        let context = match self.prepare_save(initial_filesize) {
+            Ok(Some(context)) => context,
+            Ok(None) => return Ok(None),
+            Err(err) => {
+                self.header = initial_header;
+                self.instructions = initial_instructions;
+                return Err(err);
+            }
+        };
 
-        // Ensure all records have the correct signal layout before writing anything to disk
-        if !self.records_match_signals() {
-            return Err(EDFError::InvalidRecordSignals);
+        // Write the entire edited file into a temporary file next to the target file. The target file
+        // remains untouched until the temporary file has been written and synced to disk entirely
+        let temp_path = get_temp_path(path);
+        let result = self.write_temp_file(&context, &temp_path);
+        if let Err(err) = result {
+            _ = std::fs::remove_file(&temp_path);
+            self.header = initial_header;
+            self.instructions = initial_instructions;
+            return Err(err);
         }
 
-        // If there were instructions, but all cancelled out, nothing has to be done either and the
-        // input remains the same again. e.g. Insert at index 1 followed by Delete at index 1.
-        if instructions.is_empty() && signal_instructions.is_empty() {
-            self.instructions.clear();
-            return Ok(());
+        // Atomically replace the target file with the fully written temporary file
+        if let Err(err) = std::fs::rename(&temp_path, path) {
+            _ = std::fs::remove_file(&temp_path);
+            self.header = initial_header;
+            self.instructions = initial_instructions;
+            return Err(EDFError::FileWriteError(err));
         }
+        sync_parent_dir(path);
 
-        // Depending on the delete strategy, update EDF+ files to be discontinuous after deleting a record
-        let removes_middle_record = instructions.iter().any(|i| matches!(i, SaveInstruction::Remove(idx) if *idx > 0 && *idx < self.record_counter - 1));
-        if (self.header.specification == EDFSpecifications::EDFPlus || self.header.specification == EDFSpecifications::BDFPlus)
-            && self.header.is_continuous
-            && removes_middle_record
-            && self.record_delete_strategy == RecordDeleteStrategy::Discontinuous
-        {
-            self.header.is_continuous = false;
+        Ok(Some(context))
+    }
+
+    /// Opens a new reader for the file at the current path. Returns the size of the file.
+    fn reopen_reader(&mut self) -> Result<u64, EDFError> {
+        let file = File::open(&self.path).map_err(EDFError::FileReadError)?;
+        let file_size = file.metadata().map_err(EDFError::FileReadError)?.len();
+        self.reader = BufReader::new(file);
+
+        Ok(file_size)
+    }
+
+    fn write_temp_file(&mut self, context: &SaveContext, temp_path: &Path) -> Result<(), EDFError> {
+        let file = File::create(temp_path).map_err(EDFError::FileWriteError)?;
+        if let Ok(metadata) = std::fs::metadata(&self.path) {
+            file.set_permissions(metadata.permissions())
+                .map_err(EDFError::FileWriteError)?;
         }
 
-        let patch_trailing_records = !signal_instructions.is_empty();
-        let mut overwrite_counter = header_size_diff;
-        let mut overwrite_buffer = Vec::new();
-        let mut record_counter = instructions
-            .iter()
-            .filter(|i| i.has_record_index())
-            .map(SaveInstruction::record_index)
-            .next()
-            .unwrap_or(0);
-        let mut instruction_idx = 0;
+        let mut writer = BufWriter::new(file);
+        self.write_edited(context, &mut writer, ProgressOperation::Save)?;
+        let file = writer
+            .into_inner()
+            .map_err(|err| EDFError::FileWriteError(err.into_error()))?;
+        file.sync_all().map_err(EDFError::FileWriteError)
+    }
 
-        if patch_trailing_records {
-            record_counter = 0;
+    /// Writes the entire file in its edited state to the given writer. The header is written first and is
+    /// followed by every data-record which is either read from disk and patched to match the new signal
+    /// layout, or taken from the pending instructions. Returns the amount of bytes written.
+    fn write_edited<W: Write>(
+        &mut self,
+        context: &SaveContext,
+        writer: &mut W,
+        operation: ProgressOperation
+    ) -> Result<u64, EDFError> {
+        self.check_cancelled()?;
+        self.clipping_statistics = vec![ClippingStatistics::default(); self.header.signal_count];
+        let header = self.header.serialize()?;
+        writer.write_all(&header).map_err(EDFError::FileWriteError)?;
+        let mut written = header.len() as u64;
+
+        // In case there are no signals, there are no records to be written either
+        if self.header.signal_count == 0 {
+            return Ok(written);
         }
 
-        // Seek to first data-record edit position. In case the file header has to be written, this seek operation will be useless
-        file.seek(SeekFrom::Start(
-            initial_header_size + record_counter as u64 * initial_record_bytes as u64,
-        ))
-        .map_err(EDFError::FileWriteError)?;
+        let mut buffer = vec![0; context.initial_record_bytes];
+        let mut items = ResolvedItems::new(&context.instructions, context.initial_record_count);
+        let mut previous = None;
+        let mut processed = 0;
+        while let Some(item) = items.next() {
+            self.check_cancelled()?;
+            let initial_idx = item.initial_index();
+            let data = match item {
+                ResolvedValue::Pending(SaveValue::Record(record)) => self.serialize_pending_record(context, record, processed as usize)?,
+                ResolvedValue::Pending(SaveValue::Signal(_)) => return Err(EDFError::InvalidRecordSignals),
+                ResolvedValue::Initial(idx) => {
+                    let offset = context.initial_header_size + idx as u64 * context.initial_record_bytes as u64;
+                    seek_read_exact_at(self.reader.get_mut(), &mut buffer, offset)
+                        .map_err(EDFError::FileReadError)?;
 
-        // Loop through all instructions and perform each of them
-        loop {
-            let instruct = match instructions.get(instruction_idx) {
-                Some(instruct) => instruct,
-                None => {
-                    if patch_trailing_records {
-                        &SaveInstruction::Patch
-                    } else {
-                        break;
-                    }
+                    // The data-records surrounding the current one in the edited file are required to resample its samples
+                    let next = items.clone().next().and_then(|next| next.initial_index());
+                    let neighbours = (previous, next);
+                    self.patch_initial_record(context, buffer.clone(), idx, items.removed_count(), processed as usize, neighbours)?
                 }
             };
+            previous = initial_idx;
 
-            match instruct {
-                SaveInstruction::WriteHeader => {
-                    instruction_idx += 1;
-
-                    // NOTE: This instruction must not be called more than once per save operation and
-                    // must be called as the first instruction if it is present!
-
-                    file.seek(SeekFrom::Start(0))
-                        .map_err(EDFError::FileWriteError)?;
-                    if let Ok(read_length) = usize::try_from(overwrite_counter)
-                        && read_length > 0
-                    {
-                        let read_max = initial_filesize.saturating_sub(initial_header_size);
-                        let read_length = read_length.min(read_max as usize);
-                        if read_length > 0 {
-                            let mut buffer = vec![0; read_length]; // TODO: This should be a function global defined buffer
-                            seek_read_exact_at(&mut file, &mut buffer, initial_header_size)
-                                .map_err(EDFError::FileWriteError)?;
-                            overwrite_buffer.append(&mut buffer);
-                        }
-                    }
-
-                    file.write_all(&self.header.serialize()?)
-                        .map_err(EDFError::FileWriteError)?;
-
-                    // Require re-writing all data-records from the beginning due to a change in offset
-                    if overwrite_counter != 0 {
-                        record_counter = 0;
-                    } else if !patch_trailing_records {
-                        file.seek(SeekFrom::Start(
-                            initial_header_size
-                                + record_counter as u64 * initial_record_bytes as u64,
-                        ))
-                        .map_err(EDFError::FileWriteError)?;
-                    }
-                }
-                SaveInstruction::Remove(idx) if *idx == record_counter => {
-                    instruction_idx += 1;
-                    _ = overwrite_buffer
-                        .drain(0..initial_record_bytes.min(overwrite_buffer.len()))
-                        .count();
-                    overwrite_counter -= initial_record_bytes as i64;
-                }
-                SaveInstruction::Insert(idx, SaveValue::Record(value))
-                    if *idx == record_counter =>
-                {
-                    instruction_idx += 1;
-                    record_counter += 1;
-
-                    // println!("I - BEFORE {} / {} / {}", overwrite_counter, overwrite_buffer.len(), file.stream_position().map_err(SerializeError::FileWriteError)?.saturating_sub(1024));
-                    let read_offset = if overwrite_counter < 0 {
-                        overwrite_counter.abs()
-                    } else {
-                        0
-                    } as u64;
-                    let current_file_position = file.stream_position().map_err(EDFError::FileWriteError)? + read_offset;
-                    let read_max = initial_filesize.saturating_sub(current_file_position);
-                    if let Ok(new_buffer_length) =
-                        usize::try_from(overwrite_counter + new_record_bytes as i64)
-                        && new_buffer_length > 0
-                    {
-                        let read_length = new_buffer_length - overwrite_buffer.len(); // This will only ever be different from `new_record_bytes` in case `overwrite_counter` was negative and < `new_record_bytes`
-                        let read_length = read_length.min(read_max as usize);
-                        if read_length > 0 {
-                            let mut buffer = vec![0; read_length]; // TODO: This should be a function global defined buffer
-                            seek_read_exact_at(&mut file, &mut buffer, current_file_position)
-                                .map_err(EDFError::FileWriteError)?;
-                            overwrite_buffer.append(&mut buffer);
-                        }
-                    }
+            writer.write_all(&data).map_err(EDFError::FileWriteError)?;
+            written += data.len() as u64;
 
-                    file.write_all(&value.serialize()?)
-                        .map_err(EDFError::FileWriteError)?;
-                    overwrite_counter += new_record_bytes.min(read_max as usize) as i64;
-                }
-                SaveInstruction::Update(idx, SaveValue::Record(value))
-                    if *idx == record_counter =>
-                {
-                    instruction_idx += 1;
-                    record_counter += 1;
-
-                    let buffer_read_count = overwrite_buffer
-                        .drain(0..initial_record_bytes.min(overwrite_buffer.len()))
-                        .count();
-                    let disk_read_count = initial_record_bytes.saturating_sub(buffer_read_count);
-
-                    let read_offset = if overwrite_counter < 0 {
-                        overwrite_counter.abs()
-                    } else {
-                        0
-                    } as u64;
-                    let buffered_offset = if overwrite_counter > 0 {
-                        overwrite_counter
-                    } else {
-                        0
-                    } as u64;
-                    let current_file_position = file.stream_position().map_err(EDFError::FileWriteError)? + read_offset;
-
-                    // Add data to overwrite buffer which would be overwritten after writing the current record
-                    let read_max = initial_filesize.saturating_sub(current_file_position);
-                    let target_read_length =
-                        overwrite_counter + new_record_bytes as i64 - buffered_offset as i64;
-                    let read_length = u64::try_from(target_read_length)
-                        .map(|len| len.min(read_max).saturating_sub(disk_read_count as u64))
-                        .unwrap_or(0) as usize;
-                    if read_length > 0 {
-                        let mut buffer = vec![0; read_length]; // TODO: This should be a function global defined buffer
-                        seek_read_exact_at(
-                            &mut file,
-                            &mut buffer,
-                            current_file_position + disk_read_count as u64,
-                        )
-                        .map_err(EDFError::FileWriteError)?;
-                        overwrite_buffer.append(&mut buffer);
-                    }
+            processed += 1;
+            self.report_progress(operation.clone(), processed, context.record_count as u64);
+        }
 
-                    // When coming to end of file, instead of adding the diff, remove everything that would read past the file end
-                    let exceed = if target_read_length.max(0) as u64 > read_max {
-                        target_read_length.max(0) as u64 - read_max
-                    } else {
-                        0
-                    };
-                    overwrite_counter += new_record_bytes as i64
-                        - disk_read_count as i64
-                        - buffer_read_count as i64
-                        - exceed as i64;
+        Ok(written)
+    }
 
-                    file.write_all(&value.serialize()?)
-                        .map_err(EDFError::FileWriteError)?;
-                }
-                SaveInstruction::Patch | _ => {
-                    // Break if the last available record has already been written
-                    if record_counter == self.record_counter {
-                        break;
-                    }
+    /// Serializes a pending data-record to be written at the given index. The Time-keeping annotation list of
+    /// data-records in upgraded files is generated from that index.
+    fn serialize_pending_record(&self, context: &SaveContext, record: &Record, record_idx: usize) -> Result<Vec<u8>, EDFError> {
+        if !context.generate_time_keeping {
+            return record.serialize();
+        }
 
-                    // In case the file offset is at the beginning of the next data-record, seek to the next position
-                    // in the file where another instruction has to be handled. All records between the current
-                    // position and the target position remain entirely unchanged (as long as signals have not changed)
-                    if overwrite_counter == 0 && !patch_trailing_records {
-                        record_counter = instructions
-                            .iter()
-                            .skip(instruction_idx)
-                            .find_map(|i| {
-                                if i.record_index() != usize::MAX {
-                                    Some(i.record_index())
-                                } else {
-                                    None
-                                }
-                            })
-                            .unwrap_or(0);
-                        file.seek(SeekFrom::Start(
-                            initial_header_size
-                                + record_counter as u64 * initial_record_bytes as u64,
-                        ))
-                        .map_err(EDFError::FileWriteError)?;
-                        continue;
-                    }
+        let mut record = record.clone();
+        record.set_time_keeping(record_idx as f64 * self.header.record_duration)?;
+        record.serialize()
+    }
 
-                    // Try to read the record from the overwrite buffer
-                    let read_offset = if overwrite_counter < 0 {
-                        overwrite_counter.abs()
-                    } else {
-                        0
-                    } as u64;
-                    let buffered_offset = if overwrite_counter > 0 {
-                        overwrite_counter
-                    } else {
-                        0
-                    } as u64;
-                    let mut buffer_read = overwrite_buffer
-                        .drain(0..initial_record_bytes.min(overwrite_buffer.len()))
-                        .collect::<Vec<_>>();
-                    let buffer_read_count = buffer_read.len();
-                    let disk_read_count = initial_record_bytes - buffer_read_count;
-
-                    // Read the remaining bytes of the current record from disk if it was not entirely in the buffer
-                    let current_file_position =
-                        file.stream_position()
-                            .map_err(EDFError::FileWriteError)? + read_offset;
-                    if disk_read_count > 0 {
-                        let mut buffer = vec![0; disk_read_count]; // TODO: This should be a function global defined buffer
-                        seek_read_exact_at(&mut file, &mut buffer, current_file_position)
-                            .map_err(EDFError::FileWriteError)?;
-                        buffer_read.append(&mut buffer);
-                    }
+    /// Patches the raw bytes of a data-record stored on disk to match the new signal layout and shifts its onsets
+    /// by the amount of removed data-records preceding it (in case records are deleted continuously). The Time-keeping
+    /// annotation list of data-records in upgraded files is generated from the given index the data-record is written
+    /// to. The given neighbours are the initial indices of the data-records preceding and following it in the edited
+    /// file (see `read_contiguous_neighbours`). Records which do not require any changes are returned as they are.
+    fn patch_initial_record(
+        &mut self,
+        context: &SaveContext,
+        buffer: Vec<u8>,
+        initial_idx: usize,
+        removed_counter: usize,
+        record_idx: usize,
+        neighbours: (Option<usize>, Option<usize>)
+    ) -> Result<Vec<u8>, EDFError> {
+        let shift_record = context.shift_onsets && removed_counter > 0;
+        if context.signal_instructions.is_empty() && !shift_record {
+            return Ok(buffer);
+        }
 
-                    // Add data to overwrite buffer which would be overwritten after writing the current record
-                    let read_max = initial_filesize.saturating_sub(current_file_position);
-                    let target_read_length =
-                        overwrite_counter + new_record_bytes as i64 - buffered_offset as i64;
-                    let read_length = u64::try_from(target_read_length)
-                        .map(|len| len.min(read_max).saturating_sub(disk_read_count as u64))
-                        .unwrap_or(0) as usize;
-                    if read_length > 0 {
-                        let mut buffer = vec![0; read_length]; // TODO: This should be a function global defined buffer
-                        seek_read_exact_at(
-                            &mut file,
-                            &mut buffer,
-                            current_file_position + disk_read_count as u64,
-                        )
-                        .map_err(EDFError::FileWriteError)?;
-                        overwrite_buffer.append(&mut buffer);
-                    }
+        let cursor = Cursor::new(buffer);
+        let mut reader = BufReader::new(cursor);
+        let mut record = Self::read_record_data(
+            &mut reader,
+            initial_idx as u64,
+            &context.initial_signals,
+            context.initial_record_duration,
+            &context.initial_specification
+        )?;
+        let (previous, next) = match requires_neighbours(&context.signal_instructions) {
+            true => self.read_initial_neighbours(context, record.get_start_offset(), neighbours)?,
+            false => (None, None),
+        };
+        let statistics = record.patch_record_with_neighbours(&context.signal_instructions, &context.initial_signals, previous, next)?;
+        for (total, signal) in self.clipping_statistics.iter_mut().zip(&statistics) {
+            total.add(signal);
+        }
+        if shift_record {
+            record.shift_onsets(-(removed_counter as f64 * context.initial_record_duration));
+        }
+        if context.generate_time_keeping {
+            record.set_time_keeping(record_idx as f64 * self.header.record_duration)?;
+        }
 
-                    // When coming to end of file, instead of adding the diff, remove everything that would read past the file end
-                    let exceed = if target_read_length.max(0) as u64 > read_max {
-                        target_read_length.max(0) as u64 - read_max
-                    } else {
-                        0
-                    };
-                    overwrite_counter += new_record_bytes as i64
-                        - disk_read_count as i64
-                        - buffer_read_count as i64
-                        - exceed as i64;
-
-                    // In case the signals changed, patch the record and update the buffer with the patched data
-                    if !signal_instructions.is_empty() {
-                        let cursor = Cursor::new(buffer_read);
-                        let mut reader = BufReader::new(cursor);
-                        let mut record = Self::read_record_data(
-                            &mut reader,
-                            0,
-                            &initial_signals,
-                            initial_record_duration,
-                            &self.header.specification
-                        )?;
-                        record.patch_record(&signal_instructions)?;
-                        buffer_read = record.serialize()?;
-                    }
+        record.serialize()
+    }
 
-                    file.write_all(&buffer_read)
-                        .map_err(EDFError::FileWriteError)?;
-                    record_counter += 1;
-                }
-            }
-        }
+    /// Reads the data-records stored on disk at the given initial indices, which precede and follow a data-record
+    /// starting at the given onset in the edited file (see `read_contiguous_neighbours`)
+    fn read_initial_neighbours(
+        &mut self,
+        context: &SaveContext,
+        onset: f64,
+        neighbours: (Option<usize>, Option<usize>)
+    ) -> Result<(Option<Record>, Option<Record>), EDFError> {
+        let mut buffer = vec![0; context.initial_record_bytes];
+        read_contiguous_neighbours(onset, context.initial_record_duration, neighbours, |idx| {
+            let offset = context.initial_header_size + idx as u64 * context.initial_record_bytes as u64;
+            seek_read_exact_at(self.reader.get_mut(), &mut buffer, offset).map_err(EDFError::FileReadError)?;
+            Self::read_record_data(
+                &mut BufReader::new(Cursor::new(&buffer)),
+                idx as u64,
+                &context.initial_signals,
+                context.initial_record_duration,
+                &context.initial_specification
+            )
+        })
+    }
 
-        // The file size has changed, therefore either write the remaining buffered data to disk, or
-        // truncate the file (and replacing the content to be truncated with NUL bytes before)
-        if overwrite_counter != 0 {
-            if overwrite_counter > 0 {
-                file.write_all(&overwrite_buffer)
-                    .map_err(EDFError::FileWriteError)?;
-                overwrite_buffer.clear();
-            } else {
-                let reduced_by_length = overwrite_counter.abs() as usize;
-                let position = file.stream_position().map_err(EDFError::FileWriteError)?;
-                file.write_all(&repeat_n(0, reduced_by_length).collect::<Vec<_>>())
-                    .map_err(EDFError::FileWriteError)?;
-                file.set_len(position).map_err(EDFError::FileWriteError)?;
-            }
-        }
+    /// Clears all pending instructions after they were written to disk and updates the values
+    /// required to be able to edit the file again.
+    fn finish_save(&mut self, context: &SaveContext, new_file_size: u64) -> Result<(), EDFError> {
+        self.clear_saved_changes()?;
+        self.restore_reader_position(context, new_file_size)
+    }
 
-        // Flush the write buffer, clear the pending instructions and get the new file length
-        file.flush().map_err(EDFError::FileWriteError)?;
+    /// Clears all pending instructions as soon as they are part of the file on disk and updates the initial
+    /// values of the header to match the saved state.
+    fn clear_saved_changes(&mut self) -> Result<(), EDFError> {
         self.instructions.clear();
-        let new_file_size = file.metadata().map_err(EDFError::FileWriteError)?.len();
+        self.signal_instructions.clear();
+        self.normalized_instructions = None;
+        self.undo_history.clear();
+        self.redo_history.clear();
 
         // Update the initial record size and header hash so they are valid for the current state.
         // This ensures the next save action works with the right offsets and instructions
         self.header.update_initial_record_bytes();
         self.header.update_initial_header_sha256()?;
 
+        // The pending changes of a previously saved session are part of the file now
+        remove_session_file(&self.path)
+    }
+
+    /// Seeks the reader to the data-record it was at before saving, which is clamped to the given new size of the file
+    fn restore_reader_position(&mut self, context: &SaveContext, new_file_size: u64) -> Result<(), EDFError> {
         // Try to seek to the position the reader initially was at
-        if let Some(record_idx) = initial_record_position {
+        let new_record_bytes = self.header.data_record_bytes();
+        if let Some(record_idx) = context.initial_record_position {
             let seek_pos = self.header.header_bytes as u64 + record_idx * new_record_bytes as u64;
             self.reader
                 .seek(SeekFrom::Start(seek_pos.min(new_file_size)))
@@ -668,6 +2469,244 @@ impl EDFFile {
         Ok(())
     }
 
+    /// Writes all pending changes into a session file next to the EDF file (see `get_session_path`) without modifying
+    /// the EDF file itself. The session can be resumed using `resume_session()` after opening the file again, which allows
+    /// continuing to edit the file e.g. after restarting the application. Edits which can be reverted with `undo()` or
+    /// re-applied with `redo()` are kept as well. The session file is removed after saving the file.
+    pub fn save_session(&self) -> Result<(), EDFError> {
+        let file_size = self
+            .reader
+            .get_ref()
+            .metadata()
+            .map_err(EDFError::FileReadError)?
+            .len();
+
+        // Store the state of the file on disk to detect whether the file was changed outside of this session
+        let mut writer = SessionWriter::new();
+        writer.write_u64(file_size);
+        writer.write_str(self.header.get_initial_header_sha256());
+
+        // Store all header values which are not changed by the edits
+        writer.write_str(&self.header.version);
+        writer.write_patient_id(&self.header.patient_id);
+        writer.write_recording_id(&self.header.recording_id);
+        writer.write_date(&self.header.get_start_date());
+        writer.write_time(&self.header.start_time);
+        writer.write_specification(&self.header.specification);
+        writer.write_bool(self.header.is_continuous);
+        writer.write_i64(self.header.record_count.map(|c| c as i64).unwrap_or(-1));
+        writer.write_f64(self.header.record_duration);
+
+        // Store the file settings
+        writer.write_u8(match self.save_mode {
+            SaveMode::Default => 0,
+            SaveMode::Recording => 1,
+        });
+        writer.write_u8(match self.record_delete_strategy {
+            RecordDeleteStrategy::Continuous => 0,
+            RecordDeleteStrategy::Discontinuous => 1,
+        });
+        writer.write_u8(match self.save_strategy {
+            SaveStrategy::InPlace => 0,
+            SaveStrategy::Atomic => 1,
+        });
+        writer.write_patch_options(&self.get_patch_options());
+
+        // Store all edits in the order they were applied, as the pending changes are restored by re-applying them
+        writer.write_u64(self.undo_history.len() as u64);
+        for item in &self.undo_history {
+            write_session_edit(&mut writer, &item.edit)?;
+        }
+        writer.write_u64(self.redo_history.len() as u64);
+        for edit in &self.redo_history {
+            write_session_edit(&mut writer, edit)?;
+        }
+
+        writer.finish(get_session_path(&self.path))
+    }
+
+    /// Removes the session file of this file in case there is one. The pending changes in memory are not affected.
+    pub fn discard_session(&self) -> Result<(), EDFError> {
+        remove_session_file(&self.path)
+    }
+
+    /// Returns whether or not there is a session file belonging to this file (see `save_session`)
+    pub fn has_session(&self) -> bool {
+        get_session_path(&self.path).exists()
+    }
+
+    /// Restores the pending changes of the session file belonging to this file (see `save_session`). This requires the
+    /// file to not have any pending changes yet. Returns whether or not there was a session to resume. In case the file
+    /// was changed since the session was saved, `EDFError::SessionMismatch` is returned and the session file can be
+    /// removed using `discard_session()`. The file is left unchanged in case the session could not be resumed.
+    pub fn resume_session(&mut self) -> Result<bool, EDFError> {
+        self.ensure_no_pending_changes()?;
+        let Some(mut reader) = SessionReader::open(get_session_path(&self.path))? else {
+            return Ok(false);
+        };
+
+        // Keep the current state to restore it in case the session is invalid
+        let header = self.header.clone();
+        let counters = (self.record_counter, self.signal_counter);
+        let save_mode = self.save_mode.clone();
+        let record_delete_strategy = self.record_delete_strategy.clone();
+        let save_strategy = self.save_strategy.clone();
+        let resample_method = self.resample_method.clone();
+        let rescale_mode = self.rescale_mode.clone();
+
+        if let Err(err) = self.apply_session(&mut reader) {
+            self.header = header;
+            (self.record_counter, self.signal_counter) = counters;
+            self.save_mode = save_mode;
+            self.record_delete_strategy = record_delete_strategy;
+            self.save_strategy = save_strategy;
+            self.resample_method = resample_method;
+            self.rescale_mode = rescale_mode;
+            self.instructions.clear();
+            self.signal_instructions.clear();
+            self.normalized_instructions = None;
+            self.undo_history.clear();
+            self.redo_history.clear();
+            return Err(err);
+        }
+
+        Ok(true)
+    }
+
+    /// Applies the state and pending changes stored in the given session to this file
+    fn apply_session(&mut self, reader: &mut SessionReader) -> Result<(), EDFError> {
+        // Ensure the file was not changed since the session was saved
+        let file_size = self
+            .reader
+            .get_ref()
+            .metadata()
+            .map_err(EDFError::FileReadError)?
+            .len();
+        if reader.read_u64()? != file_size || reader.read_string()? != *self.header.get_initial_header_sha256() {
+            return Err(EDFError::SessionMismatch);
+        }
+
+        let version = reader.read_string()?;
+        let patient_id = reader.read_patient_id()?;
+        let recording_id = reader.read_recording_id()?;
+        let start_date = reader.read_date()?;
+        let start_time = reader.read_time()?;
+        let specification = reader.read_specification()?;
+        let is_continuous = reader.read_bool()?;
+        let record_count = usize::try_from(reader.read_i64()?).ok();
+        let record_duration = reader.read_f64()?;
+
+        self.save_mode = match reader.read_u8()? {
+            0 => SaveMode::Default,
+            1 => SaveMode::Recording,
+            _ => return Err(EDFError::InvalidSession),
+        };
+        self.record_delete_strategy = match reader.read_u8()? {
+            0 => RecordDeleteStrategy::Continuous,
+            1 => RecordDeleteStrategy::Discontinuous,
+            _ => return Err(EDFError::InvalidSession),
+        };
+        self.save_strategy = match reader.read_u8()? {
+            0 => SaveStrategy::InPlace,
+            1 => SaveStrategy::Atomic,
+            _ => return Err(EDFError::InvalidSession),
+        };
+        let options = reader.read_patch_options()?;
+        self.resample_method = options.resample_method;
+        self.rescale_mode = options.rescale_mode;
+
+        // Re-apply all edits in their initial order
+        for _ in 0..reader.read_usize()? {
+            let edit = self.read_session_edit(reader)?;
+            self.apply_edit(edit)?;
+        }
+        let mut redo_history = Vec::new();
+        for _ in 0..reader.read_usize()? {
+            redo_history.push(self.read_session_edit(reader)?);
+        }
+        self.redo_history = redo_history;
+
+        if !reader.is_finished() {
+            return Err(EDFError::InvalidSession);
+        }
+
+        self.header
+            .with_version(version)
+            .with_patient_id(patient_id)
+            .with_recording_id(recording_id)
+            .with_start_date(start_date)
+            .with_start_time(start_time)
+            .with_specification(specification)
+            .with_is_continuous(is_continuous)
+            .with_record_duration(record_duration)
+            .record_count = record_count;
+
+        Ok(())
+    }
+
+    fn read_session_edit(&self, reader: &mut SessionReader) -> Result<Edit, EDFError> {
+        let is_signal = reader.read_bool()?;
+        let options = if is_signal { Some(reader.read_patch_options()?) } else { None };
+        let kind = reader.read_u8()?;
+        let index = reader.read_usize()?;
+
+        // Read the value of the edit, which is only missing for remove edits and replaced by the target index or specification
+        // for move and convert edits
+        let value = if kind == 2 {
+            None
+        } else if kind == 4 {
+            return match options {
+                Some(options) => Ok(Edit::Signal(SaveInstruction::Move(index, reader.read_usize()?), options)),
+                None => Err(EDFError::InvalidSession),
+            };
+        } else if kind == 5 {
+            return match options {
+                Some(options) => Ok(Edit::Signal(SaveInstruction::Convert(reader.read_specification()?), options)),
+                None => Err(EDFError::InvalidSession),
+            };
+        } else if is_signal {
+            Some(SaveValue::Signal(reader.read_signal()?))
+        } else {
+            let specification = reader.read_specification()?;
+            let default_offset = reader.read_f64()?;
+            let data = reader.read_bytes()?;
+            let mut record = Self::read_record_data(
+                &mut BufReader::new(Cursor::new(data)),
+                0,
+                self.header.get_signals(),
+                self.header.record_duration,
+                &specification
+            )?;
+            record.default_offset = default_offset;
+            Some(SaveValue::Record(record))
+        };
+
+        let instruction = match (kind, value) {
+            (0, Some(value)) => SaveInstruction::Insert(index, value),
+            (1, Some(value)) => SaveInstruction::Update(index, value),
+            (2, None) => SaveInstruction::Remove(index),
+            (3, Some(value)) => SaveInstruction::Append(value),
+            _ => return Err(EDFError::InvalidSession),
+        };
+
+        Ok(match options {
+            Some(options) => Edit::Signal(instruction, options),
+            None => Edit::Record(instruction),
+        })
+    }
+
+    /// Returns the amount of data-records stored on disk. In case the file is still being recorded, the
+    /// record count is not available in the header and is therefore calculated from the file size instead.
+    fn get_initial_record_count(&self, filesize: u64) -> usize {
+        self.header.record_count.unwrap_or_else(|| {
+            let record_bytes = self.header.get_initial_record_bytes() as u64;
+            if record_bytes == 0 {
+                return 0;
+            }
+            (filesize.saturating_sub(self.header.header_bytes as u64) / record_bytes) as usize
+        })
+    }
+
     /// Gets the duration of the recording by multiplying the record duration with the
     /// record count. Note that this is not the actual duration in discontinuous files.
     /// For those scenarios, use the [`read_file_duration()`] function
@@ -699,9 +2738,10 @@ impl EDFFile {
                 };
 
                 // No actual file duration available as the file is still in recording mode
-                let Some(record_count) = self.header.record_count else {
+                if self.header.record_count.is_none() {
                     return Ok(Duration::ZERO);
-                };
+                }
+                let record_count = self.record_counter;
 
                 // Seek to the beginning of the last record
                 self.reader
@@ -736,13 +2776,11 @@ impl EDFFile {
         }
     }
 
+    /// Reads the data-record at the current reader position. Pending changes which were not yet saved are
+    /// taken into account. This means e.g. records A, B, C, D are stored in the EDF and then E was inserted at
+    /// index 2, the records returned by reading all records from the front will result in A, B, E, C, D before
+    /// and after saving. Records stored on disk are patched to match the pending signal changes as well.
     pub fn read_record(&mut self) -> Result<Option<Record>, EDFError> {
-        // TODO: Try to read the record from a state after save in case it was not yet saved. Meaning e.g.
-        // records A, B, C, D are stored in the EDF and then E was inserted at index 2, the records returned
-        // by reading all records from the front should result in A, B, E, C, D before and after saving. Therefore
-        // the records would have to be able to read either from in-memory pending instructions or from disk (at the
-        // correct offset). Also handle add/remove of signals to those in-memory records as well
-
         // TODO: Check if "Trigger Status channel" is always mandatory for BDF files or if this would break
         // many BDF files. Potentially only add optional parsing of this channel
 
@@ -763,31 +2801,89 @@ impl EDFFile {
             return Err(EDFError::InvalidReadRange);
         }
 
-        // Get the data-record index and check if there are any records left in the file
+        // Get the data-record index and check if there are any records left in the file (including pending records)
         let record_idx = (position - self.header.header_bytes as u64) / record_size;
-        let record_count = self
-            .header
-            .record_count
-            .ok_or(EDFError::ReadWhileRecording)?;
-        if record_idx + 1 > record_count as u64 {
+        if self.header.record_count.is_none() {
+            return Err(EDFError::ReadWhileRecording);
+        }
+        if record_idx + 1 > self.record_counter as u64 {
             return Ok(None);
         }
 
+        // Resolve the record through the pending instructions. The record is either stored on disk at a
+        // potentially different index or it is only available in memory
+        let record_duration = self.header.record_duration;
+        let disk_idx = match resolve_index(self.get_normalized_instructions(), record_idx as usize) {
+            ResolvedValue::Pending(SaveValue::Record(record)) => {
+                let mut record = record.clone();
+                record.default_offset = record_idx as f64 * record_duration;
+                self.seek_next_record(position + record_size)?;
+                return Ok(Some(record));
+            }
+            ResolvedValue::Pending(SaveValue::Signal(_)) => return Err(EDFError::ItemNotFound),
+            ResolvedValue::Initial(disk_idx) => disk_idx as u64,
+        };
+
         // Read and parse the record from disk
+        if disk_idx != record_idx {
+            self.reader
+                .seek(SeekFrom::Start(self.header.header_bytes as u64 + disk_idx * record_size))
+                .map_err(EDFError::FileReadError)?;
+        }
         let mut record = Self::read_record_data(
             &mut self.reader,
-            record_idx,
+            disk_idx,
             &self.header.signals,
-            self.header.record_duration,
+            record_duration,
             &self.header.specification
         )?;
 
-        // Patch the record to match the new signal definitions
-        record.patch_record(&self.signal_instructions)?;
+        // Patch the record to match the new signal definitions, which potentially requires the records surrounding it
+        // in the edited file to resample its samples
+        let signal_instructions = normalize_instructions(&self.signal_instructions, self.header.signal_count);
+        let (previous, next) = match requires_neighbours(&signal_instructions) {
+            true => {
+                let record_idx = record_idx as usize;
+                let previous = match record_idx {
+                    0 => None,
+                    idx => resolve_index(self.get_normalized_instructions(), idx - 1).initial_index(),
+                };
+                let next = match record_idx + 1 < self.record_counter {
+                    true => resolve_index(self.get_normalized_instructions(), record_idx + 1).initial_index(),
+                    false => None,
+                };
+                read_contiguous_neighbours(record.get_start_offset(), record_duration, (previous, next), |idx| {
+                    self.read_initial_record(idx)
+                })?
+            }
+            false => (None, None),
+        };
+        record.patch_record_with_neighbours(&signal_instructions, &self.header.signals, previous, next)?;
+        record.default_offset = record_idx as f64 * record_duration;
 
+        self.seek_next_record(position + record_size)?;
         Ok(Some(record))
     }
 
+    /// Moves the reader to the given position of the next record in case the record was not read from its own position
+    fn seek_next_record(&mut self, position: u64) -> Result<(), EDFError> {
+        if self.reader.stream_position().map_err(EDFError::FileReadError)? != position {
+            self.reader
+                .seek(SeekFrom::Start(position))
+                .map_err(EDFError::FileReadError)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the normalized list of pending data-record instructions. The list is cached until
+    /// the pending instructions change.
+    fn get_normalized_instructions(&mut self) -> &Vec<SaveInstruction> {
+        let initial_record_count = self.header.record_count.unwrap_or(0);
+        self.normalized_instructions
+            .get_or_insert_with(|| normalize_instructions(&self.instructions, initial_record_count))
+    }
+
     fn read_record_data<R: BufRead + Seek>(
         reader: &mut R,
         record_idx: u64,
@@ -903,6 +2999,7 @@ impl EDFFile {
     /// `Time-keeping Timestamped-Annotation-List` entries. Therefore if you were to read across 5 data-records,
     /// you will get at least 5 Time-keeping TALs returned in the `annotations` of the `SpanningRecord`
     pub fn read_nanos(&mut self, nanoseconds: u128) -> Result<SpanningRecord, EDFError> {
+        let offset_start = self.record_read_offset_ns;
         let offset_end = self.record_read_offset_ns + nanoseconds;
         let record_duration_ns = (self.header.record_duration * 1_000_000_000.0) as u128;
 
@@ -913,6 +3010,8 @@ impl EDFFile {
         // to succeed then), it would return the 3rd record.
         let mut records = SpanningRecord::new(&self.header);
         let mut offset_current = self.record_read_offset_ns;
+        let initial_position = self.reader.stream_position().map_err(EDFError::FileReadError)?;
+        let initial_gap_read_offset_ns = self.gap_read_offset_ns;
         let mut read_start_ns = if self.seek_previous_record()? {
             self.read_record()?
                 .map(|r| (r.get_start_offset() * 1_000_000_000.0) as u128)
@@ -923,6 +3022,21 @@ impl EDFFile {
 
         // Read until either reaching the desired read duration or until no more records are available
         while offset_current < offset_end {
+            // In case the read is cancelled, restore the state before reading to be able to read the same data again
+            if let Err(err) = self.check_cancelled() {
+                self.record_read_offset_ns = offset_start;
+                self.gap_read_offset_ns = initial_gap_read_offset_ns;
+                self.reader
+                    .seek(SeekFrom::Start(initial_position))
+                    .map_err(EDFError::FileReadError)?;
+                return Err(err);
+            }
+            self.report_progress(
+                ProgressOperation::Read,
+                (offset_current - offset_start).min(nanoseconds) as u64,
+                nanoseconds as u64
+            );
+
             let Some(mut record) = self.read_record()? else {
                 remaining_record_ns = 0;
                 break;
@@ -1055,6 +3169,7 @@ impl EDFFile {
 
         // Finish the record (to remove any potentially trailing empty spans)
         records.finish();
+        self.report_progress(ProgressOperation::Read, nanoseconds as u64, nanoseconds as u64);
 
         // Update the current record offset after reading
         self.record_read_offset_ns += remaining_record_ns;
@@ -1098,7 +3213,7 @@ impl EDFFile {
     }
 }
 
-fn i24_from_le_bytes(bytes: [u8; 3]) -> i32 {
+pub(crate) fn i24_from_le_bytes(bytes: [u8; 3]) -> i32 {
     i32::from_le_bytes([
         bytes[0],
         bytes[1],
@@ -1107,6 +3222,203 @@ fn i24_from_le_bytes(bytes: [u8; 3]) -> i32 {
     ])
 }
 
+/// Rounds the given amount of seconds to full nanoseconds to prevent floating point artifacts in serialized onsets
+pub(crate) fn round_nanos(seconds: f64) -> f64 {
+    (seconds * 1_000_000_000.0).round() / 1_000_000_000.0
+}
+
+/// Returns the given value as a whole number in case it is one (ignoring floating point inaccuracies)
+fn whole_number(value: f64) -> Option<usize> {
+    let rounded = value.round();
+    ((value - rounded).abs() < 1e-6 && rounded >= 0.0).then_some(rounded as usize)
+}
+
+/// Checks whether or not the given data-record duration fits into the 8 characters of its header field and is read back
+/// as the exact same value
+fn fits_record_duration_field(record_duration: f64) -> bool {
+    let serialized = record_duration.to_string();
+    serialized.len() <= 8 && serialized.parse::<f64>() == Ok(record_duration)
+}
+
+/// Returns the annotations of the first data-record filling the given gap, which are the Time-keeping annotation
+/// and the annotation spanning the entire gap (in case there is one)
+fn gap_annotations(gap: &FilledGap, record_duration: f64, annotation: Option<&str>) -> Result<Vec<AnnotationList>, EDFError> {
+    let onset = round_nanos(gap.onset);
+    let mut annotations = vec![AnnotationList::new_time_keeping(onset)];
+    if let Some(annotation) = annotation {
+        annotations.push(AnnotationList::new(onset, gap.count as f64 * record_duration, vec![annotation.to_string()])?);
+    }
+
+    Ok(annotations)
+}
+
+/// Returns the path of the temporary file used for atomic saves. This is the path of the
+/// given file with the additional extension `.tmp`
+fn get_temp_path(path: &Path) -> PathBuf {
+    let mut temp_path = path.as_os_str().to_os_string();
+    temp_path.push(".tmp");
+    PathBuf::from(temp_path)
+}
+
+/// Creates a new file at the given path, which must not exist yet. The given header is written first, while the given
+/// function has to write all data-records. In case anything fails, the partially written file is removed again.
+/// Returns the created file.
+pub(crate) fn create_new_file<F>(path: &Path, mut header: EDFHeader, f: F) -> Result<EDFFile, EDFError>
+where
+    F: FnOnce(&EDFHeader, &mut BufWriter<File>) -> Result<(), EDFError>,
+{
+    header.signal_count = header.signals.len();
+    header.header_bytes = header.calculate_header_bytes();
+    let serialized = header.serialize()?;
+
+    let file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)
+        .map_err(|err| match err.kind() {
+            std::io::ErrorKind::AlreadyExists => EDFError::FileAlreadyExists,
+            _ => EDFError::FileWriteError(err),
+        })?;
+    let mut writer = BufWriter::new(file);
+    let result = writer
+        .write_all(&serialized)
+        .map_err(EDFError::FileWriteError)
+        .and_then(|_| f(&header, &mut writer))
+        .and_then(|_| {
+            let file = writer
+                .into_inner()
+                .map_err(|err| EDFError::FileWriteError(err.into_error()))?;
+            file.sync_all().map_err(EDFError::FileWriteError)
+        });
+    if let Err(err) = result {
+        _ = std::fs::remove_file(path);
+        return Err(err);
+    }
+    sync_parent_dir(path);
+
+    EDFFile::open(path)
+}
+
+/// Tries to sync the directory containing the given file to ensure a rename operation is persisted
+/// on disk. Not every platform and file system supports this, therefore errors are being ignored.
+pub(crate) fn sync_parent_dir(path: &Path) {
+    #[cfg(unix)]
+    {
+        let parent = path
+            .parent()
+            .filter(|p| !p.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+        if let Ok(dir) = File::open(parent) {
+            _ = dir.sync_all();
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        _ = path;
+    }
+}
+
+fn write_session_edit(writer: &mut SessionWriter, edit: &Edit) -> Result<(), EDFError> {
+    let instruction = match edit {
+        Edit::Record(instruction) => {
+            writer.write_bool(false);
+            instruction
+        }
+        Edit::Signal(instruction, options) => {
+            writer.write_bool(true);
+            writer.write_patch_options(options);
+            instruction
+        }
+    };
+    let (kind, index, value) = match instruction {
+        SaveInstruction::Insert(idx, value) => (0, *idx, Some(value)),
+        SaveInstruction::Update(idx, value) => (1, *idx, Some(value)),
+        SaveInstruction::Remove(idx) => (2, *idx, None),
+        SaveInstruction::Append(value) => (3, 0, Some(value)),
+        SaveInstruction::Move(from, to) => {
+            writer.write_u8(4);
+            writer.write_u64(*from as u64);
+            writer.write_u64(*to as u64);
+            return Ok(());
+        }
+        SaveInstruction::Convert(specification) => {
+            writer.write_u8(5);
+            writer.write_u64(0);
+            writer.write_specification(specification);
+            return Ok(());
+        }
+        _ => return Err(EDFError::InvalidSession),
+    };
+
+    writer.write_u8(kind);
+    writer.write_u64(index as u64);
+    match value {
+        Some(SaveValue::Signal(signal)) => writer.write_signal(signal),
+        Some(SaveValue::Record(record)) => {
+            writer.write_specification(&record.specification);
+            writer.write_f64(record.default_offset);
+            writer.write_bytes(&record.serialize()?);
+        }
+        None => {}
+    }
+
+    Ok(())
+}
+
+/// Removes the session file belonging to the file at the given path in case it exists
+fn remove_session_file(path: &Path) -> Result<(), EDFError> {
+    match std::fs::remove_file(get_session_path(path)) {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(EDFError::FileWriteError(err)),
+        _ => Ok(()),
+    }
+}
+
+fn seek_write_all_at(file: &mut File, buffer: &[u8], offset: u64) -> Result<(), std::io::Error> {
+    #[cfg(unix)]
+    {
+        file.write_all_at(buffer, offset)
+    }
+    #[cfg(not(unix))]
+    {
+        file.seek(SeekFrom::Start(offset))?;
+        file.write_all(buffer)
+    }
+}
+
+/// Checks whether or not the given signal instructions resample signals using the samples of neighbouring data-records
+/// Reads the neighbouring data-records of a data-record starting at the given onset using the given function. The
+/// neighbours are given as the initial indices of the data-records preceding and following it in the edited file, which
+/// are `None` in case there is no such data-record or it is only available in memory. Pending data-records already match
+/// the edited signals and can therefore not be resampled alongside the data-record. Neighbours are only returned in case
+/// their onsets are contiguous to the data-record, as samples on the other side of a gap or of a removed data-record do
+/// not belong to the same waveform.
+fn read_contiguous_neighbours<F>(
+    onset: f64,
+    record_duration: f64,
+    neighbours: (Option<usize>, Option<usize>),
+    mut read: F
+) -> Result<(Option<Record>, Option<Record>), EDFError>
+where
+    F: FnMut(usize) -> Result<Record, EDFError>,
+{
+    let previous = neighbours.0
+        .map(&mut read)
+        .transpose()?
+        .filter(|record| (onset - record.get_start_offset() - record_duration).abs() < 1e-6);
+    let next = neighbours.1
+        .map(&mut read)
+        .transpose()?
+        .filter(|record| (record.get_start_offset() - onset - record_duration).abs() < 1e-6);
+
+    Ok((previous, next))
+}
+
+fn requires_neighbours(signal_instructions: &[SaveInstruction]) -> bool {
+    signal_instructions
+        .iter()
+        .any(|i| matches!(i, SaveInstruction::Patch(options) if options.resample_method != ResampleMethod::Resize))
+}
+
 fn seek_read_exact_at(file: &mut File, buffer: &mut Vec<u8>, offset: u64) -> Result<(), std::io::Error> {
     #[cfg(unix)]
     {
@@ -0,0 +1,78 @@
+use crate::headers::signal_header::SignalHeader;
+use crate::resample::gcd;
+
+/// The value written into every sample of the data-records filling a gap
+#[derive(Debug, Default, Clone, PartialEq)]
+pub enum GapFillValue {
+    /// Every sample is set to a digital value of 0
+    #[default]
+    Zero,
+
+    /// Every sample is set to the digital minimum of its signal, which allows viewers to distinguish the
+    /// filled gaps from recorded data
+    DigitalMinimum,
+
+    /// Every sample is set to the given digital value, clamped to the digital range of its signal
+    Digital(i32),
+}
+
+impl GapFillValue {
+    /// Returns the digital value the samples of the given signal are filled with
+    pub fn digital_value(&self, signal: &SignalHeader) -> i32 {
+        let min = signal.digital_minimum.min(signal.digital_maximum);
+        let max = signal.digital_minimum.max(signal.digital_maximum);
+        match self {
+            Self::Zero => 0.clamp(min, max),
+            Self::DigitalMinimum => signal.digital_minimum,
+            Self::Digital(value) => (*value).clamp(min, max),
+        }
+    }
+}
+
+/// Determines how gaps are treated which are not a whole multiple of the data-record duration
+#[derive(Debug, Default, Clone, PartialEq)]
+pub enum GapStrategy {
+    /// Filling the gaps fails with `EDFError::UnalignedGap` in case any gap is not a whole multiple of the
+    /// data-record duration
+    #[default]
+    Strict,
+
+    /// The file is re-blocked to the largest data-record duration all gaps are a whole multiple of while filling
+    /// the gaps. This keeps the timing of all data-records, but fails with `EDFError::InvalidRecordDuration` in case
+    /// the signals can not be represented using that data-record duration (see `EDFFile::reblock`)
+    Reblock,
+
+    /// Gaps are extended to the next whole multiple of the data-record duration. This shifts all subsequent
+    /// data-records (including their annotations) towards the end of the recording by the added duration
+    Pad,
+}
+
+/// Options for filling the gaps of a discontinuous file using `EDFFile::fill_gaps`
+#[derive(Debug, Clone, PartialEq)]
+pub struct GapFillOptions {
+    /// The value every sample of the added data-records is set to
+    pub fill_value: GapFillValue,
+
+    /// The text of the annotation marking every filled gap. The annotation spans the entire gap and is stored in the
+    /// first data-record filling it. No annotation is added in case this is `None`
+    pub annotation: Option<String>,
+
+    /// How gaps which are not a whole multiple of the data-record duration are treated
+    pub strategy: GapStrategy,
+}
+
+impl Default for GapFillOptions {
+    fn default() -> Self {
+        Self {
+            fill_value: GapFillValue::default(),
+            annotation: Some("Gap".to_string()),
+            strategy: GapStrategy::default(),
+        }
+    }
+}
+
+/// Returns the largest duration (in nanoseconds) both the given data-record duration and all given gap durations are a
+/// whole multiple of
+pub(crate) fn common_duration_ns(record_duration_ns: u64, gaps_ns: &[u64]) -> u64 {
+    gaps_ns.iter().fold(record_duration_ns, |divisor, gap| gcd(divisor as usize, *gap as usize) as u64)
+}
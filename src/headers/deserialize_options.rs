@@ -0,0 +1,143 @@
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+
+/// Describes how strictly the header of a file is checked against the specification while deserializing it
+#[derive(Debug, Default, Clone, PartialEq)]
+pub enum DeserializeMode {
+    /// Every violation of the specification results in an error
+    #[default]
+    Strict,
+
+    /// Common violations of the specification found in files of various vendors are fixed while deserializing the
+    /// header and reported as warnings instead. Saving the file afterwards writes a header conforming to the specification
+    Lenient,
+}
+
+/// The options used to deserialize the header of a file
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct DeserializeOptions {
+    pub mode: DeserializeMode,
+}
+
+impl DeserializeOptions {
+    pub fn strict() -> Self {
+        Self { mode: DeserializeMode::Strict }
+    }
+
+    pub fn lenient() -> Self {
+        Self { mode: DeserializeMode::Lenient }
+    }
+
+    pub fn is_lenient(&self) -> bool {
+        self.mode == DeserializeMode::Lenient
+    }
+}
+
+/// The kind of violation of the specification which was fixed while deserializing a header in lenient mode
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeserializeWarningKind {
+    /// A number used a comma as decimal separator
+    DecimalComma,
+
+    /// A required field was empty and was replaced by its default value
+    EmptyField,
+
+    /// The EDF+ patient or recording identification had less subfields than required. The available subfields are
+    /// parsed in order, while all missing subfields are unknown
+    MissingSubfields,
+
+    /// A subfield of the EDF+ patient or recording identification could not be parsed and is unknown instead
+    InvalidSubfield,
+
+    /// A date or time did not use the format required by the specification (e.g. a 4 digit year in the start date or a
+    /// lowercase month name in the EDF+ patient or recording identification)
+    DateFormat,
+
+    /// A text field contained characters which are not printable ASCII characters, which were replaced by `?`
+    NonASCII,
+}
+
+/// A violation of the specification which was fixed while deserializing a header in lenient mode
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeserializeWarning {
+    pub kind: DeserializeWarningKind,
+
+    /// The name of the header field (e.g. `physical_minimum` or `patient_id`)
+    pub field: &'static str,
+
+    /// The index of the signal in case the field is part of a signal header
+    pub signal: Option<usize>,
+
+    /// The value of the field as it is stored in the file
+    pub value: String,
+}
+
+impl DeserializeWarning {
+    pub(crate) fn new(kind: DeserializeWarningKind, field: &'static str, signal: Option<usize>, value: &str) -> Self {
+        Self {
+            kind,
+            field,
+            signal,
+            value: value.to_string(),
+        }
+    }
+}
+
+/// Parses the given number, where a comma used as decimal separator is accepted in lenient mode
+pub(crate) fn parse_number<T: FromStr>(
+    value: &str,
+    field: &'static str,
+    signal: Option<usize>,
+    options: &DeserializeOptions,
+    warnings: &mut Vec<DeserializeWarning>,
+) -> Option<T> {
+    if let Ok(number) = T::from_str(value) {
+        return Some(number);
+    }
+    if !options.is_lenient() || !value.contains(',') {
+        return None;
+    }
+
+    let number = T::from_str(&value.replace(',', ".")).ok()?;
+    warnings.push(DeserializeWarning::new(DeserializeWarningKind::DecimalComma, field, signal, value));
+    Some(number)
+}
+
+/// Replaces all characters of the given text which are not printable ASCII characters with `?` in lenient mode
+pub(crate) fn sanitize_text(
+    value: String,
+    field: &'static str,
+    signal: Option<usize>,
+    options: &DeserializeOptions,
+    warnings: &mut Vec<DeserializeWarning>,
+) -> String {
+    if !options.is_lenient() || value.chars().all(|c| matches!(c, ' '..='~')) {
+        return value;
+    }
+
+    warnings.push(DeserializeWarning::new(DeserializeWarningKind::NonASCII, field, signal, &value));
+    value.chars().map(|c| if matches!(c, ' '..='~') { c } else { '?' }).collect()
+}
+
+/// Parses a date of the EDF+ patient or recording identification using the format dd-MMM-yyyy, where the month name is
+/// matched case-insensitively. In lenient mode, 2 digit years are accepted as well, where years up to 68 are in the 21st
+/// century and all others in the 20th century. Month names which are not uppercase as required by the specification are
+/// reported as warning in lenient mode, as the header is rewritten using uppercase month names
+pub(crate) fn parse_subfield_date(
+    value: &str,
+    field: &'static str,
+    options: &DeserializeOptions,
+    warnings: &mut Vec<DeserializeWarning>,
+) -> Option<NaiveDate> {
+    let has_short_year = value.rsplit('-').next().is_some_and(|year| year.len() == 2);
+    let has_uppercase_month = !value.chars().any(|c| c.is_ascii_lowercase());
+    if !options.is_lenient() || (!has_short_year && has_uppercase_month) {
+        return NaiveDate::parse_from_str(value, "%d-%b-%Y").ok();
+    }
+
+    let format = if has_short_year { "%d-%b-%y" } else { "%d-%b-%Y" };
+    let date = NaiveDate::parse_from_str(value, format).ok()?;
+    warnings.push(DeserializeWarning::new(DeserializeWarningKind::DateFormat, field, None, value));
+    Some(date)
+}
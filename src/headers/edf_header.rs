@@ -5,6 +5,7 @@ use std::str::FromStr;
 
 use crate::EDFSpecifications;
 use crate::error::edf_error::EDFError;
+use crate::headers::deserialize_options::{DeserializeOptions, DeserializeWarning, DeserializeWarningKind, parse_number, sanitize_text};
 use crate::headers::patient::PatientId;
 use crate::headers::recording::RecordingId;
 use crate::headers::signal_header::SignalHeader;
@@ -26,12 +27,16 @@ pub struct EDFHeader {
     pub(crate) signal_count: usize,
     pub(crate) signals: Vec<SignalHeader>,
     pub(crate) updated_signals: Option<Vec<SignalHeader>>,
+    pub(crate) updated_specification: Option<EDFSpecifications>,
 
     initial_record_size: usize,
     initial_record_hash: String,
 
-    #[allow(dead_code)]
-    reserved: String,
+    /// Whether or not the header stored on disk differs from its parsed values (e.g. in case it was parsed leniently)
+    /// and therefore has to be written when saving, regardless of any changes
+    header_needs_rewrite: bool,
+
+    pub(crate) reserved: String,
 }
 
 impl EDFHeader {
@@ -39,6 +44,7 @@ impl EDFHeader {
         Self {
             version: "0".to_string(),
             updated_signals: None,
+            updated_specification: None,
             ..Default::default()
         }
     }
@@ -116,7 +122,7 @@ impl EDFHeader {
     }
 
     pub fn get_specification(&self) -> EDFSpecifications {
-        self.specification.clone()
+        self.updated_specification.as_ref().unwrap_or(&self.specification).clone()
     }
 
     pub fn is_continuous(&self) -> bool {
@@ -182,11 +188,35 @@ impl EDFHeader {
     /// be called after the file was saved and the header has changed on disk. This value is only required for
     /// saving files to check whether or not the value of the header has changed.
     pub(crate) fn update_initial_header_sha256(&mut self) -> Result<(), EDFError> {
+        self.header_needs_rewrite = false;
         Ok(self.initial_record_hash = self.get_sha256()?)
     }
 
+    /// Returns whether or not the header has to be written when saving, as the header stored on disk differs from
+    /// its parsed values (e.g. in case it was parsed leniently)
+    pub(crate) fn needs_rewrite(&self) -> bool {
+        self.header_needs_rewrite
+    }
+
     pub fn create_record(&self) -> Record {
-        Record::new(self.updated_signals.as_ref().unwrap_or(&self.signals), &self.specification)
+        Record::new(
+            self.updated_signals.as_ref().unwrap_or(&self.signals),
+            self.updated_specification.as_ref().unwrap_or(&self.specification)
+        )
+    }
+
+    /// Parses the free text patient and recording identifications of plain EDF (or BDF) files into their EDF+ subfields
+    /// (see `PatientId::from_legacy` and `RecordingId::from_legacy`). Identifications which already use the EDF+
+    /// subfields are kept as they are.
+    pub(crate) fn upgrade_ids_to_plus(&mut self) {
+        if self.patient_id.is_legacy() {
+            let value = self.patient_id.name.clone().unwrap_or_default();
+            self.patient_id = PatientId::from_legacy(&value);
+        }
+        if self.recording_id.is_legacy() {
+            let value = self.recording_id.admin_code.clone().unwrap_or_default();
+            self.recording_id = RecordingId::from_legacy(&value, self.get_start_date());
+        }
     }
 
     pub(crate) fn modify_signals(&mut self) -> &mut Vec<SignalHeader> {
@@ -208,23 +238,25 @@ impl EDFHeader {
         }
     }
 
+    /// Returns the value of the reserved field matching the specification and continuity of the file
+    pub(crate) fn serialize_reserved(&self) -> &'static str {
+        match self.specification {
+            EDFSpecifications::EDF => "",
+            EDFSpecifications::EDFPlus if self.is_continuous => "EDF+C",
+            EDFSpecifications::EDFPlus => "EDF+D",
+            EDFSpecifications::BDF => "24BIT",
+            EDFSpecifications::BDFPlus if self.is_continuous => "BDF+C",
+            EDFSpecifications::BDFPlus => "BDF+D",
+        }
+    }
+
     pub fn serialize(&self) -> Result<Vec<u8>, EDFError> {
         let version = self.serialize_version()?;
         let user_id = pad_string(&self.patient_id.serialize(&self.specification)?, 80)?;
         let recording_id = pad_string(&self.recording_id.serialize(&self.specification)?, 80)?;
         let start_date = pad_string(&Self::serialize_old_start_date(&self.start_date), 8)?;
         let start_time = pad_string(&self.start_time.format("%H.%M.%S").to_string(), 8)?;
-        let reserved = pad_string(
-            match self.specification {
-                EDFSpecifications::EDF => "",
-                EDFSpecifications::EDFPlus if self.is_continuous => "EDF+C",
-                EDFSpecifications::EDFPlus => "EDF+D",
-                EDFSpecifications::BDF => "24BIT",
-                EDFSpecifications::BDFPlus if self.is_continuous => "BDF+C",
-                EDFSpecifications::BDFPlus => "BDF+D",
-            },
-            44,
-        )?;
+        let reserved = pad_string(self.serialize_reserved(), 44)?;
         let record_count = pad_string(
             &self
                 .record_count
@@ -326,52 +358,44 @@ impl EDFHeader {
     }
 
     pub fn deserialize<R: BufRead + Seek>(reader: &mut R) -> Result<Self, EDFError> {
+        Self::deserialize_with_options(reader, &DeserializeOptions::strict()).map(|(header, _)| header)
+    }
+
+    /// Deserializes the header like `deserialize`, using the given options. In lenient mode, common violations of the
+    /// specification are fixed instead of resulting in an error. Returns the header together with a warning for every fixed violation.
+    pub fn deserialize_with_options<R: BufRead + Seek>(
+        reader: &mut R,
+        options: &DeserializeOptions,
+    ) -> Result<(Self, Vec<DeserializeWarning>), EDFError> {
+        let mut warnings = Vec::new();
+
         // Immediately seek to the reserved location of the header to get the specification
         reader
             .seek(SeekFrom::Start(192))
             .map_err(EDFError::FileReadError)?;
         let reserved = read_ascii(reader, 44)?;
 
-        // Distinguish between EDF/BDF and EDF+/BDF+ specifications
-        let is_continuous_edfplus = reserved.starts_with("EDF+C");
-        let is_discontinuous_edfplus = reserved.starts_with("EDF+D");
-
-        let is_biosemi = reserved.starts_with("24BIT");
-        let is_continuous_bdfplus = reserved.starts_with("BDF+C");
-        let is_discontinuous_bdfplus = reserved.starts_with("BDF+D");
-
-        let is_bdf = is_biosemi || is_continuous_bdfplus || is_discontinuous_bdfplus;
-        let is_plus = is_continuous_edfplus || is_discontinuous_edfplus || is_continuous_bdfplus || is_discontinuous_bdfplus;
-
-        let specification = if is_bdf && is_plus {
-            EDFSpecifications::BDFPlus
-        } else if is_bdf {
-            EDFSpecifications::BDF
-        } else if is_plus {
-            EDFSpecifications::EDFPlus
-        } else {
-            EDFSpecifications::EDF
-        };
-
-        // Check if data is expected to be continuous based on header
-        let is_continuous = is_continuous_edfplus || is_continuous_bdfplus || !is_plus;
+        let (specification, is_continuous) = Self::parse_reserved(&reserved);
 
         // Seek back to the beginning of the file and parse general header values
         reader
             .seek(SeekFrom::Start(0))
             .map_err(EDFError::FileReadError)?;
         let version = read_version(reader, &specification)?;
-        let patient_id = PatientId::deserialize(
-            read_ascii(reader, 80)?.trim_ascii_end().to_string(),
+        let patient_id = PatientId::deserialize_with_options(
+            read_text(reader, 80, "patient_id", None, options, &mut warnings)?,
             &specification,
+            options,
+            &mut warnings,
         )?;
-        let recording_id = RecordingId::deserialize(
-            read_ascii(reader, 80)?.trim_ascii_end().to_string(),
+        let recording_id = RecordingId::deserialize_with_options(
+            read_text(reader, 80, "recording_id", None, options, &mut warnings)?,
             &specification,
+            options,
+            &mut warnings,
         )?;
-        let start_date = Self::parse_old_start_date(&read_ascii(reader, 8)?)?;
-        let start_time = NaiveTime::parse_from_str(&read_ascii(reader, 8)?, "%H.%M.%S")
-            .map_err(|_| EDFError::InvalidStartTime)?;
+        let start_date = Self::parse_start_date_with_options(&read_ascii(reader, 8)?, options, &mut warnings)?;
+        let start_time = Self::parse_start_time_with_options(&read_ascii(reader, 8)?, options, &mut warnings)?;
         let header_bytes = usize::from_str(&read_ascii(reader, 8)?.trim_ascii_end())
             .map_err(|_| EDFError::InvalidHeaderSize)?;
 
@@ -385,55 +409,53 @@ impl EDFHeader {
         // Duration in seconds
         //   EDF/EDF+: Should be a whole number, except if the data-record size would exceed 61440 bytes. Then it should be smaller e.g. 0.01
         //   BDF/BDF+: Can be anything above 0, the max data-record size is 15 MB.
-        let record_duration = f64::from_str(&read_ascii(reader, 8)?.trim_ascii_end())
-            .map_err(|_| EDFError::InvalidRecordDuration)?;
+        let record_duration = parse_number(read_ascii(reader, 8)?.trim_ascii_end(), "record_duration", None, options, &mut warnings)
+            .ok_or(EDFError::InvalidRecordDuration)?;
         let signal_count = usize::from_str(&read_ascii(reader, 4)?.trim_ascii_end())
             .map_err(|_| EDFError::InvalidSignalCount)?;
 
         let mut signals = vec![SignalHeader::default(); signal_count];
 
         // Get labels
-        for signal in &mut signals {
-            signal.label = read_ascii(reader, 16)?.trim_ascii_end().to_string();
+        for (idx, signal) in signals.iter_mut().enumerate() {
+            signal.label = read_text(reader, 16, "label", Some(idx), options, &mut warnings)?;
         }
 
         // Get transducers
-        for signal in &mut signals {
-            signal.transducer = read_ascii(reader, 80)?.trim_ascii_end().to_string();
+        for (idx, signal) in signals.iter_mut().enumerate() {
+            signal.transducer = read_text(reader, 80, "transducer", Some(idx), options, &mut warnings)?;
         }
 
         // Get physical dimensions
-        for signal in &mut signals {
-            signal.physical_dimension = read_ascii(reader, 8)?.trim_ascii_end().to_string();
+        for (idx, signal) in signals.iter_mut().enumerate() {
+            signal.physical_dimension = read_text(reader, 8, "physical_dimension", Some(idx), options, &mut warnings)?;
         }
 
         // Get physical minimum
-        for signal in &mut signals {
-            signal.physical_minimum = f64::from_str(&read_ascii(reader, 8)?.trim_ascii_end())
-                .map_err(|_| EDFError::InvalidPhysicalRange)?;
+        for (idx, signal) in signals.iter_mut().enumerate() {
+            signal.physical_minimum = parse_number(read_ascii(reader, 8)?.trim_ascii_end(), "physical_minimum", Some(idx), options, &mut warnings)
+                .ok_or(EDFError::InvalidPhysicalRange)?;
         }
 
         // Get physical maximum
-        for signal in &mut signals {
-            signal.physical_maximum = f64::from_str(&read_ascii(reader, 8)?.trim_ascii_end())
-                .map_err(|_| EDFError::InvalidPhysicalRange)?;
+        for (idx, signal) in signals.iter_mut().enumerate() {
+            signal.physical_maximum = parse_number(read_ascii(reader, 8)?.trim_ascii_end(), "physical_maximum", Some(idx), options, &mut warnings)
+                .ok_or(EDFError::InvalidPhysicalRange)?;
         }
 
         // Get digital minimum
-        for signal in &mut signals {
-            signal.digital_minimum = i32::from_str(&read_ascii(reader, 8)?.trim_ascii_end())
-                .map_err(|_| EDFError::InvalidPhysicalRange)?;
+        for (idx, signal) in signals.iter_mut().enumerate() {
+            signal.digital_minimum = Self::parse_digital_with_options(&read_ascii(reader, 8)?, "digital_minimum", idx, specification.digital_range().0, options, &mut warnings)?;
         }
 
         // Get digital maximum
-        for signal in &mut signals {
-            signal.digital_maximum = i32::from_str(&read_ascii(reader, 8)?.trim_ascii_end())
-                .map_err(|_| EDFError::InvalidPhysicalRange)?;
+        for (idx, signal) in signals.iter_mut().enumerate() {
+            signal.digital_maximum = Self::parse_digital_with_options(&read_ascii(reader, 8)?, "digital_maximum", idx, specification.digital_range().1, options, &mut warnings)?;
         }
 
         // Get pre-filters
-        for signal in &mut signals {
-            signal.prefilter = read_ascii(reader, 80)?.trim_ascii_end().to_string();
+        for (idx, signal) in signals.iter_mut().enumerate() {
+            signal.prefilter = read_text(reader, 80, "prefilter", Some(idx), options, &mut warnings)?;
         }
 
         // Get sample count per record
@@ -443,8 +465,8 @@ impl EDFHeader {
         }
 
         // Get reserved fields
-        for signal in &mut signals {
-            signal.reserved = read_ascii(reader, 32)?.trim_ascii_end().to_string();
+        for (idx, signal) in signals.iter_mut().enumerate() {
+            signal.reserved = read_text(reader, 32, "reserved", Some(idx), options, &mut warnings)?;
         }
 
         let mut header = Self {
@@ -463,14 +485,46 @@ impl EDFHeader {
             signals,
             initial_record_size: 0,
             initial_record_hash: String::new(),
+            header_needs_rewrite: !warnings.is_empty(),
             updated_signals: None,
+            updated_specification: None,
         };
 
         // Get the hash of the header value to check for changes on save later
         header.initial_record_hash = header.get_sha256()?;
         header.update_initial_record_bytes();
 
-        Ok(header)
+        Ok((header, warnings))
+    }
+
+    /// Returns the specification and whether or not the data-records are expected to be continuous based on the
+    /// reserved field of the header
+    pub(crate) fn parse_reserved(reserved: &str) -> (EDFSpecifications, bool) {
+        // Distinguish between EDF/BDF and EDF+/BDF+ specifications
+        let is_continuous_edfplus = reserved.starts_with("EDF+C");
+        let is_discontinuous_edfplus = reserved.starts_with("EDF+D");
+
+        let is_biosemi = reserved.starts_with("24BIT");
+        let is_continuous_bdfplus = reserved.starts_with("BDF+C");
+        let is_discontinuous_bdfplus = reserved.starts_with("BDF+D");
+
+        let is_bdf = is_biosemi || is_continuous_bdfplus || is_discontinuous_bdfplus;
+        let is_plus = is_continuous_edfplus || is_discontinuous_edfplus || is_continuous_bdfplus || is_discontinuous_bdfplus;
+
+        let specification = if is_bdf && is_plus {
+            EDFSpecifications::BDFPlus
+        } else if is_bdf {
+            EDFSpecifications::BDF
+        } else if is_plus {
+            EDFSpecifications::EDFPlus
+        } else {
+            EDFSpecifications::EDF
+        };
+
+        // Check if data is expected to be continuous based on header
+        let is_continuous = is_continuous_edfplus || is_continuous_bdfplus || !is_plus;
+
+        (specification, is_continuous)
     }
 
     /// Serializes the header of the EDF file and calculates a SHA256 hash and returns the result
@@ -525,6 +579,67 @@ impl EDFHeader {
         NaiveDate::parse_from_str(&parsed_year, "%d.%m.%Y").map_err(|_| EDFError::InvalidStartDate)
     }
 
+    /// Parses the old style start date like `parse_old_start_date`. In lenient mode, the separators `-` and `/` as well as
+    /// 4 digit years are accepted too (e.g. `1.2.2026`)
+    fn parse_start_date_with_options(
+        date: &str,
+        options: &DeserializeOptions,
+        warnings: &mut Vec<DeserializeWarning>,
+    ) -> Result<NaiveDate, EDFError> {
+        let error = match Self::parse_old_start_date(date) {
+            Ok(date) => return Ok(date),
+            Err(err) if !options.is_lenient() => return Err(err),
+            Err(err) => err,
+        };
+
+        let normalized = date.trim().replace(['-', '/'], ".");
+        let parsed = match normalized.rsplit('.').next() {
+            Some(year) if year.len() == 4 => NaiveDate::parse_from_str(&normalized, "%d.%m.%Y").map_err(|_| error),
+            _ => Self::parse_old_start_date(&normalized),
+        }?;
+        warnings.push(DeserializeWarning::new(DeserializeWarningKind::DateFormat, "start_date", None, date));
+
+        Ok(parsed)
+    }
+
+    /// Parses the start time in the format hh.mm.ss. In lenient mode, the separator `:` is accepted too
+    fn parse_start_time_with_options(
+        time: &str,
+        options: &DeserializeOptions,
+        warnings: &mut Vec<DeserializeWarning>,
+    ) -> Result<NaiveTime, EDFError> {
+        if let Ok(parsed) = NaiveTime::parse_from_str(time, "%H.%M.%S") {
+            return Ok(parsed);
+        }
+        if !options.is_lenient() {
+            return Err(EDFError::InvalidStartTime);
+        }
+
+        let parsed = NaiveTime::parse_from_str(time.trim(), "%H:%M:%S").map_err(|_| EDFError::InvalidStartTime)?;
+        warnings.push(DeserializeWarning::new(DeserializeWarningKind::DateFormat, "start_time", None, time));
+
+        Ok(parsed)
+    }
+
+    /// Parses the digital minimum or maximum of a signal. In lenient mode, an empty value is replaced by the given
+    /// default value (the smallest or largest value supported by the specification)
+    fn parse_digital_with_options(
+        value: &str,
+        field: &'static str,
+        signal: usize,
+        default: i32,
+        options: &DeserializeOptions,
+        warnings: &mut Vec<DeserializeWarning>,
+    ) -> Result<i32, EDFError> {
+        let value = value.trim_ascii_end();
+        if value.is_empty() && options.is_lenient() {
+            warnings.push(DeserializeWarning::new(DeserializeWarningKind::EmptyField, field, Some(signal), value));
+            return Ok(default);
+        }
+
+        i32::from_str(value).map_err(|_| EDFError::InvalidPhysicalRange)
+    }
+
     /// Returns the serialized old style date with clipping year 1985. When the year is later than 2084, the expected
     /// output year is the string 'yy'. The output format will be dd.mm.yy
     pub fn serialize_old_start_date(date: &NaiveDate) -> String {
@@ -567,6 +682,20 @@ pub fn read_ascii<'a, R: BufRead>(reader: &'a mut R, count: usize) -> Result<Str
     Ok(buf.iter().map(|c| *c as char).collect())
 }
 
+/// Reads a text field of the given size without its trailing spaces. In lenient mode, characters which are not
+/// printable ASCII characters are replaced by `?`
+fn read_text<R: BufRead>(
+    reader: &mut R,
+    count: usize,
+    field: &'static str,
+    signal: Option<usize>,
+    options: &DeserializeOptions,
+    warnings: &mut Vec<DeserializeWarning>,
+) -> Result<String, EDFError> {
+    let value = read_ascii(reader, count)?.trim_ascii_end().to_string();
+    Ok(sanitize_text(value, field, signal, options, warnings))
+}
+
 fn pad_string(value: &str, size: usize) -> Result<String, EDFError> {
     if value.len() > size {
         return Err(EDFError::FieldSizeExceeded);
@@ -671,7 +800,9 @@ mod tests {
             reserved: "EDF+C                                       ".to_string(),
             initial_record_size: 30646,
             updated_signals: None,
+            updated_specification: None,
             initial_record_hash: String::new(),
+            header_needs_rewrite: false,
         };
         assert!(expected.update_initial_header_sha256().is_ok());
         assert!(value.is_ok());
@@ -679,4 +810,76 @@ mod tests {
         assert_eq!(value, expected);
         assert_eq!(value.serialize().unwrap(), test_header.into_bytes());
     }
+
+    #[test]
+    fn deserialize_lenient() {
+        let test_header = "0       MCH-0234567 F                                                                   Startdate 16-SEP-1987 PSG-1234/1987 NN Telemetry03                              16.09.8720.35.001024    EDF+C                                       2880    30      3   EEG Fpz-Cz      Temp r\u{e9}ctal     EDF Annotations AgAgCl cup electrodes                                                           Rectal thermistor                                                                                                                                               uV      degC            -440,5  34.4    -1      510     40.2    1               -2048   -32768  2047    2047    32767   HP:0.1Hz LP:75Hz N:50Hz                                                         LP:0.1Hz (first order)                                                                                                                                          15000   3       320     Reserved for EEG signal         Reserved for Body temperature                                   ";
+        let to_bytes = |header: &str| header.chars().map(|c| c as u8).collect::<Vec<_>>();
+        let lenient_header = test_header;
+        let test_header = to_bytes(test_header);
+
+        // Strict mode rejects the non-conformant header
+        let mut reader = BufReader::new(Cursor::new(&test_header));
+        assert!(EDFHeader::deserialize(&mut reader).is_err());
+
+        // Lenient mode fixes every violation and reports it
+        let mut reader = BufReader::new(Cursor::new(&test_header));
+        let value = EDFHeader::deserialize_with_options(&mut reader, &DeserializeOptions::lenient());
+        assert!(value.is_ok());
+        let (header, warnings) = value.unwrap();
+        let kinds = warnings.iter().map(|warning| (warning.kind.clone(), warning.field, warning.signal)).collect::<Vec<_>>();
+        assert_eq!(kinds, vec![
+            (DeserializeWarningKind::MissingSubfields, "patient_id", None),
+            (DeserializeWarningKind::NonASCII, "label", Some(1)),
+            (DeserializeWarningKind::DecimalComma, "physical_minimum", Some(0)),
+            (DeserializeWarningKind::EmptyField, "digital_minimum", Some(0)),
+        ]);
+        assert_eq!(header.patient_id.code, Some("MCH-0234567".to_string()));
+        assert_eq!(header.patient_id.sex, Some(Sex::Female));
+        assert_eq!(header.patient_id.name, None);
+        assert_eq!(header.signals[1].label, "Temp r?ctal");
+        assert_eq!(header.signals[0].physical_minimum, -440.5);
+        assert_eq!(header.signals[0].digital_minimum, -32768);
+        assert!(header.serialize().is_ok());
+
+        // The fixed header has to be written when saving, while keeping the hash identifying the parsed header
+        assert!(header.needs_rewrite());
+        assert_eq!(*header.get_initial_header_sha256(), header.get_sha256().unwrap());
+
+        // Dates and times using other separators, 4 digit years or lowercase month names are fixed as well
+        let deserialize = |from: &str, to: &str, options: &DeserializeOptions| {
+            let test_header = to_bytes(&lenient_header.replace(from, to));
+            let mut reader = BufReader::new(Cursor::new(&test_header));
+            EDFHeader::deserialize_with_options(&mut reader, options)
+        };
+        let date_formats = [
+            ("16/09/8720:35:00", vec![("start_date", "16/09/87"), ("start_time", "20:35:00")]),
+            ("16-09-8720.35.00", vec![("start_date", "16-09-87")]),
+            ("6.9.198720.35.00", vec![("start_date", "6.9.1987")]),
+        ];
+        let date_warnings = |warnings: &[DeserializeWarning]| {
+            warnings
+                .iter()
+                .filter(|warning| warning.kind == DeserializeWarningKind::DateFormat)
+                .map(|warning| (warning.field, warning.value.clone()))
+                .collect::<Vec<_>>()
+        };
+        for (date_time, expected) in date_formats {
+            assert!(deserialize("16.09.8720.35.00", date_time, &DeserializeOptions::strict()).is_err());
+            let (header, warnings) = deserialize("16.09.8720.35.00", date_time, &DeserializeOptions::lenient()).unwrap();
+            let expected = expected.into_iter().map(|(field, value)| (field, value.to_string())).collect::<Vec<_>>();
+            assert_eq!(date_warnings(&warnings), expected);
+            assert_eq!(header.start_time, NaiveTime::from_hms_opt(20, 35, 0).unwrap());
+            assert!(header.needs_rewrite());
+        }
+        let (header, _) = deserialize("16.09.8720.35.00", "6.9.198720.35.00", &DeserializeOptions::lenient()).unwrap();
+        assert_eq!(header.start_date, NaiveDate::from_ymd_opt(1987, 9, 6).unwrap());
+
+        // Lowercase month names are parsed case-insensitively, but reported as the header is rewritten using uppercase
+        let (header, warnings) = deserialize("Startdate 16-SEP-1987", "Startdate 16-sep-1987", &DeserializeOptions::lenient()).unwrap();
+        assert_eq!(date_warnings(&warnings), vec![("recording_id", "16-sep-1987".to_string())]);
+        assert_eq!(header.recording_id.startdate, NaiveDate::from_ymd_opt(1987, 9, 16));
+        assert!(header.needs_rewrite());
+        assert!(String::from_utf8(header.serialize().unwrap()).unwrap().contains("Startdate 16-SEP-1987"));
+    }
 }
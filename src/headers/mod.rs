@@ -1,4 +1,5 @@
 pub mod annotation_list;
+pub mod deserialize_options;
 pub mod edf_header;
 pub mod patient;
 pub mod recording;
@@ -4,6 +4,7 @@ use std::str::FromStr;
 
 use crate::EDFSpecifications;
 use crate::error::edf_error::EDFError;
+use crate::headers::deserialize_options::{DeserializeOptions, DeserializeWarning, DeserializeWarningKind, parse_subfield_date};
 use crate::utils::{deserialize_field, is_printable_ascii, serialize_field};
 
 #[derive(Debug, Default, Clone, PartialEq)]
@@ -16,27 +17,58 @@ pub struct PatientId {
 }
 
 impl PatientId {
-    // TODO: This could be a deserializer option to try and parse the individual parts anyways
     /// Deserializes the provided user identification to the parsed struct. If the file type
     /// is not compatible with the EDF+ specification, the fields value will be stored in its
     /// entirety within the `name` field. This is to prevent splitting the field in a potentially
     /// undesired way.
     pub fn deserialize(value: String, spec: &EDFSpecifications) -> Result<Self, EDFError> {
+        Self::deserialize_with_options(value, spec, &DeserializeOptions::strict(), &mut Vec::new())
+    }
+
+    /// Deserializes the provided user identification like `deserialize`. In lenient mode, EDF+ user identifications
+    /// with less than 4 subfields are parsed anyways and subfields which can not be parsed are unknown instead. Every
+    /// fixed violation is added to the given warnings.
+    pub fn deserialize_with_options(
+        value: String,
+        spec: &EDFSpecifications,
+        options: &DeserializeOptions,
+        warnings: &mut Vec<DeserializeWarning>,
+    ) -> Result<Self, EDFError> {
         let parts = value.split_ascii_whitespace().collect::<Vec<_>>();
 
         // Parse user id based on EDF+ spec if it is valid
-        if (*spec == EDFSpecifications::EDFPlus || *spec == EDFSpecifications::BDFPlus) && parts.len() >= 4 {
+        if spec.is_plus() && (parts.len() >= 4 || options.is_lenient()) {
+            if parts.len() < 4 {
+                warnings.push(DeserializeWarning::new(DeserializeWarningKind::MissingSubfields, "patient_id", None, &value));
+            }
+            let part = |idx: usize| parts.get(idx).copied().and_then(deserialize_field);
+
+            let sex = match part(1).map(|v| Sex::from_str(&v)).transpose() {
+                Ok(sex) => sex,
+                Err(_) if options.is_lenient() => {
+                    warnings.push(DeserializeWarning::new(DeserializeWarningKind::InvalidSubfield, "patient_id", None, parts[1]));
+                    None
+                }
+                Err(err) => return Err(err),
+            };
+            let date = match part(2) {
+                Some(date) => match parse_subfield_date(&date, "patient_id", options, warnings) {
+                    Some(date) => Some(date),
+                    None if options.is_lenient() => {
+                        warnings.push(DeserializeWarning::new(DeserializeWarningKind::InvalidSubfield, "patient_id", None, &date));
+                        None
+                    }
+                    None => return Err(EDFError::InvalidUserIdDate),
+                },
+                None => None,
+            };
+
             return Ok(PatientId {
-                code: deserialize_field(parts[0]),
-                sex: deserialize_field(parts[1])
-                    .map(|v| Sex::from_str(&v))
-                    .transpose()?,
-                date: deserialize_field(parts[2])
-                    .map(|v| NaiveDate::parse_from_str(&v, "%d-%b-%Y"))
-                    .transpose()
-                    .map_err(|_| EDFError::InvalidUserIdDate)?,
-                name: deserialize_field(parts[3]),
-                additional: parts[4..].iter().cloned().map(deserialize_field).collect(),
+                code: part(0),
+                sex,
+                date,
+                name: part(3),
+                additional: parts.iter().skip(4).cloned().map(deserialize_field).collect(),
             });
         }
 
@@ -50,6 +82,26 @@ impl PatientId {
         Err(EDFError::InvalidUserIdSegmentCount)
     }
 
+    /// Parses the free text patient identification of a plain EDF or BDF file into the subfields of the EDF+
+    /// specification. In case the text does not follow the EDF+ structure, all subfields remain unknown and
+    /// the words of the text are kept as additional subfields.
+    pub fn from_legacy(value: &str) -> Self {
+        if let Ok(patient) = Self::deserialize(value.to_string(), &EDFSpecifications::EDFPlus) {
+            return patient;
+        }
+
+        PatientId {
+            additional: value.split_ascii_whitespace().map(deserialize_field).collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Returns whether or not the patient identification is the free text of a plain EDF or BDF file, which is
+    /// stored in its entirety within the `name` field
+    pub fn is_legacy(&self) -> bool {
+        self.code.is_none() && self.sex.is_none() && self.date.is_none() && self.additional.is_empty()
+    }
+
     pub fn serialize(&self, spec: &EDFSpecifications) -> Result<String, EDFError> {
         let value = match spec {
             EDFSpecifications::EDF | EDFSpecifications::BDF => self.name.clone().unwrap_or_default(),
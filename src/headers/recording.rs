@@ -2,6 +2,7 @@ use chrono::NaiveDate;
 
 use crate::EDFSpecifications;
 use crate::error::edf_error::EDFError;
+use crate::headers::deserialize_options::{DeserializeOptions, DeserializeWarning, DeserializeWarningKind, parse_subfield_date};
 use crate::utils::{deserialize_field, is_printable_ascii, serialize_field};
 
 #[derive(Debug, Default, Clone, PartialEq)]
@@ -14,28 +15,61 @@ pub struct RecordingId {
 }
 
 impl RecordingId {
-    // TODO: This could be a deserializer option to try and parse the individual parts anyways
     /// Deserializes the provided recording identification to the parsed struct. If the file type
     /// is not compatible with the EDF+ specification, the fields value will be stored in its
     /// entirety within the `admin_code` field. This is to prevent splitting the field in a potentially
     /// undesired way.
     pub fn deserialize(value: String, spec: &EDFSpecifications) -> Result<Self, EDFError> {
+        Self::deserialize_with_options(value, spec, &DeserializeOptions::strict(), &mut Vec::new())
+    }
+
+    /// Deserializes the provided recording identification like `deserialize`. In lenient mode, EDF+ recording
+    /// identifications with less than 5 subfields are parsed anyways and a start date which can not be parsed is unknown
+    /// instead. Recording identifications not starting with "Startdate" keep all of their words as additional subfields.
+    /// Every fixed violation is added to the given warnings.
+    pub fn deserialize_with_options(
+        value: String,
+        spec: &EDFSpecifications,
+        options: &DeserializeOptions,
+        warnings: &mut Vec<DeserializeWarning>,
+    ) -> Result<Self, EDFError> {
         let parts = value.split_ascii_whitespace().collect::<Vec<_>>();
+        let has_startdate = parts.first().is_some_and(|part| *part == "Startdate" || *part == "X");  // Some files indicate missing Startdate by replacing the text "Startdate" with "X"
 
         // Parse patient id based on EDF+ spec if it is valid
-        if (*spec == EDFSpecifications::EDFPlus || *spec == EDFSpecifications::BDFPlus) &&
-            parts.len() >= 5 &&
-            (parts[0] == "Startdate" || parts[0] == "X")    // Some files indicate missing Startdate by replacing the text "Startdate" with "X"
-        {
+        if spec.is_plus() && has_startdate && (parts.len() >= 5 || options.is_lenient()) {
+            if parts.len() < 5 {
+                warnings.push(DeserializeWarning::new(DeserializeWarningKind::MissingSubfields, "recording_id", None, &value));
+            }
+            let part = |idx: usize| parts.get(idx).copied().and_then(deserialize_field);
+
+            let startdate = match part(1) {
+                Some(date) => match parse_subfield_date(&date, "recording_id", options, warnings) {
+                    Some(date) => Some(date),
+                    None if options.is_lenient() => {
+                        warnings.push(DeserializeWarning::new(DeserializeWarningKind::InvalidSubfield, "recording_id", None, &date));
+                        None
+                    }
+                    None => return Err(EDFError::InvalidRecordingIdDate),
+                },
+                None => None,
+            };
+
+            return Ok(RecordingId {
+                startdate,
+                admin_code: part(2),
+                technician: part(3),
+                equipment: part(4),
+                additional: parts.iter().skip(5).cloned().map(deserialize_field).collect()
+            });
+        }
+
+        // Keep the words of recording ids without any of the EDF+ structure as additional subfields
+        if spec.is_plus() && options.is_lenient() {
+            warnings.push(DeserializeWarning::new(DeserializeWarningKind::MissingSubfields, "recording_id", None, &value));
             return Ok(RecordingId {
-                startdate: deserialize_field(parts[1])
-                    .map(|v| NaiveDate::parse_from_str(&v, "%d-%b-%Y"))
-                    .transpose()
-                    .map_err(|_| EDFError::InvalidRecordingIdDate)?,
-                admin_code: deserialize_field(parts[2]),
-                technician: deserialize_field(parts[3]),
-                equipment: deserialize_field(parts[4]),
-                additional: parts[5..].iter().cloned().map(deserialize_field).collect()
+                additional: parts.iter().cloned().map(deserialize_field).collect(),
+                ..Default::default()
             });
         }
 
@@ -49,6 +83,28 @@ impl RecordingId {
         Err(EDFError::InvalidRecordingIdSegmentCount)
     }
 
+    /// Parses the free text recording identification of a plain EDF or BDF file into the subfields of the EDF+
+    /// specification. In case the text does not follow the EDF+ structure, the given start date of the recording
+    /// is used, all other subfields remain unknown and the words of the text are kept as additional subfields.
+    pub fn from_legacy(value: &str, startdate: NaiveDate) -> Self {
+        if let Ok(mut recording) = Self::deserialize(value.to_string(), &EDFSpecifications::EDFPlus) {
+            recording.startdate = recording.startdate.or(Some(startdate));
+            return recording;
+        }
+
+        RecordingId {
+            startdate: Some(startdate),
+            additional: value.split_ascii_whitespace().map(deserialize_field).collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Returns whether or not the recording identification is the free text of a plain EDF or BDF file, which is
+    /// stored in its entirety within the `admin_code` field
+    pub fn is_legacy(&self) -> bool {
+        self.startdate.is_none() && self.technician.is_none() && self.equipment.is_none() && self.additional.is_empty()
+    }
+
     pub fn serialize(&self, spec: &EDFSpecifications) -> Result<String, EDFError> {
         let value = match spec {
             EDFSpecifications::EDF | EDFSpecifications::BDF => self.admin_code.clone().unwrap_or_default(),
@@ -26,10 +26,7 @@ impl SignalHeader {
             EDFSpecifications::BDF | EDFSpecifications::BDFPlus => "BDF",
         };
 
-        let digital_range = match specification {
-            EDFSpecifications::EDF | EDFSpecifications::EDFPlus => (-32768, 32767),
-            EDFSpecifications::BDF | EDFSpecifications::BDFPlus => (-8388608, 8388607),
-        };
+        let digital_range = specification.digital_range();
 
         let sample_bytes = match specification {
             EDFSpecifications::EDF | EDFSpecifications::EDFPlus => 2,
@@ -101,6 +98,33 @@ impl SignalHeader {
         }
     }
 
+    /// Returns the signal converted from the sample size of specification `from` to the sample size of specification `to`.
+    /// When widening 16 bit samples to 24 bit samples, the digital range is multiplied by 256. When narrowing 24 bit samples to
+    /// 16 bit samples, the digital range is divided by 256 in case it does not fit into 16 bits. The physical range remains the
+    /// same in both cases. Annotation signals are relabeled and keep at least the amount of bytes they were able to store before.
+    pub fn convert_specification(&self, from: &EDFSpecifications, to: &EDFSpecifications) -> SignalHeader {
+        let mut signal = self.clone();
+        if from.sample_bytes() == to.sample_bytes() {
+            return signal;
+        }
+
+        if self.is_annotation() {
+            let annotation = Self::new_annotation(0, to.clone());
+            signal.label = annotation.label;
+            signal.digital_minimum = annotation.digital_minimum;
+            signal.digital_maximum = annotation.digital_maximum;
+            signal.samples_count = (self.samples_count * from.sample_bytes()).div_ceil(to.sample_bytes());
+        } else if to.sample_bytes() > from.sample_bytes() {
+            signal.digital_minimum = self.digital_minimum * 256;
+            signal.digital_maximum = self.digital_maximum * 256;
+        } else if self.digital_minimum < i16::MIN as i32 || self.digital_maximum > i16::MAX as i32 {
+            signal.digital_minimum = (-(-self.digital_minimum).div_euclid(256)).max(i16::MIN as i32);
+            signal.digital_maximum = self.digital_maximum.div_euclid(256).min(i16::MAX as i32);
+        }
+
+        signal
+    }
+
     pub fn to_digital_samples<S: Into<i32> + Copy>(&self, samples: &Vec<S>) -> Vec<i32> {
         samples.iter().map(|sample| {
             (*sample).into().clamp(self.digital_minimum, self.digital_maximum)
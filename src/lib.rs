@@ -146,13 +146,25 @@ pub fn main() -> Result<(), Box<dyn std::error::Error>> {
 Further examples will be added in the future
 */
 
+pub mod anonymize;
 pub mod error;
+pub mod extract;
 pub mod file;
+pub mod gaps;
 pub mod headers;
+pub mod merge;
+pub mod order;
+pub mod progress;
 pub mod record;
+pub mod repair;
+pub mod resample;
+pub mod rescale;
 pub mod save;
+pub mod session;
+pub mod split;
 mod tests;
 pub mod utils;
+pub mod validate;
 
 #[derive(Debug, Default, Clone, PartialEq)]
 pub enum EDFSpecifications {
@@ -169,3 +181,26 @@ pub enum EDFSpecifications {
     /// The 24 bit version of the EDF+ format. See the specifications [here](https://www.teuniz.net/edfbrowser/bdfplus%20format%20description.html).
     BDFPlus,
 }
+
+impl EDFSpecifications {
+    /// Returns the amount of bytes a single sample takes up in a data-record
+    pub fn sample_bytes(&self) -> usize {
+        match self {
+            EDFSpecifications::EDF | EDFSpecifications::EDFPlus => 2,
+            EDFSpecifications::BDF | EDFSpecifications::BDFPlus => 3,
+        }
+    }
+
+    /// Returns the smallest and largest digital value a single sample is able to store
+    pub fn digital_range(&self) -> (i32, i32) {
+        match self {
+            EDFSpecifications::EDF | EDFSpecifications::EDFPlus => (-32768, 32767),
+            EDFSpecifications::BDF | EDFSpecifications::BDFPlus => (-8388608, 8388607),
+        }
+    }
+
+    /// Returns whether or not the specification is an extended one (EDF+ or BDF+) which supports annotations
+    pub fn is_plus(&self) -> bool {
+        matches!(self, EDFSpecifications::EDFPlus | EDFSpecifications::BDFPlus)
+    }
+}
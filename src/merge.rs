@@ -0,0 +1,236 @@
+use std::io::Write;
+use std::path::Path;
+
+use chrono::NaiveDateTime;
+
+use crate::EDFSpecifications;
+use crate::error::edf_error::EDFError;
+use crate::file::{EDFFile, create_new_file, round_nanos};
+use crate::headers::annotation_list::AnnotationList;
+use crate::headers::signal_header::SignalHeader;
+
+/// A difference between the header of a file and the header of the first file which prevents the files from being
+/// merged. The `file` always refers to the index of the file within the list of files passed to `merge_files`, while
+/// the `signal` refers to the index of the signal when ignoring all annotation signals.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MergeIncompatibility {
+    /// The file stores its samples using a different amount of bits (EDF and BDF files can not be merged)
+    SampleBits { file: usize },
+
+    /// The data-records of the file have a different duration
+    RecordDuration { file: usize, expected: f64, actual: f64 },
+
+    /// The file has a different amount of signals which are not annotation signals
+    SignalCount { file: usize, expected: usize, actual: usize },
+
+    /// The signal of the file has a different label
+    Label { file: usize, signal: usize, expected: String, actual: String },
+
+    /// The signal of the file has a different physical dimension
+    PhysicalDimension { file: usize, signal: usize, expected: String, actual: String },
+
+    /// The signal of the file has a different physical range
+    PhysicalRange { file: usize, signal: usize, expected: (f64, f64), actual: (f64, f64) },
+
+    /// The signal of the file has a different digital range
+    DigitalRange { file: usize, signal: usize, expected: (i32, i32), actual: (i32, i32) },
+
+    /// The signal of the file has a different samples count
+    SamplesCount { file: usize, signal: usize, expected: usize, actual: usize },
+}
+
+/// Checks whether or not the given files can be merged using `merge_files`. Every file is compared with the first
+/// file, where all signals (except for annotation signals) have to match in order and every field except for the
+/// transducer and prefilter. Returns every difference found, which is empty in case the files can be merged.
+pub fn check_merge_compatibility(files: &[EDFFile]) -> Vec<MergeIncompatibility> {
+    let mut incompatibilities = Vec::new();
+    let Some(first) = files.first() else {
+        return incompatibilities;
+    };
+
+    let expected_signals = regular_signals(first.header.get_signals());
+    for (file, edf) in files.iter().enumerate().skip(1) {
+        if edf.header.get_specification().sample_bytes() != first.header.get_specification().sample_bytes() {
+            incompatibilities.push(MergeIncompatibility::SampleBits { file });
+        }
+
+        let (expected, actual) = (first.header.get_record_duration(), edf.header.get_record_duration());
+        if (expected - actual).abs() > 1e-9 {
+            incompatibilities.push(MergeIncompatibility::RecordDuration { file, expected, actual });
+        }
+
+        let signals = regular_signals(edf.header.get_signals());
+        if signals.len() != expected_signals.len() {
+            incompatibilities.push(MergeIncompatibility::SignalCount {
+                file,
+                expected: expected_signals.len(),
+                actual: signals.len(),
+            });
+        }
+
+        for (signal, (expected, actual)) in expected_signals.iter().zip(&signals).enumerate() {
+            if expected.label != actual.label {
+                incompatibilities.push(MergeIncompatibility::Label {
+                    file,
+                    signal,
+                    expected: expected.label.clone(),
+                    actual: actual.label.clone(),
+                });
+            }
+            if expected.physical_dimension != actual.physical_dimension {
+                incompatibilities.push(MergeIncompatibility::PhysicalDimension {
+                    file,
+                    signal,
+                    expected: expected.physical_dimension.clone(),
+                    actual: actual.physical_dimension.clone(),
+                });
+            }
+            if expected.physical_minimum != actual.physical_minimum || expected.physical_maximum != actual.physical_maximum {
+                incompatibilities.push(MergeIncompatibility::PhysicalRange {
+                    file,
+                    signal,
+                    expected: (expected.physical_minimum, expected.physical_maximum),
+                    actual: (actual.physical_minimum, actual.physical_maximum),
+                });
+            }
+            if expected.digital_minimum != actual.digital_minimum || expected.digital_maximum != actual.digital_maximum {
+                incompatibilities.push(MergeIncompatibility::DigitalRange {
+                    file,
+                    signal,
+                    expected: (expected.digital_minimum, expected.digital_maximum),
+                    actual: (actual.digital_minimum, actual.digital_maximum),
+                });
+            }
+            if expected.samples_count != actual.samples_count {
+                incompatibilities.push(MergeIncompatibility::SamplesCount {
+                    file,
+                    signal,
+                    expected: expected.samples_count,
+                    actual: actual.samples_count,
+                });
+            }
+        }
+    }
+
+    incompatibilities
+}
+
+/// Merges the given files into a single EDF+ (or BDF+) file at the given path, which must not exist yet. The files are
+/// ordered by their start date and time, where the header of the earliest file is used for the merged file. The data-records
+/// of every file are placed at their real offset relative to the start of the earliest file, which is stored in the
+/// Time-keeping annotation of every data-record. The merged file is continuous in case all data-records directly follow each
+/// other, otherwise it is discontinuous. All annotations of a data-record are merged into a single annotation signal
+/// following the regular signals. Files overlapping each other can not be merged and result in `EDFError::RecordsOutOfOrder`,
+/// while files with incompatible signals result in `EDFError::IncompatibleFiles` (see `check_merge_compatibility` for details).
+/// The data-records are read using `EDFFile::read_record_at`, therefore pending changes of the files are included. Every
+/// data-record is read twice, as the size of the merged annotation signal has to be known before writing the header. In case
+/// the path already exists `EDFError::FileAlreadyExists` is returned, while the merged file is removed again in case writing
+/// it fails. Returns the merged file.
+pub fn merge_files<P: AsRef<Path>>(files: &mut [EDFFile], path: P) -> Result<EDFFile, EDFError> {
+    if files.is_empty() {
+        return Err(EDFError::ItemNotFound);
+    }
+    if !check_merge_compatibility(files).is_empty() {
+        return Err(EDFError::IncompatibleFiles);
+    }
+
+    // Order the files by their start and determine the offset of each file relative to the earliest file
+    let mut order = (0..files.len()).collect::<Vec<_>>();
+    order.sort_by_key(|idx| start_of(&files[*idx]));
+    let start = start_of(&files[order[0]]);
+    let offsets = order
+        .iter()
+        .map(|idx| (start_of(&files[*idx]) - start).num_milliseconds() as f64 / 1000.0)
+        .collect::<Vec<_>>();
+
+    // Collect the onset and merged annotations of every data-record as well as the size required for the merged annotation
+    // signal. The size is part of the header, which has to be written before the first data-record, while keeping the samples
+    // of every file in memory is not feasible. The samples are therefore read again when copying the data-records, where the
+    // annotations collected here are reused
+    let earliest = files[order[0]].header.clone();
+    let record_duration = earliest.get_record_duration();
+    let specification = match earliest.get_specification().sample_bytes() {
+        2 => EDFSpecifications::EDFPlus,
+        _ => EDFSpecifications::BDFPlus,
+    };
+    let mut annotation_samples_count = 0;
+    let mut onsets = Vec::new();
+    let mut annotations = Vec::new();
+    for (file_idx, offset) in order.iter().zip(&offsets) {
+        let edf = &mut files[*file_idx];
+        if let Some(signal) = edf.header.get_signals().iter().find(|signal| signal.is_annotation()) {
+            annotation_samples_count = annotation_samples_count.max(signal.samples_count * signal.annotation_char_bytes());
+        }
+
+        for record_idx in 0..edf.header.get_record_count().unwrap_or(0) {
+            let record = edf.read_record_at(record_idx)?.ok_or(EDFError::ItemNotFound)?;
+            let onset = round_nanos(offset + record.get_start_offset());
+            let merged = merged_annotations(record.annotations, onset, *offset);
+            annotation_samples_count = annotation_samples_count.max(merged.iter().map(|tal| tal.serialize().len()).sum());
+            onsets.push(onset);
+            annotations.push(merged);
+        }
+    }
+
+    // Files overlapping each other can not be represented within a single file
+    for onsets in onsets.windows(2) {
+        if onsets[1] < onsets[0] + record_duration - 1e-9 {
+            return Err(EDFError::RecordsOutOfOrder);
+        }
+    }
+
+    // The merged header contains all regular signals followed by a single annotation signal
+    let mut header = earliest.clone();
+    let annotation_signal = SignalHeader::new_annotation(0, specification.clone());
+    let mut signals = regular_signals(earliest.get_signals()).into_iter().cloned().collect::<Vec<_>>();
+    signals.push(annotation_signal.clone());
+    signals.last_mut().unwrap().samples_count = annotation_samples_count.div_ceil(annotation_signal.annotation_char_bytes()).max(1);
+    header.signals = signals;
+    header.updated_signals = None;
+    header.updated_specification = None;
+    header.signal_count = header.signals.len();
+    header.header_bytes = header.calculate_header_bytes();
+    header.specification = specification;
+    header.record_count = Some(onsets.len());
+    header.is_continuous = onsets.windows(2).all(|onsets| (onsets[1] - onsets[0] - record_duration).abs() < 1e-9);
+    header.upgrade_ids_to_plus();
+
+    // Write the merged file by copying every data-record into the new layout
+    create_new_file(path.as_ref(), header, |header, writer| {
+        let mut annotations = annotations.into_iter();
+        for file_idx in &order {
+            let edf = &mut files[*file_idx];
+            for record_idx in 0..edf.header.get_record_count().unwrap_or(0) {
+                let record = edf.read_record_at(record_idx)?.ok_or(EDFError::ItemNotFound)?;
+                let mut merged = header.create_record();
+                merged.raw_signal_samples = record.raw_signal_samples;
+                merged.annotations = vec![annotations.next().ok_or(EDFError::ItemNotFound)?];
+                writer.write_all(&merged.serialize()?).map_err(EDFError::FileWriteError)?;
+            }
+        }
+
+        Ok(())
+    })
+}
+
+/// Returns all signals which are not annotation signals
+fn regular_signals(signals: &[SignalHeader]) -> Vec<&SignalHeader> {
+    signals.iter().filter(|signal| !signal.is_annotation()).collect()
+}
+
+/// Returns the date and time the recording of the given file started
+fn start_of(edf: &EDFFile) -> NaiveDateTime {
+    edf.header.start_date().and_time(edf.header.get_start_time())
+}
+
+/// Merges the annotations of all annotation signals of a data-record into a single list starting with the Time-keeping
+/// annotation of the given onset. All other annotations are shifted by the offset of the file they originate from.
+fn merged_annotations(annotations: Vec<Vec<AnnotationList>>, onset: f64, offset: f64) -> Vec<AnnotationList> {
+    let mut merged = vec![AnnotationList::new_time_keeping(onset)];
+    for mut tal in annotations.into_iter().flatten().filter(|tal| !tal.is_time_keeping()) {
+        tal.onset = round_nanos(tal.onset + offset);
+        merged.push(tal);
+    }
+
+    merged
+}
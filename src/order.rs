@@ -0,0 +1,37 @@
+/// The result of checking the chronological order of the data-records of a file using `EDFFile::check_record_order`.
+/// All indices refer to the data-records including the pending changes.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct RecordOrderReport {
+    /// The indices of all data-records starting before the preceding data-record starts
+    pub out_of_order: Vec<usize>,
+
+    /// The indices of all data-records starting after the preceding data-record starts, but before it ends
+    pub overlapping: Vec<usize>,
+}
+
+impl RecordOrderReport {
+    /// Creates the report from the onsets of all data-records of a file
+    pub(crate) fn new(onsets: &[f64], record_duration: f64) -> Self {
+        let mut report = Self::default();
+        for (idx, onsets) in onsets.windows(2).enumerate() {
+            if onsets[1] < onsets[0] - 1e-9 {
+                report.out_of_order.push(idx + 1);
+            } else if onsets[1] < onsets[0] + record_duration - 1e-9 {
+                report.overlapping.push(idx + 1);
+            }
+        }
+
+        report
+    }
+
+    /// Returns whether or not all data-records are stored in chronological order. Overlapping data-records are
+    /// not considered here, as those can not be resolved by reordering the data-records
+    pub fn is_chronological(&self) -> bool {
+        self.out_of_order.is_empty()
+    }
+
+    /// Returns whether or not all data-records are stored in chronological order without overlapping each other
+    pub fn is_valid(&self) -> bool {
+        self.out_of_order.is_empty() && self.overlapping.is_empty()
+    }
+}
@@ -0,0 +1,77 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Token to cooperatively cancel long running operations like saving a file. The token can be cloned and shared
+/// with other threads, where calling `cancel()` on any clone cancels the operations of all clones. Cancelled
+/// operations return `EDFError::Cancelled` and leave the file in a consistent state.
+#[derive(Debug, Default, Clone)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requests all operations using this token to be cancelled
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Resets the token, allowing it to be used for subsequent operations again
+    pub fn reset(&self) {
+        self.cancelled.store(false, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// The long running operation a `Progress` is reported for
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProgressOperation {
+    /// The pending changes are being written to disk. The progress is measured in data-records
+    Save,
+
+    /// The edited file is being written to a different location. The progress is measured in data-records
+    Export,
+
+    /// A duration of data is being read. The progress is measured in nanoseconds
+    Read,
+
+    /// The data-records are being re-blocked to a new data-record duration. The progress is measured in the
+    /// data-records of the file before re-blocking
+    Reblock,
+
+    /// The gaps of a discontinuous file are being filled. The progress is measured in the data-records of the file
+    /// before filling its gaps
+    FillGaps,
+
+    /// A file is being split into multiple parts. The progress is measured in the data-records of the split file
+    Split,
+
+    /// A file is being cropped to a time window. The progress is measured in the data-records within the window
+    Crop,
+
+    /// A subset of the signals is being copied into a new file. The progress is measured in data-records
+    Extract,
+
+    /// The protected health information of a file is being removed. The progress is measured in data-records
+    Anonymize,
+
+    /// A file which was not written completely is being repaired. The progress is measured in data-records
+    Repair,
+}
+
+/// The progress of a long running operation. The unit of the values depends on the operation
+#[derive(Debug, Clone, PartialEq)]
+pub struct Progress {
+    pub operation: ProgressOperation,
+    pub processed: u64,
+    pub total: u64,
+}
+
+/// Callback receiving the progress of long running operations
+pub type ProgressHandler = Box<dyn FnMut(&Progress) + Send>;
@@ -6,6 +6,8 @@ use crate::error::edf_error::EDFError;
 use crate::headers::annotation_list::AnnotationList;
 use crate::headers::edf_header::EDFHeader;
 use crate::headers::signal_header::SignalHeader;
+use crate::resample::{ResampleMethod, resample};
+use crate::rescale::{ClippingStatistics, RescaleMode, rescale};
 use crate::save::{SaveInstruction, SaveValue};
 
 #[derive(Debug, Default, Clone, PartialEq)]
@@ -51,6 +53,14 @@ impl Samples {
         Ok(())
     }
 
+    /// Removes the given amount of samples from the front and returns them
+    pub fn take_front(&mut self, count: usize) -> Samples {
+        match self {
+            Self::Values16Bit(samples) => Self::Values16Bit(samples.drain(..count.min(samples.len())).collect()),
+            Self::Values24Bit(samples) => Self::Values24Bit(samples.drain(..count.min(samples.len())).collect()),
+        }
+    }
+
     pub fn range<R: RangeBounds<usize>>(&self, range: R) -> Samples {
         let start = match range.start_bound() {
             Bound::Included(&n) => n,
@@ -70,10 +80,20 @@ impl Samples {
     }
 }
 
+/// Options controlling how the samples of a data-record are converted when patching it with signal instructions
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct PatchOptions {
+    /// The method used to convert the samples of signals whose samples count is updated
+    pub resample_method: ResampleMethod,
+
+    /// Whether the digital or the physical values of signals whose physical or digital range is updated are kept
+    pub rescale_mode: RescaleMode,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Record {
     layout: RecordLayout,
-    specification: EDFSpecifications,
+    pub(crate) specification: EDFSpecifications,
     pub(crate) default_offset: f64,
     pub raw_signal_samples: Vec<Samples>,
     pub annotations: Vec<Vec<AnnotationList>>,
@@ -126,41 +146,166 @@ impl Record {
         })
     }
 
-    pub fn patch_record(&mut self, instructions: &Vec<SaveInstruction>) -> Result<(), EDFError> {
-        if instructions.is_empty() {
-            return Ok(());
-        }
+    /// Applies the given signal instructions to the record, which turns the record into a record matching the signals after
+    /// applying the instructions. The given signal headers have to be the ones the record matches before applying the
+    /// instructions, as they are required to resample, rescale or convert the samples. Updated signals are converted using
+    /// the options of the preceding PATCH instruction or the default options if there is none (see `PatchOptions`). Returns
+    /// the clipping statistics of every signal the record matches after applying the instructions.
+    pub fn patch_record(
+        &mut self,
+        instructions: &Vec<SaveInstruction>,
+        signals: &[SignalHeader],
+    ) -> Result<Vec<ClippingStatistics>, EDFError> {
+        self.patch_record_with_neighbours(instructions, signals, None, None)
+    }
 
-        // Process each instruction
-        let mut signal_idx = instructions[0].index();
-        let mut instruction_idx = 0;
-        loop {
-            let Some(tr) = instructions.get(instruction_idx) else {
-                break;
+    /// Applies the given signal instructions to the record like `patch_record()`, where the given preceding and following
+    /// records are used to resample the samples at the edges of the record. The neighbouring records have to match the
+    /// same signals as the record and are patched alongside the record to keep their samples at the same state.
+    pub(crate) fn patch_record_with_neighbours(
+        &mut self,
+        instructions: &Vec<SaveInstruction>,
+        signals: &[SignalHeader],
+        mut previous: Option<Record>,
+        mut next: Option<Record>,
+    ) -> Result<Vec<ClippingStatistics>, EDFError> {
+        let mut options = PatchOptions::default();
+        let mut signals = signals.to_vec();
+        let mut statistics = vec![ClippingStatistics::default(); signals.len()];
+
+        // Apply every instruction in order, as normalized instructions may contain multiple
+        // segments separated by MOVE and CONVERT instructions
+        for tr in instructions {
+            let previous_signals = match previous.is_some() || next.is_some() {
+                true => Some(signals.clone()),
+                false => None,
             };
 
             match tr {
-                SaveInstruction::Remove(idx) if *idx == signal_idx => {
-                    instruction_idx += 1;
+                SaveInstruction::Remove(idx) => {
                     self.remove_signal(*idx)?;
+                    signals.remove(*idx);
+                    statistics.remove(*idx);
                 }
-                SaveInstruction::Insert(idx, SaveValue::Signal(value)) if *idx == signal_idx => {
-                    instruction_idx += 1;
+                SaveInstruction::Insert(idx, SaveValue::Signal(value)) => {
                     if value.is_annotation() {
                         self.insert_annotation(*idx, value.samples_count)?;
                     } else {
                         self.insert_signal_samples(*idx, value.samples_count)?;
                     }
+                    signals.insert(*idx, value.clone());
+                    statistics.insert(*idx, ClippingStatistics::default());
                 }
-                SaveInstruction::Update(idx, SaveValue::Signal(value)) if *idx == signal_idx => {
-                    signal_idx += 1;
-                    instruction_idx += 1;
-                    self.update_samples_count(*idx, value.samples_count)?;
+                SaveInstruction::Update(idx, SaveValue::Signal(value)) => {
+                    let signal = signals.get_mut(*idx).ok_or(EDFError::ItemNotFound)?;
+                    if options.rescale_mode == RescaleMode::KeepPhysical {
+                        statistics[*idx].add(&self.rescale_signal(*idx, signal, value)?);
+                    }
+                    *signal = value.clone();
+                    self.resample_signal_with_neighbours(*idx, value, &options.resample_method, previous.as_ref(), next.as_ref())?;
                 }
-                _ => {
-                    signal_idx += 1;
+                SaveInstruction::Move(from, to) => {
+                    self.move_signal(*from, *to)?;
+                    let signal = signals.remove(*from);
+                    signals.insert(*to, signal);
+                    let signal_statistics = statistics.remove(*from);
+                    statistics.insert(*to, signal_statistics);
+                }
+                SaveInstruction::Convert(specification) => {
+                    let converted = signals
+                        .iter()
+                        .map(|signal| signal.convert_specification(&self.specification, specification))
+                        .collect::<Vec<_>>();
+                    let clipped = self.convert_specification(specification, &signals, &converted)?;
+                    for (total, signal) in statistics.iter_mut().zip(&clipped) {
+                        total.add(signal);
+                    }
+                    signals = converted;
+                }
+                SaveInstruction::Patch(updated) => options = updated.clone(),
+                _ => {}
+            }
+
+            // Keep the neighbouring records at the same state as the record for the following instructions
+            if let Some(previous_signals) = previous_signals {
+                let instruction_listed = vec![SaveInstruction::Patch(options.clone()), tr.clone()];
+                for neighbour in [&mut previous, &mut next].into_iter().flatten() {
+                    neighbour.patch_record(&instruction_listed, &previous_signals)?;
+                }
+            }
+        }
+
+        Ok(statistics)
+    }
+
+    /// Converts the samples of every signal from the sample size of the current specification of the record to the sample
+    /// size of the given specification. The given signals `from` have to be the ones the record currently matches, while the
+    /// signals `to` are the ones the record matches after the conversion (see `SignalHeader::convert_specification`). The samples
+    /// are re-quantized to keep their physical values, where samples outside of the new digital range are clipped. Returns the
+    /// amount of clipped samples of every signal.
+    pub fn convert_specification(
+        &mut self,
+        specification: &EDFSpecifications,
+        from: &[SignalHeader],
+        to: &[SignalHeader],
+    ) -> Result<Vec<ClippingStatistics>, EDFError> {
+        if from.len() != self.layout.signal_map.len() || to.len() != from.len() {
+            return Err(EDFError::InvalidRecordSignals);
+        }
+
+        // Specifications of the same sample size (e.g. EDF and EDF+) share the same data-record layout
+        let mut statistics = vec![ClippingStatistics::default(); from.len()];
+        if self.specification.sample_bytes() == specification.sample_bytes() {
+            self.specification = specification.clone();
+            return Ok(statistics);
+        }
+
+        for (i, signal_statistics) in statistics.iter_mut().enumerate() {
+            match self.layout.signal_map.get(&i) {
+                Some(SignalType::Samples(idx)) => {
+                    let (rescaled, clipped) = match &self.raw_signal_samples[*idx] {
+                        Samples::Values16Bit(values) => rescale(values, &from[i], &to[i])?,
+                        Samples::Values24Bit(values) => rescale(values, &from[i], &to[i])?,
+                    };
+                    self.raw_signal_samples[*idx] = match specification.sample_bytes() {
+                        2 => Samples::Values16Bit(rescaled.into_iter().map(|value| value.clamp(i16::MIN as i32, i16::MAX as i32) as i16).collect()),
+                        _ => Samples::Values24Bit(rescaled),
+                    };
+                    *signal_statistics = clipped;
+                }
+                Some(SignalType::Annotation(idx)) => {
+                    self.layout.annotation_samples_count[*idx] = to[i].samples_count;
                 }
+                None => return Err(EDFError::InvalidRecordSignals),
+            }
+        }
+        self.specification = specification.clone();
+
+        Ok(statistics)
+    }
+
+    /// Moves the samples or annotations of the signal at index `from` to index `to`, shifting all
+    /// signals in between. The index `to` refers to the position of the signal after moving it.
+    pub fn move_signal(&mut self, from: usize, to: usize) -> Result<(), EDFError> {
+        if to >= self.layout.signal_map.len() {
+            return Err(EDFError::ItemNotFound);
+        }
+
+        match self.layout.signal_map.get(&from) {
+            Some(SignalType::Samples(idx)) => {
+                let samples = self.raw_signal_samples[*idx].clone();
+                self.remove_signal(from)?;
+                self.insert_signal_samples(to, samples.len())?;
+                self.set_samples(to, samples)?;
+            }
+            Some(SignalType::Annotation(idx)) => {
+                let samples_count = self.layout.annotation_samples_count[*idx];
+                let annotations = std::mem::take(&mut self.annotations[*idx]);
+                self.remove_signal(from)?;
+                self.insert_annotation(to, samples_count)?;
+                self.set_annotation(to, annotations)?;
             }
+            None => return Err(EDFError::ItemNotFound),
         }
 
         Ok(())
@@ -268,6 +413,118 @@ impl Record {
         Ok(())
     }
 
+    /// Re-quantizes the samples of the signal at the given index from the calibration of signal `from` to the calibration
+    /// of signal `to`, keeping the physical values they represent. Samples which can not be represented within the digital
+    /// range of `to` are clipped. Returns the amount of clipped samples. Annotation signals remain unchanged.
+    pub fn rescale_signal(
+        &mut self,
+        signal_index: usize,
+        from: &SignalHeader,
+        to: &SignalHeader,
+    ) -> Result<ClippingStatistics, EDFError> {
+        let unchanged = from.physical_minimum == to.physical_minimum
+            && from.physical_maximum == to.physical_maximum
+            && from.digital_minimum == to.digital_minimum
+            && from.digital_maximum == to.digital_maximum;
+        let idx = match self.layout.signal_map.get(&signal_index) {
+            Some(SignalType::Samples(idx)) => *idx,
+            Some(SignalType::Annotation(_)) => return Ok(ClippingStatistics::default()),
+            None => return Err(EDFError::ItemNotFound),
+        };
+        if unchanged {
+            return Ok(ClippingStatistics::default());
+        }
+
+        let statistics = match self.raw_signal_samples.get_mut(idx) {
+            Some(Samples::Values16Bit(values)) => {
+                let (rescaled, statistics) = rescale(values, from, to)?;
+                *values = rescaled
+                    .into_iter()
+                    .map(|value| value.clamp(i16::MIN as i32, i16::MAX as i32) as i16)
+                    .collect();
+                statistics
+            }
+            Some(Samples::Values24Bit(values)) => {
+                let (rescaled, statistics) = rescale(values, from, to)?;
+                *values = rescaled;
+                statistics
+            }
+            None => return Err(EDFError::ItemNotFound),
+        };
+
+        Ok(statistics)
+    }
+
+    /// Converts the samples of the signal at the given index to the samples count of the given signal header using
+    /// the given resample method. The resampled values are limited to the digital range of the given signal header.
+    /// Annotation signals only have their samples count updated.
+    pub fn resample_signal(
+        &mut self,
+        signal_index: usize,
+        signal: &SignalHeader,
+        method: &ResampleMethod,
+    ) -> Result<(), EDFError> {
+        self.resample_signal_with_neighbours(signal_index, signal, method, None, None)
+    }
+
+    /// Converts the samples of the signal at the given index like `resample_signal()`, where the samples of the same signal
+    /// in the given preceding and following records are used to resample the samples at the edges of the record.
+    pub(crate) fn resample_signal_with_neighbours(
+        &mut self,
+        signal_index: usize,
+        signal: &SignalHeader,
+        method: &ResampleMethod,
+        previous: Option<&Record>,
+        next: Option<&Record>,
+    ) -> Result<(), EDFError> {
+        if *method == ResampleMethod::Resize {
+            return self.update_samples_count(signal_index, signal.samples_count);
+        }
+
+        let Some(SignalType::Samples(idx)) = self.layout.signal_map.get(&signal_index) else {
+            return self.update_samples_count(signal_index, signal.samples_count);
+        };
+
+        let neighbour_values = |record: Option<&Record>| {
+            record
+                .and_then(|record| record.get_sample_values(signal_index))
+                .unwrap_or_default()
+        };
+        let previous = neighbour_values(previous);
+        let next = neighbour_values(next);
+
+        let min = signal.digital_minimum.min(signal.digital_maximum) as f64;
+        let max = signal.digital_minimum.max(signal.digital_maximum) as f64;
+        let convert = |values: Vec<f64>| {
+            resample(&previous, &values, &next, signal.samples_count, method)
+                .into_iter()
+                .map(move |value| value.round().clamp(min, max))
+        };
+        match self.raw_signal_samples.get_mut(*idx) {
+            Some(Samples::Values16Bit(values)) => {
+                *values = convert(values.iter().map(|v| *v as f64).collect()).map(|v| v as i16).collect();
+            }
+            Some(Samples::Values24Bit(values)) => {
+                *values = convert(values.iter().map(|v| *v as f64).collect()).map(|v| v as i32).collect();
+            }
+            None => return Err(EDFError::ItemNotFound),
+        }
+
+        Ok(())
+    }
+
+    /// Returns the digital values of the samples of the signal at the given index or `None` in case it is not an ordinary signal
+    fn get_sample_values(&self, signal_index: usize) -> Option<Vec<f64>> {
+        let Some(SignalType::Samples(idx)) = self.layout.signal_map.get(&signal_index) else {
+            return None;
+        };
+
+        Some(match self.raw_signal_samples.get(*idx)? {
+            Samples::Values16Bit(values) => values.iter().map(|v| *v as f64).collect(),
+            Samples::Values24Bit(values) => values.iter().map(|v| *v as f64).collect(),
+        })
+    }
+
     pub fn set_annotation(
         &mut self,
         signal_index: usize,
@@ -382,6 +639,32 @@ impl Record {
             .unwrap_or(self.default_offset)
     }
 
+    /// Sets the onset of the Time-keeping annotation list in the first annotation signal of the record. The
+    /// Time-keeping annotation list is added as the first annotation list in case the record does not have one yet
+    pub fn set_time_keeping(&mut self, onset: f64) -> Result<(), EDFError> {
+        let Some(tals) = self.annotations.first_mut() else {
+            return Err(EDFError::ItemNotFound);
+        };
+
+        match tals.iter_mut().find(|tal| tal.is_time_keeping()) {
+            Some(tal) => tal.onset = onset,
+            None => tals.insert(0, AnnotationList::new_time_keeping(onset)),
+        }
+        self.default_offset = onset;
+
+        Ok(())
+    }
+
+    /// Shifts the onset of every annotation list (including the Time-keeping annotation) in all annotation
+    /// signals of the record by the given amount of seconds. The resulting onsets are rounded to full
+    /// nanoseconds to prevent floating point artifacts from growing the serialized TALs.
+    pub fn shift_onsets(&mut self, seconds: f64) {
+        for annotation_list in self.annotations.iter_mut().flatten() {
+            annotation_list.onset = ((annotation_list.onset + seconds) * 1_000_000_000.0).round() / 1_000_000_000.0;
+        }
+        self.default_offset += seconds;
+    }
+
     pub fn serialize(&self) -> Result<Vec<u8>, EDFError> {
         let sample_bytes = match self.specification {
             EDFSpecifications::EDF | EDFSpecifications::EDFPlus => 2,
@@ -0,0 +1,47 @@
+/// Describes how `EDFFile::repair` handles a data-record which was only partially written to disk
+#[derive(Debug, Default, Clone, PartialEq)]
+pub enum PartialRecordStrategy {
+    /// The partial data-record is removed
+    #[default]
+    Truncate,
+
+    /// The missing bytes of the partial data-record are filled with zeros. Annotation signals which were only
+    /// partially written are cleared entirely, as their last annotation list would be incomplete
+    Pad,
+}
+
+/// A single fix made while repairing a file
+#[derive(Debug, Clone, PartialEq)]
+pub enum RepairFix {
+    /// The size of the header did not match the amount of signals
+    HeaderBytes { stored: usize, actual: usize },
+
+    /// The reserved field did not match the specification and continuity of the file
+    Reserved { stored: String, actual: String },
+
+    /// The amount of data-records did not match the size of the file. A stored value of `None` means the file was
+    /// never finished recording (a record count of -1)
+    RecordCount { stored: Option<usize>, actual: usize },
+
+    /// The data-record at the given index was only partially written and contained the given amount of bytes
+    PartialRecord { record: usize, bytes: usize, strategy: PartialRecordStrategy },
+
+    /// The data-record at the given index was missing its Time-keeping annotation, which was regenerated with the given onset
+    TimeKeeping { record: usize, onset: f64 },
+
+    /// The file was marked as continuous, even though there are gaps between its data-records
+    Discontinuous,
+}
+
+/// The report of all fixes made while repairing a file
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct RepairReport {
+    pub fixes: Vec<RepairFix>,
+}
+
+impl RepairReport {
+    /// Returns whether or not the file was already intact
+    pub fn is_empty(&self) -> bool {
+        self.fixes.is_empty()
+    }
+}
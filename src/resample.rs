@@ -0,0 +1,145 @@
+use std::f64::consts::PI;
+
+/// The amount of zero crossings of the sinc function on each side of the polyphase filter kernel. Higher values
+/// result in a steeper low-pass filter at the cost of performance
+const POLYPHASE_ZERO_CROSSINGS: f64 = 16.0;
+
+/// The method used to convert the samples of a signal in case its samples count is changed by updating the signal.
+/// Data-records stored on disk are resampled using the samples of the data-records preceding and following them after
+/// applying all pending changes, which keeps the waveform continuous across data-record boundaries. Only neighbours which
+/// are stored on disk and start right at the end or beginning of the data-record are used. Samples outside of the available
+/// data-records (e.g. at the beginning and end of the file, at gaps, next to removed data-records or for pending data-records)
+/// are treated as having the value of the first or last available sample respectively.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub enum ResampleMethod {
+    /// Truncates the samples or pads them with zeros. The existing samples are kept as they are, which
+    /// results in a waveform no longer matching the new sampling rate
+    #[default]
+    Resize,
+
+    /// Linearly interpolates between neighboring samples. This does not remove frequencies above the
+    /// Nyquist frequency of the new sampling rate and therefore causes aliasing when reducing the samples count
+    Linear,
+
+    /// Polyphase resampling with a windowed-sinc low-pass filter. When reducing the samples count, all frequencies
+    /// above the Nyquist frequency of the new sampling rate are filtered out before decimating (anti-aliasing)
+    Polyphase,
+}
+
+/// Converts the given samples to the given samples count using the given method. The first sample is kept at the
+/// same point in time, while all other samples are placed according to the new sampling rate. The given preceding and
+/// following samples (e.g. of the neighbouring data-records) are only used to resample the given samples at their edges.
+pub(crate) fn resample(
+    previous: &[f64],
+    samples: &[f64],
+    next: &[f64],
+    samples_count: usize,
+    method: &ResampleMethod,
+) -> Vec<f64> {
+    if samples.len() == samples_count {
+        return samples.to_vec();
+    }
+    if samples.is_empty() || samples_count == 0 || *method == ResampleMethod::Resize {
+        let mut resized = samples.to_vec();
+        resized.resize(samples_count, 0.0);
+        return resized;
+    }
+
+    let stream = Stream { previous, samples, next };
+    match method {
+        ResampleMethod::Linear => resample_linear(&stream, samples_count),
+        _ => resample_polyphase(&stream, samples_count),
+    }
+}
+
+/// Samples to be resampled together with the samples surrounding them
+struct Stream<'a> {
+    previous: &'a [f64],
+    samples: &'a [f64],
+    next: &'a [f64],
+}
+
+impl Stream<'_> {
+    /// Returns the sample at the given index relative to the first sample to be resampled. Indices outside of the
+    /// surrounding samples are clamped to the first or last available sample.
+    fn get(&self, idx: isize) -> f64 {
+        let first = -(self.previous.len() as isize);
+        let last = (self.samples.len() + self.next.len()) as isize - 1;
+        let idx = idx.clamp(first, last);
+        if idx < 0 {
+            self.previous[(idx - first) as usize]
+        } else if (idx as usize) < self.samples.len() {
+            self.samples[idx as usize]
+        } else {
+            self.next[idx as usize - self.samples.len()]
+        }
+    }
+}
+
+fn resample_linear(stream: &Stream, samples_count: usize) -> Vec<f64> {
+    let step = stream.samples.len() as f64 / samples_count as f64;
+    (0..samples_count)
+        .map(|i| {
+            let position = i as f64 * step;
+            let idx = position.floor() as isize;
+            let fraction = position - idx as f64;
+            stream.get(idx) * (1.0 - fraction) + stream.get(idx + 1) * fraction
+        })
+        .collect()
+}
+
+fn resample_polyphase(stream: &Stream, samples_count: usize) -> Vec<f64> {
+    let samples = stream.samples;
+    // Resampling by the ratio up/down requires a distinct set of filter taps for each of the `up` phases
+    let divisor = gcd(samples.len(), samples_count);
+    let up = samples_count / divisor;
+    let down = samples.len() / divisor;
+
+    // The cutoff frequency relative to the Nyquist frequency of the input. Decreasing the samples count requires
+    // the cutoff to be lowered to the Nyquist frequency of the output to prevent aliasing
+    let cutoff = (up as f64 / down as f64).min(1.0);
+    let half_width = (POLYPHASE_ZERO_CROSSINGS / cutoff).ceil() as isize;
+
+    // Calculate the filter taps of every phase, normalized to keep the amplitude of constant signals
+    let phases = (0..up)
+        .map(|phase| {
+            let offset = phase as f64 / up as f64;
+            let taps = (1 - half_width..=half_width)
+                .map(|k| windowed_sinc(k as f64 - offset, cutoff, half_width as f64))
+                .collect::<Vec<_>>();
+            let sum = taps.iter().sum::<f64>();
+            taps.into_iter().map(|tap| tap / sum).collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    (0..samples_count)
+        .map(|i| {
+            let position = i * down;
+            let base = (position / up) as isize;
+            phases[position % up]
+                .iter()
+                .enumerate()
+                .map(|(k, tap)| tap * stream.get(base + 1 - half_width + k as isize))
+                .sum()
+        })
+        .collect()
+}
+
+/// Value of the sinc low-pass filter kernel with the given cutoff at the given distance (in input samples),
+/// weighted by a Blackman window reaching zero at the given half width
+fn windowed_sinc(distance: f64, cutoff: f64, half_width: f64) -> f64 {
+    let relative = distance / half_width;
+    if relative.abs() >= 1.0 {
+        return 0.0;
+    }
+
+    let window = 0.42 + 0.5 * (PI * relative).cos() + 0.08 * (2.0 * PI * relative).cos();
+    let x = PI * cutoff * distance;
+    let sinc = if x == 0.0 { 1.0 } else { x.sin() / x };
+
+    cutoff * sinc * window
+}
+
+pub(crate) fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
@@ -0,0 +1,88 @@
+use crate::error::edf_error::EDFError;
+use crate::headers::signal_header::SignalHeader;
+
+/// Determines how the stored digital samples of a signal are treated in case its physical or digital range is
+/// changed by updating the signal.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub enum RescaleMode {
+    /// The digital samples are kept as they are. Therefore the physical values they represent change according
+    /// to the new physical and digital ranges
+    #[default]
+    KeepDigital,
+
+    /// The digital samples are re-quantized from the previous calibration to the new one, which keeps the physical
+    /// values they represent. Samples outside of the new range are clipped to the new digital minimum or maximum
+    KeepPhysical,
+}
+
+/// The amount of samples of a signal which had to be clipped while being rescaled, as their physical value
+/// can not be represented within the new range of the signal.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ClippingStatistics {
+    /// The amount of samples which were clipped to the new digital minimum
+    pub clipped_below: u64,
+
+    /// The amount of samples which were clipped to the new digital maximum
+    pub clipped_above: u64,
+}
+
+impl ClippingStatistics {
+    pub fn clipped_samples(&self) -> u64 {
+        self.clipped_below + self.clipped_above
+    }
+
+    pub(crate) fn add(&mut self, other: &ClippingStatistics) {
+        self.clipped_below += other.clipped_below;
+        self.clipped_above += other.clipped_above;
+    }
+}
+
+/// Converts digital samples of the calibration of signal `from` into digital samples of the calibration of signal
+/// `to`, keeping the physical values they represent. Returns the rescaled samples along with the amount of samples
+/// which had to be clipped to the digital range of `to`.
+pub(crate) fn rescale<S: Into<f64> + Copy>(
+    samples: &[S],
+    from: &SignalHeader,
+    to: &SignalHeader,
+) -> Result<(Vec<i32>, ClippingStatistics), EDFError> {
+    let (from_gain, from_offset) = calibration(from)?;
+    let (to_gain, to_offset) = calibration(to)?;
+    let min = to.digital_minimum.min(to.digital_maximum);
+    let max = to.digital_minimum.max(to.digital_maximum);
+
+    let mut statistics = ClippingStatistics::default();
+    let rescaled = samples
+        .iter()
+        .map(|sample| {
+            let physical = from_gain * ((*sample).into() + from_offset);
+            let digital = (physical / to_gain - to_offset).round();
+            if digital < min as f64 {
+                statistics.clipped_below += 1;
+                min
+            } else if digital > max as f64 {
+                statistics.clipped_above += 1;
+                max
+            } else {
+                digital as i32
+            }
+        })
+        .collect();
+
+    Ok((rescaled, statistics))
+}
+
+/// Returns the gain and offset mapping digital values of the given signal to physical values using
+/// `physical = gain * (digital + offset)`
+fn calibration(signal: &SignalHeader) -> Result<(f64, f64), EDFError> {
+    if signal.digital_maximum == signal.digital_minimum {
+        return Err(EDFError::InvalidDigitalRange);
+    }
+    if signal.physical_maximum == signal.physical_minimum {
+        return Err(EDFError::InvalidPhysicalRange);
+    }
+
+    let gain = (signal.physical_maximum - signal.physical_minimum) / (signal.digital_maximum - signal.digital_minimum) as f64;
+    let offset = signal.physical_maximum / gain - signal.digital_maximum as f64;
+
+    Ok((gain, offset))
+}
@@ -1,5 +1,6 @@
+use crate::EDFSpecifications;
 use crate::headers::signal_header::SignalHeader;
-use crate::record::Record;
+use crate::record::{PatchOptions, Record};
 
 pub fn normalize_instructions(
     instructions: &Vec<SaveInstruction>,
@@ -12,12 +13,64 @@ pub fn normalize_instructions(
     // instruction with the given index
     let mut item_counter = initial_count;
 
+    // MOVE and CONVERT instructions can not be merged with the surrounding instructions without losing the values of the
+    // affected items. Therefore the instructions in between those instructions are normalized separately, resulting in a
+    // list of normalized segments which have to be applied in order. PATCH instructions separate segments as well, as
+    // UPDATE instructions using different patch options can not be merged either
+    let is_separator = |i: &SaveInstruction| matches!(i, SaveInstruction::Move(..) | SaveInstruction::Convert(_) | SaveInstruction::Patch(_));
+    for segment in instructions.split_inclusive(is_separator) {
+        let (segment, separator) = match segment.split_last() {
+            Some((separator, segment)) if is_separator(separator) => (segment, Some(separator)),
+            _ => (segment, None),
+        };
+        normalized_instructions.extend(normalize_segment(segment, &mut item_counter));
+
+        match separator {
+            // Ignore MOVE instructions without any effect and cancel out a MOVE instruction directly reverting the previous one
+            Some(SaveInstruction::Move(from, to)) if from != to => {
+                if normalized_instructions.last() == Some(&SaveInstruction::Move(*to, *from)) {
+                    normalized_instructions.pop();
+                } else {
+                    normalized_instructions.push(SaveInstruction::Move(*from, *to));
+                }
+            }
+            Some(SaveInstruction::Convert(specification)) => {
+                normalized_instructions.push(SaveInstruction::Convert(specification.clone()));
+            }
+            Some(SaveInstruction::Patch(options)) => {
+                normalized_instructions.push(SaveInstruction::Patch(options.clone()));
+            }
+            _ => {}
+        }
+    }
+
+    // Remove PATCH instructions without any UPDATE instruction using their options
+    let mut is_used = false;
+    for idx in (0..normalized_instructions.len()).rev() {
+        match normalized_instructions[idx] {
+            SaveInstruction::Update(..) => is_used = true,
+            SaveInstruction::Patch(_) => {
+                if !is_used {
+                    normalized_instructions.remove(idx);
+                }
+                is_used = false;
+            }
+            _ => {}
+        }
+    }
+
+    normalized_instructions
+}
+
+fn normalize_segment(instructions: &[SaveInstruction], item_counter: &mut usize) -> Vec<SaveInstruction> {
+    let mut normalized_instructions = Vec::new();
+
     // Go through every instruction and normalize it and add it to the final list of
     // normalized instructions and remove/ignore instructions cancelling out each other
     for tr in instructions {
         // Turn an APPEND instruction into an INSERT instruction
         let mut instruction = if let SaveInstruction::Append(c) = tr {
-            SaveInstruction::Insert(item_counter, c.clone())
+            SaveInstruction::Insert(*item_counter, c.clone())
         } else {
             tr.clone()
         };
@@ -43,7 +96,7 @@ pub fn normalize_instructions(
                     };
                 }
 
-                item_counter += 1;
+                *item_counter += 1;
             }
             SaveInstruction::Update(current_idx, ref c) => {
                 // If there was an INSERT instruction before which would be updated by the current UPDATE instruction,
@@ -102,7 +155,7 @@ pub fn normalize_instructions(
                     normalized_instructions.remove(idx_eliminate as usize);
                 }
 
-                item_counter -= 1;
+                *item_counter -= 1;
             }
             SaveInstruction::WriteHeader => {}
 
@@ -133,6 +186,223 @@ pub fn normalize_instructions(
     merge_to_updates(normalized_instructions)
 }
 
+/// Resolves the item at the given index of the edited list by walking through the provided normalized instructions.
+/// Returns either the index of the item within the initial (unmodified) list or the pending value of the instruction
+/// which inserted or updated the item. The instructions must be normalized using `normalize_instructions`.
+pub fn resolve_index(instructions: &[SaveInstruction], index: usize) -> ResolvedValue<'_> {
+    let mut initial_idx = 0;
+    let mut edited_idx = 0;
+
+    for instruction in instructions.iter().filter(|i| i.has_record_index()) {
+        // All items in between the current and the next instruction remain unchanged
+        let target_idx = instruction.index();
+        if index < target_idx {
+            break;
+        }
+        initial_idx += target_idx - edited_idx;
+        edited_idx = target_idx;
+
+        match instruction {
+            SaveInstruction::Remove(_) => {
+                initial_idx += 1;
+            }
+            SaveInstruction::Insert(_, value) => {
+                if edited_idx == index {
+                    return ResolvedValue::Pending(value);
+                }
+                edited_idx += 1;
+            }
+            SaveInstruction::Update(_, value) => {
+                if edited_idx == index {
+                    return ResolvedValue::Pending(value);
+                }
+                initial_idx += 1;
+                edited_idx += 1;
+            }
+            _ => {}
+        }
+    }
+
+    ResolvedValue::Initial(initial_idx + index - edited_idx)
+}
+
+/// Iterator over all items of the edited list in their final order. Every item is resolved the same way as
+/// with `resolve_index`, but the instructions only have to be walked through once for the entire list.
+#[derive(Debug, Clone)]
+pub struct ResolvedItems<'a> {
+    instructions: &'a [SaveInstruction],
+    instruction_idx: usize,
+    initial_idx: usize,
+    initial_count: usize,
+    edited_idx: usize,
+    removed_count: usize,
+}
+
+impl<'a> ResolvedItems<'a> {
+    /// Creates a new iterator over the edited list. The instructions must be normalized using `normalize_instructions`
+    /// and the initial count has to be the amount of items in the list before applying the instructions.
+    pub fn new(instructions: &'a [SaveInstruction], initial_count: usize) -> Self {
+        Self {
+            instructions,
+            instruction_idx: 0,
+            initial_idx: 0,
+            initial_count,
+            edited_idx: 0,
+            removed_count: 0,
+        }
+    }
+
+    /// Returns the amount of items of the initial list which were removed before the current item
+    pub fn removed_count(&self) -> usize {
+        self.removed_count
+    }
+}
+
+impl<'a> Iterator for ResolvedItems<'a> {
+    type Item = ResolvedValue<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            // Handle the next instruction in case it targets the current item or in case there are no
+            // unchanged items left in the initial list
+            let instruction = self.instructions.get(self.instruction_idx);
+            let initial_left = self.initial_idx < self.initial_count;
+            if let Some(instruction) = instruction
+                && (instruction.index() == self.edited_idx || !initial_left || !instruction.has_record_index())
+            {
+                self.instruction_idx += 1;
+                match instruction {
+                    SaveInstruction::Remove(_) => {
+                        self.initial_idx += 1;
+                        self.removed_count += 1;
+                    }
+                    SaveInstruction::Insert(_, value) => {
+                        self.edited_idx += 1;
+                        return Some(ResolvedValue::Pending(value));
+                    }
+                    SaveInstruction::Update(_, value) => {
+                        self.initial_idx += 1;
+                        self.edited_idx += 1;
+                        return Some(ResolvedValue::Pending(value));
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+
+            // Otherwise the next item of the initial list remains unchanged
+            if !initial_left {
+                return None;
+            }
+            self.initial_idx += 1;
+            self.edited_idx += 1;
+            return Some(ResolvedValue::Initial(self.initial_idx - 1));
+        }
+    }
+}
+
+/// Compact description of the edited list which allows resolving items in both directions (from the edited list
+/// to the initial list and vice versa) without walking through the instructions again. Consecutive unchanged
+/// items are stored as a single run, therefore the required memory only depends on the amount of instructions
+/// and not on the amount of items in the list.
+pub struct ResolvedLayout<'a> {
+    runs: Vec<InitialRun>,
+    pending: Vec<(usize, &'a SaveValue)>,
+    len: usize,
+}
+
+/// A range of consecutive unchanged items which are located next to each other in both the initial
+/// and the edited list
+#[derive(Debug, Clone, PartialEq)]
+struct InitialRun {
+    edited_idx: usize,
+    initial_idx: usize,
+    len: usize,
+    removed_count: usize,
+}
+
+impl<'a> ResolvedLayout<'a> {
+    /// Creates the layout of the edited list. The instructions must be normalized using `normalize_instructions`
+    /// and the initial count has to be the amount of items in the list before applying the instructions.
+    pub fn new(instructions: &'a [SaveInstruction], initial_count: usize) -> Self {
+        let mut runs: Vec<InitialRun> = Vec::new();
+        let mut pending = Vec::new();
+        let mut items = ResolvedItems::new(instructions, initial_count);
+        let mut edited_idx = 0;
+
+        while let Some(item) = items.next() {
+            match item {
+                ResolvedValue::Pending(value) => pending.push((edited_idx, value)),
+                ResolvedValue::Initial(initial_idx) => match runs.last_mut() {
+                    Some(run) if run.edited_idx + run.len == edited_idx && run.initial_idx + run.len == initial_idx => {
+                        run.len += 1;
+                    }
+                    _ => runs.push(InitialRun {
+                        edited_idx,
+                        initial_idx,
+                        len: 1,
+                        removed_count: items.removed_count(),
+                    }),
+                },
+            }
+            edited_idx += 1;
+        }
+
+        Self {
+            runs,
+            pending,
+            len: edited_idx,
+        }
+    }
+
+    /// Returns the amount of items in the edited list
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the edited list does not contain any items
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Resolves the item at the given index of the edited list. Returns `None` in case the index is out of bounds.
+    pub fn get(&self, edited_idx: usize) -> Option<ResolvedValue<'a>> {
+        if let Ok(pos) = self.pending.binary_search_by_key(&edited_idx, |(idx, _)| *idx) {
+            return Some(ResolvedValue::Pending(self.pending[pos].1));
+        }
+
+        let run = self.find_run(edited_idx)?;
+        Some(ResolvedValue::Initial(run.initial_idx + edited_idx - run.edited_idx))
+    }
+
+    /// Returns the amount of items of the initial list which were removed before the unchanged item at the given
+    /// index of the edited list. Returns 0 for pending items.
+    pub fn removed_count(&self, edited_idx: usize) -> usize {
+        self.find_run(edited_idx)
+            .map(|run| run.removed_count)
+            .unwrap_or(0)
+    }
+
+    /// Returns the index within the edited list of the last item of the initial list within the given range
+    /// (inclusive) which was not removed or updated. Returns `None` in case no such item exists.
+    pub fn last_edited_index_in(&self, initial_first: usize, initial_last: usize) -> Option<usize> {
+        let pos = self.runs.partition_point(|run| run.initial_idx <= initial_last);
+        let run = self.runs[..pos].last()?;
+        let run_last = run.initial_idx + run.len - 1;
+        let initial_idx = run_last.min(initial_last);
+
+        (initial_idx >= initial_first).then(|| run.edited_idx + initial_idx - run.initial_idx)
+    }
+
+    /// Returns the run of unchanged items containing the given index of the edited list
+    fn find_run(&self, edited_idx: usize) -> Option<&InitialRun> {
+        let pos = self.runs.partition_point(|run| run.edited_idx <= edited_idx);
+        self.runs[..pos]
+            .last()
+            .filter(|run| edited_idx < run.edited_idx + run.len)
+    }
+}
+
 /// Merges a delete instruction immediately followed by an insert instruction where both are targeting
 /// the same index into a single Update instruction.
 fn merge_to_updates(instructions: Vec<SaveInstruction>) -> Vec<SaveInstruction> {
@@ -154,6 +424,61 @@ fn merge_to_updates(instructions: Vec<SaveInstruction>) -> Vec<SaveInstruction>
     out
 }
 
+/// Description of what saving the pending changes of a file would do. See `EDFFile::plan_save()`
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct SavePlan {
+    /// The normalized data-record instructions which will be applied
+    pub record_instructions: Vec<SaveInstruction>,
+
+    /// The normalized signal instructions which will be applied
+    pub signal_instructions: Vec<SaveInstruction>,
+
+    /// Whether or not the file header will be written
+    pub rewrites_header: bool,
+
+    /// The amount of data-records on disk which will be moved to a different position within the file
+    pub moved_records: usize,
+
+    /// The amount of data-records on disk which will be written again, either because they are moved or because
+    /// their content changes (e.g. due to signal changes or shifted onsets)
+    pub rewritten_records: usize,
+
+    /// The amount of inserted or updated data-records which will be written
+    pub pending_records: usize,
+
+    /// The total amount of bytes which will be written using the configured `SaveStrategy`
+    pub written_bytes: u64,
+
+    /// Whether or not a continuous EDF+ file will become discontinuous due to removed data-records
+    pub becomes_discontinuous: bool,
+
+    /// The size of the file before saving in bytes
+    pub initial_file_size: u64,
+
+    /// The size of the file after saving in bytes
+    pub file_size: u64,
+}
+
+/// The origin of an item after applying all pending instructions
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResolvedValue<'a> {
+    /// The item is unchanged and located at the given index of the initial list
+    Initial(usize),
+
+    /// The item was inserted or updated and is only available in memory
+    Pending(&'a SaveValue),
+}
+
+impl ResolvedValue<'_> {
+    /// Returns the index of the item within the initial list or `None` in case the item is only available in memory
+    pub fn initial_index(&self) -> Option<usize> {
+        match self {
+            ResolvedValue::Initial(idx) => Some(*idx),
+            ResolvedValue::Pending(_) => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum SaveValue {
     Record(Record),
@@ -167,7 +492,11 @@ pub enum SaveInstruction {
     Insert(usize, SaveValue),
     Append(SaveValue),
     Remove(usize),
-    Patch,
+    Move(usize, usize),
+    Convert(EDFSpecifications),
+
+    /// Sets the options used to patch the samples of data-records for all following UPDATE instructions of signals
+    Patch(PatchOptions),
 }
 
 impl SaveInstruction {
@@ -0,0 +1,338 @@
+use chrono::{NaiveDate, NaiveTime};
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::EDFSpecifications;
+use crate::error::edf_error::EDFError;
+use crate::file::sync_parent_dir;
+use crate::headers::patient::{PatientId, Sex};
+use crate::headers::recording::RecordingId;
+use crate::headers::signal_header::SignalHeader;
+use crate::record::PatchOptions;
+use crate::resample::ResampleMethod;
+use crate::rescale::RescaleMode;
+
+/// Format of dates stored in session files
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Format of times stored in session files
+const TIME_FORMAT: &str = "%H:%M:%S%.f";
+
+/// Identifier at the beginning of every session file
+const SESSION_MAGIC: &[u8] = b"EDFRS-SESSION";
+
+/// The version of the session file format. Session files of other versions are rejected
+const SESSION_VERSION: u8 = 4;
+
+/// Returns the path of the session file belonging to the EDF file at the given path. The session
+/// file is located next to the EDF file and has the additional extension `.session`
+pub fn get_session_path<P: AsRef<Path>>(path: P) -> PathBuf {
+    let mut session_path = path.as_ref().as_os_str().to_owned();
+    session_path.push(".session");
+    PathBuf::from(session_path)
+}
+
+/// Writer for the binary session file format. All numbers are stored in little endian byte order
+/// and all variable sized values are prefixed with their length.
+pub(crate) struct SessionWriter {
+    buffer: Vec<u8>,
+}
+
+impl SessionWriter {
+    pub fn new() -> Self {
+        let mut buffer = SESSION_MAGIC.to_vec();
+        buffer.push(SESSION_VERSION);
+
+        Self { buffer }
+    }
+
+    pub fn write_u8(&mut self, value: u8) {
+        self.buffer.push(value);
+    }
+
+    pub fn write_bool(&mut self, value: bool) {
+        self.write_u8(value as u8);
+    }
+
+    pub fn write_u64(&mut self, value: u64) {
+        self.buffer.extend(value.to_le_bytes());
+    }
+
+    pub fn write_i64(&mut self, value: i64) {
+        self.buffer.extend(value.to_le_bytes());
+    }
+
+    pub fn write_f64(&mut self, value: f64) {
+        self.buffer.extend(value.to_le_bytes());
+    }
+
+    pub fn write_bytes(&mut self, value: &[u8]) {
+        self.write_u64(value.len() as u64);
+        self.buffer.extend(value);
+    }
+
+    pub fn write_str(&mut self, value: &str) {
+        self.write_bytes(value.as_bytes());
+    }
+
+    pub fn write_opt_str(&mut self, value: Option<&str>) {
+        self.write_bool(value.is_some());
+        if let Some(value) = value {
+            self.write_str(value);
+        }
+    }
+
+    pub fn write_date(&mut self, value: &NaiveDate) {
+        self.write_str(&value.format(DATE_FORMAT).to_string());
+    }
+
+    pub fn write_time(&mut self, value: &NaiveTime) {
+        self.write_str(&value.format(TIME_FORMAT).to_string());
+    }
+
+    pub fn write_specification(&mut self, specification: &EDFSpecifications) {
+        self.write_u8(match specification {
+            EDFSpecifications::EDF => 0,
+            EDFSpecifications::EDFPlus => 1,
+            EDFSpecifications::BDF => 2,
+            EDFSpecifications::BDFPlus => 3,
+        });
+    }
+
+    pub fn write_patch_options(&mut self, options: &PatchOptions) {
+        self.write_u8(match options.resample_method {
+            ResampleMethod::Resize => 0,
+            ResampleMethod::Linear => 1,
+            ResampleMethod::Polyphase => 2,
+        });
+        self.write_u8(match options.rescale_mode {
+            RescaleMode::KeepDigital => 0,
+            RescaleMode::KeepPhysical => 1,
+        });
+    }
+
+    pub fn write_patient_id(&mut self, patient_id: &PatientId) {
+        self.write_opt_str(patient_id.code.as_deref());
+        self.write_opt_str(patient_id.sex.as_ref().map(|sex| sex.to_string()).as_deref());
+        self.write_opt_str(patient_id.date.map(|date| date.format(DATE_FORMAT).to_string()).as_deref());
+        self.write_opt_str(patient_id.name.as_deref());
+        self.write_u64(patient_id.additional.len() as u64);
+        for value in &patient_id.additional {
+            self.write_opt_str(value.as_deref());
+        }
+    }
+
+    pub fn write_recording_id(&mut self, recording_id: &RecordingId) {
+        self.write_opt_str(recording_id.startdate.map(|date| date.format(DATE_FORMAT).to_string()).as_deref());
+        self.write_opt_str(recording_id.admin_code.as_deref());
+        self.write_opt_str(recording_id.technician.as_deref());
+        self.write_opt_str(recording_id.equipment.as_deref());
+        self.write_u64(recording_id.additional.len() as u64);
+        for value in &recording_id.additional {
+            self.write_opt_str(value.as_deref());
+        }
+    }
+
+    pub fn write_signal(&mut self, signal: &SignalHeader) {
+        self.write_str(&signal.label);
+        self.write_str(&signal.transducer);
+        self.write_str(&signal.physical_dimension);
+        self.write_f64(signal.physical_minimum);
+        self.write_f64(signal.physical_maximum);
+        self.write_i64(signal.digital_minimum as i64);
+        self.write_i64(signal.digital_maximum as i64);
+        self.write_str(&signal.prefilter);
+        self.write_u64(signal.samples_count as u64);
+        self.write_str(&signal.reserved);
+    }
+
+    /// Writes the session into a temporary file which then replaces the session file at the given path.
+    /// This ensures a previously written session file is never left in a partially written state.
+    pub fn finish<P: AsRef<Path>>(self, path: P) -> Result<(), EDFError> {
+        let mut temp_path = path.as_ref().as_os_str().to_owned();
+        temp_path.push(".tmp");
+
+        let result = File::create(&temp_path)
+            .and_then(|mut file| {
+                file.write_all(&self.buffer)?;
+                file.sync_all()
+            })
+            .and_then(|_| std::fs::rename(&temp_path, path.as_ref()));
+        if let Err(err) = result {
+            _ = std::fs::remove_file(&temp_path);
+            return Err(EDFError::FileWriteError(err));
+        }
+        sync_parent_dir(path.as_ref());
+
+        Ok(())
+    }
+}
+
+/// Reader for session files written by the `SessionWriter`
+pub(crate) struct SessionReader {
+    data: Vec<u8>,
+    position: usize,
+}
+
+impl SessionReader {
+    /// Reads the session file at the given path. Returns `None` in case no session file exists.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Option<Self>, EDFError> {
+        if !path.as_ref().exists() {
+            return Ok(None);
+        }
+
+        let data = std::fs::read(path).map_err(EDFError::FileReadError)?;
+        let mut reader = Self { data, position: 0 };
+        if reader.read_exact(SESSION_MAGIC.len())? != SESSION_MAGIC || reader.read_u8()? != SESSION_VERSION {
+            return Err(EDFError::InvalidSession);
+        }
+
+        Ok(Some(reader))
+    }
+
+    fn read_exact(&mut self, count: usize) -> Result<&[u8], EDFError> {
+        let end = self.position.checked_add(count).ok_or(EDFError::InvalidSession)?;
+        let value = self.data.get(self.position..end).ok_or(EDFError::InvalidSession)?;
+        self.position = end;
+
+        Ok(value)
+    }
+
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N], EDFError> {
+        self.read_exact(N)?
+            .try_into()
+            .map_err(|_| EDFError::InvalidSession)
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8, EDFError> {
+        Ok(self.read_array::<1>()?[0])
+    }
+
+    pub fn read_bool(&mut self) -> Result<bool, EDFError> {
+        match self.read_u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(EDFError::InvalidSession),
+        }
+    }
+
+    pub fn read_u64(&mut self) -> Result<u64, EDFError> {
+        Ok(u64::from_le_bytes(self.read_array()?))
+    }
+
+    pub fn read_usize(&mut self) -> Result<usize, EDFError> {
+        usize::try_from(self.read_u64()?).map_err(|_| EDFError::InvalidSession)
+    }
+
+    pub fn read_i64(&mut self) -> Result<i64, EDFError> {
+        Ok(i64::from_le_bytes(self.read_array()?))
+    }
+
+    pub fn read_f64(&mut self) -> Result<f64, EDFError> {
+        Ok(f64::from_le_bytes(self.read_array()?))
+    }
+
+    pub fn read_bytes(&mut self) -> Result<Vec<u8>, EDFError> {
+        let length = self.read_usize()?;
+        Ok(self.read_exact(length)?.to_vec())
+    }
+
+    pub fn read_string(&mut self) -> Result<String, EDFError> {
+        String::from_utf8(self.read_bytes()?).map_err(|_| EDFError::InvalidSession)
+    }
+
+    pub fn read_opt_string(&mut self) -> Result<Option<String>, EDFError> {
+        match self.read_bool()? {
+            true => Ok(Some(self.read_string()?)),
+            false => Ok(None),
+        }
+    }
+
+    pub fn read_date(&mut self) -> Result<NaiveDate, EDFError> {
+        NaiveDate::parse_from_str(&self.read_string()?, DATE_FORMAT).map_err(|_| EDFError::InvalidSession)
+    }
+
+    pub fn read_opt_date(&mut self) -> Result<Option<NaiveDate>, EDFError> {
+        self.read_opt_string()?
+            .map(|date| NaiveDate::parse_from_str(&date, DATE_FORMAT).map_err(|_| EDFError::InvalidSession))
+            .transpose()
+    }
+
+    pub fn read_time(&mut self) -> Result<NaiveTime, EDFError> {
+        NaiveTime::parse_from_str(&self.read_string()?, TIME_FORMAT).map_err(|_| EDFError::InvalidSession)
+    }
+
+    pub fn read_specification(&mut self) -> Result<EDFSpecifications, EDFError> {
+        match self.read_u8()? {
+            0 => Ok(EDFSpecifications::EDF),
+            1 => Ok(EDFSpecifications::EDFPlus),
+            2 => Ok(EDFSpecifications::BDF),
+            3 => Ok(EDFSpecifications::BDFPlus),
+            _ => Err(EDFError::InvalidSession),
+        }
+    }
+
+    pub fn read_patch_options(&mut self) -> Result<PatchOptions, EDFError> {
+        let resample_method = match self.read_u8()? {
+            0 => ResampleMethod::Resize,
+            1 => ResampleMethod::Linear,
+            2 => ResampleMethod::Polyphase,
+            _ => return Err(EDFError::InvalidSession),
+        };
+        let rescale_mode = match self.read_u8()? {
+            0 => RescaleMode::KeepDigital,
+            1 => RescaleMode::KeepPhysical,
+            _ => return Err(EDFError::InvalidSession),
+        };
+
+        Ok(PatchOptions { resample_method, rescale_mode })
+    }
+
+    pub fn read_patient_id(&mut self) -> Result<PatientId, EDFError> {
+        Ok(PatientId {
+            code: self.read_opt_string()?,
+            sex: self.read_opt_string()?
+                .map(|sex| Sex::from_str(&sex).map_err(|_| EDFError::InvalidSession))
+                .transpose()?,
+            date: self.read_opt_date()?,
+            name: self.read_opt_string()?,
+            additional: (0..self.read_usize()?)
+                .map(|_| self.read_opt_string())
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    pub fn read_recording_id(&mut self) -> Result<RecordingId, EDFError> {
+        Ok(RecordingId {
+            startdate: self.read_opt_date()?,
+            admin_code: self.read_opt_string()?,
+            technician: self.read_opt_string()?,
+            equipment: self.read_opt_string()?,
+            additional: (0..self.read_usize()?)
+                .map(|_| self.read_opt_string())
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    pub fn read_signal(&mut self) -> Result<SignalHeader, EDFError> {
+        Ok(SignalHeader {
+            label: self.read_string()?,
+            transducer: self.read_string()?,
+            physical_dimension: self.read_string()?,
+            physical_minimum: self.read_f64()?,
+            physical_maximum: self.read_f64()?,
+            digital_minimum: i32::try_from(self.read_i64()?).map_err(|_| EDFError::InvalidSession)?,
+            digital_maximum: i32::try_from(self.read_i64()?).map_err(|_| EDFError::InvalidSession)?,
+            prefilter: self.read_string()?,
+            samples_count: self.read_usize()?,
+            reserved: self.read_string()?,
+        })
+    }
+
+    /// Returns `true` in case the entire session file has been read
+    pub fn is_finished(&self) -> bool {
+        self.position == self.data.len()
+    }
+}
@@ -0,0 +1,71 @@
+use std::path::{Path, PathBuf};
+
+use crate::error::edf_error::EDFError;
+use crate::headers::annotation_list::AnnotationList;
+
+/// Describes the boundaries at which a file is split into multiple parts using `EDFFile::split`. Files are always
+/// split at data-record boundaries.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SplitMode {
+    /// Starts a new part every given amount of seconds relative to the start of the recording (e.g. `3600.0` for
+    /// one part per hour). The data-record containing a boundary starts the new part
+    Duration(f64),
+
+    /// Starts a new part every given amount of data-records
+    RecordCount(usize),
+
+    /// Starts a new part at every annotation with one of the given descriptions (e.g. "Lights off"). The data-record
+    /// containing the onset of the annotation (or the first data-record following it) starts the new part
+    Annotations(Vec<String>),
+}
+
+impl SplitMode {
+    /// Returns the index of the first data-record of every part, given the onset of every data-record and every
+    /// annotation of the file together with the index of the data-record it is stored in
+    pub(crate) fn part_starts(
+        &self,
+        onsets: &[f64],
+        annotations: &[(usize, AnnotationList)],
+        record_duration: f64,
+    ) -> Result<Vec<usize>, EDFError> {
+        let mut starts = match self {
+            SplitMode::Duration(duration) => {
+                if *duration <= 0.0 {
+                    return Err(EDFError::InvalidRecordDuration);
+                }
+                let part = |onset: f64| ((onset + 1e-9) / duration).floor();
+                (0..onsets.len())
+                    .filter(|idx| *idx == 0 || part(onsets[*idx]) != part(onsets[idx - 1]))
+                    .collect::<Vec<_>>()
+            }
+            SplitMode::RecordCount(count) => {
+                if *count == 0 {
+                    return Err(EDFError::InvalidRecordCount);
+                }
+                (0..onsets.len()).step_by(*count).collect()
+            }
+            SplitMode::Annotations(descriptions) => annotations
+                .iter()
+                .filter(|(_, tal)| tal.annotations.iter().any(|annotation| descriptions.contains(annotation)))
+                .filter_map(|(_, tal)| onsets.iter().position(|onset| onset + record_duration > tal.onset + 1e-9))
+                .chain((!onsets.is_empty()).then_some(0))
+                .collect(),
+        };
+        starts.sort();
+        starts.dedup();
+
+        Ok(starts)
+    }
+}
+
+/// Returns the path of the part with the given number by appending the number to the file name of the given path
+/// (e.g. `night.edf` becomes `night_2.edf`)
+pub(crate) fn part_path(path: &Path, part: usize) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match path.extension() {
+        Some(extension) => format!("{stem}_{part}.{}", extension.to_string_lossy()),
+        None => format!("{stem}_{part}"),
+    };
+
+    path.with_file_name(name)
+}
@@ -3,15 +3,30 @@ mod file_edit_tests {
     use chrono::{NaiveDate, NaiveTime};
     use std::fs::{self, exists, remove_file};
     use std::iter::repeat_n;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
 
     use crate::EDFSpecifications;
-    use crate::file::EDFFile;
+    use crate::anonymize::{AnonymizationChange, AnonymizeOptions, DateShift, IdentifierReplacement};
+    use crate::error::edf_error::EDFError;
+    use crate::extract::SignalSelector;
+    use crate::file::{EDFFile, RecordDeleteStrategy, SaveStrategy};
+    use crate::gaps::{GapFillOptions, GapFillValue, GapStrategy};
     use crate::headers::annotation_list::AnnotationList;
     use crate::headers::edf_header::EDFHeader;
     use crate::headers::patient::{PatientId, Sex};
     use crate::headers::recording::RecordingId;
     use crate::headers::signal_header::SignalHeader;
+    use crate::merge::{MergeIncompatibility, check_merge_compatibility, merge_files};
+    use crate::progress::{CancellationToken, Progress, ProgressOperation};
     use crate::record::{Record, Samples};
+    use crate::repair::{PartialRecordStrategy, RepairFix};
+    use crate::resample::ResampleMethod;
+    use crate::rescale::{ClippingStatistics, RescaleMode};
+    use crate::save::SaveInstruction;
+    use crate::session::get_session_path;
+    use crate::split::SplitMode;
+    use crate::validate::{Severity, Violation, ViolationKind, ViolationLocation};
 
     #[test]
     fn test_remove_all_signals() {
@@ -1655,169 +1670,2304 @@ mod file_edit_tests {
         remove_file(path_actual).unwrap();
     }
 
-    // =====================================
-    // =              HELPERS              =
-    // =====================================
+    #[test]
+    fn test_remove_record_continuous() {
+        let (path_actual, path_expected) = get_paths("remove_record_continuous");
+        let path_actual = generate_time_keeping_test_edf(&path_actual);
 
-    fn get_paths(name: &str) -> (String, String) {
-        let name_actual = name.to_string() + "_actual";
-        let name_expected = name.to_string() + "_expected";
-        let path_actual = generate_test_edf(&name_actual);
+        // ============== ACT ===============
 
-        // Clean previous test file
-        let path_expected = generate_file_path(&name_expected);
-        if exists(&path_expected).unwrap() {
-            remove_file(&path_expected).unwrap();
-        }
+        let mut edf_actual = EDFFile::open(&path_actual).unwrap();
+        edf_actual.set_record_delete_strategy(RecordDeleteStrategy::Continuous);
 
-        (path_actual, path_expected)
-    }
+        // Modify records
+        edf_actual.remove_record(1).unwrap();
+        edf_actual.remove_record(2).unwrap();
 
-    fn generate_default_signal1() -> SignalHeader {
-        let mut signal1 = SignalHeader::new();
-        signal1
-            .with_label("Signal1".to_string())
-            .with_transducer("Unknown1".to_string())
-            .with_physical_dimension("uV".to_string())
-            .with_prefilter("".to_string())
-            .with_physical_range(-1234.0, 1233.0)
-            .with_digital_range(-1024, 1024)
-            .with_samples_count(100);
+        // Apply modifications
+        edf_actual.save().unwrap();
 
-        signal1
-    }
+        // ============== EXPECTED ===============
 
-    fn generate_default_signal2() -> SignalHeader {
-        let mut signal2 = SignalHeader::new();
-        signal2
-            .with_label("Signal2".to_string())
-            .with_transducer("Unknown2".to_string())
-            .with_physical_dimension("uV".to_string())
-            .with_prefilter("HP:0.1Hz LP:75Hz".to_string())
-            .with_physical_range(-512.0, 512.0)
-            .with_digital_range(-64, 64)
-            .with_samples_count(127);
+        // Create new EDF file
+        let mut edf_expected = EDFFile::new(&path_expected).unwrap();
+        configure_default_header(&mut edf_expected.header);
 
-        signal2
-    }
+        // Create signals
+        edf_expected
+            .insert_signal(0, generate_default_signal1())
+            .unwrap();
+        edf_expected
+            .insert_signal(1, generate_default_signal2())
+            .unwrap();
+        edf_expected
+            .insert_signal(2, generate_default_annotations())
+            .unwrap();
 
-    fn generate_upsampled_signal2() -> SignalHeader {
-        let mut signal2 = generate_default_signal2();
-        signal2.with_samples_count(210);
-        signal2
-    }
+        // Create records with the onsets shifted by the removed records
+        edf_expected
+            .append_record(generate_time_keeping_record(&edf_expected, 0, 0.0))
+            .unwrap();
+        edf_expected
+            .append_record(generate_time_keeping_record(&edf_expected, 2, 1.0))
+            .unwrap();
+        edf_expected
+            .append_record(generate_time_keeping_record(&edf_expected, 4, 2.0))
+            .unwrap();
 
-    fn generate_downsampled_signal2() -> SignalHeader {
-        let mut signal2 = generate_default_signal2();
-        signal2.with_samples_count(61);
-        signal2
-    }
+        // Create the file
+        edf_expected.save().unwrap();
 
-    fn generate_default_annotations() -> SignalHeader {
-        SignalHeader::new_annotation(80, EDFSpecifications::EDF)
-    }
+        // ============== ASSERT ===============
 
-    fn generate_custom_signal_record<T: Fn(usize) -> Samples>(
-        edf: &EDFFile,
-        index: usize,
-        samples: Vec<T>,
-    ) -> Record {
-        let mut record = edf.header.create_record();
-        record.raw_signal_samples = samples.iter().map(|genr| genr(index)).collect();
-        record.annotations = vec![vec![
-            AnnotationList::new(0.0, 0.0, vec![format!("GlobalAnnotation {}", index)]).unwrap(),
-        ]];
+        let data_expected = fs::read(&path_expected).unwrap();
+        let data_actual = fs::read(&path_actual).unwrap();
+        assert!(edf_actual.header.is_continuous());
+        assert_eq!(data_expected, data_actual);
 
-        record
+        // ============== CLEANUP ==============
+
+        remove_file(path_expected).unwrap();
+        remove_file(path_actual).unwrap();
     }
 
-    fn generate_default_record(edf: &EDFFile, index: usize) -> Record {
-        let mut record = edf.header.create_record();
-        record.raw_signal_samples = vec![
-            generate_default_signal1_data(index),
-            generate_default_signal2_data(index),
-        ];
-        record.annotations = vec![vec![
-            AnnotationList::new(0.0, 0.0, vec![format!("GlobalAnnotation {}", index)]).unwrap(),
-        ]];
+    #[test]
+    fn test_remove_record_discontinuous() {
+        let (path_actual, path_expected) = get_paths("remove_record_discontinuous");
+        let path_actual = generate_time_keeping_test_edf(&path_actual);
 
-        record
-    }
+        // ============== ACT ===============
 
-    fn generate_default_signal2_data_upsampled(index: usize) -> Samples {
-        let mut default_data = generate_default_signal2_data(index);
-        assert!(default_data.extend(&Samples::Values16Bit(repeat_n(0, 210 - default_data.len()).collect())).is_ok());
-        default_data
-    }
+        let mut edf_actual = EDFFile::open(&path_actual).unwrap();
 
-    fn generate_default_signal2_data_downsampled(index: usize) -> Samples {
-        Samples::Values16Bit(((0 + 25 * index as i16)..(61 + 25 * index as i16)).collect())
-    }
+        // Modify records
+        edf_actual.remove_record(1).unwrap();
 
-    fn generate_default_signal2_data(index: usize) -> Samples {
-        Samples::Values16Bit(((0 + 25 * index as i16)..(127 + 25 * index as i16)).collect())
-    }
+        // Apply modifications
+        edf_actual.save().unwrap();
 
-    fn generate_default_signal1_data(index: usize) -> Samples {
-        Samples::Values16Bit(((0 + 25 * index as i16)..(100 + 25 * index as i16)).collect())
+        // ============== EXPECTED ===============
+
+        // Create new EDF file
+        let mut edf_expected = EDFFile::new(&path_expected).unwrap();
+        configure_default_header(&mut edf_expected.header);
+        edf_expected.header.with_is_continuous(false);
+
+        // Create signals
+        edf_expected
+            .insert_signal(0, generate_default_signal1())
+            .unwrap();
+        edf_expected
+            .insert_signal(1, generate_default_signal2())
+            .unwrap();
+        edf_expected
+            .insert_signal(2, generate_default_annotations())
+            .unwrap();
+
+        // Create records keeping their original onsets
+        edf_expected
+            .append_record(generate_time_keeping_record(&edf_expected, 0, 0.0))
+            .unwrap();
+        edf_expected
+            .append_record(generate_time_keeping_record(&edf_expected, 2, 2.0))
+            .unwrap();
+        edf_expected
+            .append_record(generate_time_keeping_record(&edf_expected, 3, 3.0))
+            .unwrap();
+        edf_expected
+            .append_record(generate_time_keeping_record(&edf_expected, 4, 4.0))
+            .unwrap();
+
+        // Create the file
+        edf_expected.save().unwrap();
+
+        // ============== ASSERT ===============
+
+        let data_expected = fs::read(&path_expected).unwrap();
+        let data_actual = fs::read(&path_actual).unwrap();
+        assert!(!edf_actual.header.is_continuous());
+        assert_eq!(data_expected, data_actual);
+
+        // ============== CLEANUP ==============
+
+        remove_file(path_expected).unwrap();
+        remove_file(path_actual).unwrap();
     }
 
-    fn configure_default_header(edf_header: &mut EDFHeader) {
-        edf_header
-            .with_specification(EDFSpecifications::EDFPlus)
-            .with_is_continuous(true)
-            .with_patient_id(PatientId {
-                code: Some("PAT-CODE1".to_string()),
-                name: Some("Pat-NAME".to_string()),
-                date: Some(NaiveDate::from_ymd_opt(2001, 07, 11).unwrap()),
-                sex: Some(Sex::Male),
-                additional: Vec::new(),
-            })
-            .with_recording_id(RecordingId {
-                admin_code: Some("REC-CODE1".to_string()),
-                equipment: Some("EQUIPMENT".to_string()),
-                technician: Some("TECHNICIAN".to_string()),
-                startdate: Some(NaiveDate::from_ymd_opt(2026, 02, 13).unwrap()),
-                additional: Vec::new(),
+    #[test]
+    fn test_read_pending_records() {
+        let (path_actual, path_expected) = get_paths("read_pending_records");
+
+        // ============== ACT ===============
+
+        let mut edf_actual = EDFFile::open(&path_actual).unwrap();
+
+        // Modify records
+        edf_actual
+            .insert_record(2, generate_default_record(&edf_actual, 30))
+            .unwrap();
+        edf_actual.remove_record(4).unwrap();
+        edf_actual
+            .update_record(0, generate_default_record(&edf_actual, 31))
+            .unwrap();
+
+        // Modify signals
+        edf_actual.remove_signal(0).unwrap();
+
+        // Read all records before saving
+        edf_actual.seek_to_record(0).unwrap();
+        let mut records_pending = Vec::new();
+        while let Some(record) = edf_actual.read_record().unwrap() {
+            records_pending.push(record);
+        }
+
+        // Apply modifications and read all records again
+        edf_actual.save().unwrap();
+        edf_actual.seek_to_record(0).unwrap();
+        let mut records_saved = Vec::new();
+        while let Some(record) = edf_actual.read_record().unwrap() {
+            records_saved.push(record);
+        }
+
+        // ============== EXPECTED ===============
+
+        let records_expected = [31, 1, 30, 2, 4]
+            .into_iter()
+            .map(|i| {
+                (
+                    vec![generate_default_signal2_data(i)],
+                    vec![vec![
+                        AnnotationList::new(0.0, 0.0, vec![format!("GlobalAnnotation {}", i)]).unwrap(),
+                    ]],
+                )
             })
-            .with_start_date(NaiveDate::from_ymd_opt(2026, 02, 13).unwrap())
-            .with_start_time(NaiveTime::from_hms_opt(17, 30, 0).unwrap())
-            .with_record_duration(1.0);
-    }
+            .collect::<Vec<_>>();
 
-    fn generate_file_path(name: &str) -> String {
-        format!("code_tests/test_{}.edf", name)
-    }
+        // ============== ASSERT ===============
 
-    // =====================================
-    // =         DEFAULT TEST FILE         =
-    // =====================================
+        let to_data = |records: Vec<Record>| {
+            records
+                .into_iter()
+                .map(|r| (r.raw_signal_samples, r.annotations))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(to_data(records_pending), records_expected);
+        assert_eq!(to_data(records_saved), records_expected);
 
-    fn generate_test_edf(name: &str) -> String {
-        // Clean previous test file
-        let path = generate_file_path(name);
-        if exists(&path).unwrap() {
-            remove_file(&path).unwrap();
+        // ============== CLEANUP ==============
+
+        if exists(&path_expected).unwrap() {
+            remove_file(path_expected).unwrap();
         }
+        remove_file(path_actual).unwrap();
+    }
 
-        // Create new EDF file
-        let mut edf = EDFFile::new(&path).unwrap();
+    #[test]
+    fn test_atomic_save() {
+        let (path_actual, path_expected) = get_paths("atomic_save");
 
-        // Configure header with defaults
-        configure_default_header(&mut edf.header);
+        // ============== ACT ===============
 
-        // Insert the 2 default signals and the annotations
-        edf.insert_signal(0, generate_default_signal1()).unwrap();
-        edf.insert_signal(1, generate_default_signal2()).unwrap();
-        edf.insert_signal(2, generate_default_annotations())
+        let mut edf_actual = EDFFile::open(&path_actual).unwrap();
+        edf_actual.set_save_strategy(SaveStrategy::Atomic);
+
+        // Modify records
+        edf_actual.remove_record(0).unwrap();
+        edf_actual
+            .insert_record(1, generate_default_record(&edf_actual, 30))
+            .unwrap();
+        edf_actual
+            .update_record(3, generate_default_record(&edf_actual, 31))
             .unwrap();
 
-        // Insert 5 default records
-        edf.append_record(generate_default_record(&edf, 0)).unwrap();
-        edf.append_record(generate_default_record(&edf, 1)).unwrap();
-        edf.append_record(generate_default_record(&edf, 2)).unwrap();
-        edf.append_record(generate_default_record(&edf, 3)).unwrap();
-        edf.append_record(generate_default_record(&edf, 4)).unwrap();
+        // Modify signals
+        edf_actual.remove_signal(1).unwrap();
+
+        // Apply modifications
+        edf_actual.save().unwrap();
+
+        // ============== EXPECTED ===============
+
+        // Create new EDF file
+        let mut edf_expected = EDFFile::new(&path_expected).unwrap();
+        configure_default_header(&mut edf_expected.header);
+
+        // Create signals
+        edf_expected
+            .insert_signal(0, generate_default_signal1())
+            .unwrap();
+        edf_expected
+            .insert_signal(1, generate_default_annotations())
+            .unwrap();
+
+        // Create records
+        for i in [1, 30, 2, 31, 4] {
+            edf_expected
+                .append_record(generate_custom_signal_record(
+                    &edf_expected,
+                    i,
+                    vec![generate_default_signal1_data],
+                ))
+                .unwrap();
+        }
+
+        // Create the file
+        edf_expected.save().unwrap();
+
+        // ============== ASSERT ===============
+
+        let data_expected = fs::read(&path_expected).unwrap();
+        let data_actual = fs::read(&path_actual).unwrap();
+        assert_eq!(data_expected, data_actual);
+        assert!(!exists(format!("{}.tmp", path_actual)).unwrap());
+
+        // The reader has to point to the new file after saving
+        let record = edf_actual.read_record_at(1).unwrap().unwrap();
+        assert_eq!(record.raw_signal_samples, vec![generate_default_signal1_data(30)]);
+
+        // ============== CLEANUP ==============
+
+        remove_file(path_expected).unwrap();
+        remove_file(path_actual).unwrap();
+    }
+
+    #[test]
+    fn test_in_place_matches_atomic() {
+        let (path_actual, path_expected) = get_paths("in_place_matches_atomic");
+        generate_test_edf("in_place_matches_atomic_expected");
+
+        let grow = |edf: &mut EDFFile| {
+            // Insert records at the start causing all subsequent records to move towards the end of the file
+            edf.insert_record(0, generate_default_record(edf, 40)).unwrap();
+            edf.insert_record(0, generate_default_record(edf, 41)).unwrap();
+            edf.remove_record(4).unwrap();
+            edf.append_record(generate_default_record(edf, 42)).unwrap();
+
+            // Insert a signal causing the header and every record to grow
+            let mut signal = generate_default_signal2();
+            signal.with_label("Signal3".to_string());
+            edf.insert_signal(1, signal).unwrap();
+        };
+        let shrink = |edf: &mut EDFFile| {
+            // Remove records and signals causing all subsequent records to move towards the start of the file
+            edf.remove_signal(0).unwrap();
+            edf.remove_record(0).unwrap();
+            edf.remove_record(1).unwrap();
+            edf.insert_record(3, generate_custom_signal_record(edf, 43, vec![
+                generate_default_signal2_data,
+                generate_default_signal2_data,
+            ])).unwrap();
+        };
+
+        let mut edf_actual = EDFFile::open(&path_actual).unwrap();
+        let mut edf_expected = EDFFile::open(&path_expected).unwrap();
+        edf_expected.set_save_strategy(SaveStrategy::Atomic);
+
+        for edit in [&grow as &dyn Fn(&mut EDFFile), &shrink] {
+            // ============== ACT ===============
+
+            edit(&mut edf_actual);
+            edf_actual.save().unwrap();
+
+            // ============== EXPECTED ===============
+
+            edit(&mut edf_expected);
+            edf_expected.save().unwrap();
+
+            // ============== ASSERT ===============
+
+            let data_expected = fs::read(&path_expected).unwrap();
+            let data_actual = fs::read(&path_actual).unwrap();
+            assert_eq!(data_expected, data_actual);
+        }
+
+        // ============== CLEANUP ==============
+
+        remove_file(path_expected).unwrap();
+        remove_file(path_actual).unwrap();
+    }
+
+    #[test]
+    fn test_save_as() {
+        let (path_actual, path_expected) = get_paths("save_as");
+        let path_target = generate_file_path("save_as_target");
+        if exists(&path_target).unwrap() {
+            remove_file(&path_target).unwrap();
+        }
+        let data_initial = fs::read(&path_actual).unwrap();
+
+        // ============== ACT ===============
+
+        let mut edf_actual = EDFFile::open(&path_actual).unwrap();
+        edf_actual.remove_record(0).unwrap();
+        edf_actual.remove_signal(1).unwrap();
+        edf_actual.save_as(&path_target).unwrap();
+
+        // ============== EXPECTED ===============
+
+        // Create new EDF file
+        let mut edf_expected = EDFFile::new(&path_expected).unwrap();
+        configure_default_header(&mut edf_expected.header);
+
+        // Create signals
+        edf_expected
+            .insert_signal(0, generate_default_signal1())
+            .unwrap();
+        edf_expected
+            .insert_signal(1, generate_default_annotations())
+            .unwrap();
+
+        // Create records
+        for i in 1..5 {
+            edf_expected
+                .append_record(generate_custom_signal_record(
+                    &edf_expected,
+                    i,
+                    vec![generate_default_signal1_data],
+                ))
+                .unwrap();
+        }
+
+        // Create the file
+        edf_expected.save().unwrap();
+
+        // ============== ASSERT ===============
+
+        let data_expected = fs::read(&path_expected).unwrap();
+        let data_target = fs::read(&path_target).unwrap();
+        assert_eq!(data_expected, data_target);
+        assert_eq!(data_initial, fs::read(&path_actual).unwrap());
+
+        // The file must point to the new file after saving
+        let record = edf_actual.read_record_at(0).unwrap().unwrap();
+        assert_eq!(record.raw_signal_samples, vec![generate_default_signal1_data(1)]);
+        edf_actual.remove_record(0).unwrap();
+        edf_actual.save().unwrap();
+        assert_eq!(data_initial, fs::read(&path_actual).unwrap());
+        assert_ne!(data_target, fs::read(&path_target).unwrap());
+
+        // Existing files must never be overwritten
+        assert!(matches!(edf_actual.save_as(&path_actual), Err(EDFError::FileAlreadyExists)));
+
+        // ============== CLEANUP ==============
+
+        remove_file(path_expected).unwrap();
+        remove_file(path_actual).unwrap();
+        remove_file(path_target).unwrap();
+    }
+
+    #[test]
+    fn test_export_to() {
+        let (path_actual, path_expected) = get_paths("export_to");
+        let data_initial = fs::read(&path_actual).unwrap();
+
+        // ============== ACT ===============
+
+        let mut edf_actual = EDFFile::open(&path_actual).unwrap();
+        edf_actual
+            .insert_record(1, generate_default_record(&edf_actual, 30))
+            .unwrap();
+        edf_actual.remove_signal(1).unwrap();
+
+        let mut exported = Vec::new();
+        let written = edf_actual.export_to(&mut exported).unwrap();
+
+        // ============== EXPECTED ===============
+
+        // Create new EDF file
+        let mut edf_expected = EDFFile::new(&path_expected).unwrap();
+        configure_default_header(&mut edf_expected.header);
+
+        // Create signals
+        edf_expected
+            .insert_signal(0, generate_default_signal1())
+            .unwrap();
+        edf_expected
+            .insert_signal(1, generate_default_annotations())
+            .unwrap();
+
+        // Create records
+        for i in [0, 30, 1, 2, 3, 4] {
+            edf_expected
+                .append_record(generate_custom_signal_record(
+                    &edf_expected,
+                    i,
+                    vec![generate_default_signal1_data],
+                ))
+                .unwrap();
+        }
+
+        // Create the file
+        edf_expected.save().unwrap();
+
+        // ============== ASSERT ===============
+
+        let data_expected = fs::read(&path_expected).unwrap();
+        assert_eq!(data_expected, exported);
+        assert_eq!(written, exported.len() as u64);
+        assert_eq!(data_initial, fs::read(&path_actual).unwrap());
+
+        // The pending changes must remain unchanged after exporting
+        edf_actual.save().unwrap();
+        assert_eq!(data_expected, fs::read(&path_actual).unwrap());
+
+        // ============== CLEANUP ==============
+
+        remove_file(path_expected).unwrap();
+        remove_file(path_actual).unwrap();
+    }
+
+    #[test]
+    fn test_undo_redo() {
+        let (path_actual, path_expected) = get_paths("undo_redo");
+
+        // ============== ACT ===============
+
+        let mut edf_actual = EDFFile::open(&path_actual).unwrap();
+        assert!(!edf_actual.undo().unwrap());
+
+        // Revert a signal removal which discarded the samples of a pending record
+        edf_actual
+            .insert_record(1, generate_default_record(&edf_actual, 30))
+            .unwrap();
+        edf_actual.remove_signal(1).unwrap();
+        assert!(edf_actual.undo().unwrap());
+        assert_eq!(edf_actual.header.get_signals().len(), 3);
+
+        // Revert a signal insertion
+        edf_actual.insert_signal(0, generate_default_signal2()).unwrap();
+        assert!(edf_actual.undo().unwrap());
+
+        // Revert and re-apply record edits
+        edf_actual.remove_record(0).unwrap();
+        assert!(edf_actual.undo().unwrap());
+        edf_actual
+            .update_record(2, generate_default_record(&edf_actual, 31))
+            .unwrap();
+        assert!(edf_actual.undo().unwrap());
+        assert!(edf_actual.can_redo());
+        assert!(edf_actual.redo().unwrap());
+        assert!(!edf_actual.redo().unwrap());
+
+        // Any new edit discards the edits which could be re-applied
+        edf_actual.remove_signal(0).unwrap();
+        assert!(edf_actual.undo().unwrap());
+        edf_actual
+            .append_record(generate_default_record(&edf_actual, 32))
+            .unwrap();
+        assert!(!edf_actual.can_redo());
+
+        // Apply modifications
+        edf_actual.save().unwrap();
+        assert!(!edf_actual.can_undo());
+
+        // ============== EXPECTED ===============
+
+        // Create new EDF file
+        let mut edf_expected = EDFFile::new(&path_expected).unwrap();
+        configure_default_header(&mut edf_expected.header);
+
+        // Create signals
+        edf_expected
+            .insert_signal(0, generate_default_signal1())
+            .unwrap();
+        edf_expected
+            .insert_signal(1, generate_default_signal2())
+            .unwrap();
+        edf_expected
+            .insert_signal(2, generate_default_annotations())
+            .unwrap();
+
+        // Create records
+        for i in [0, 30, 31, 2, 3, 4, 32] {
+            edf_expected
+                .append_record(generate_default_record(&edf_expected, i))
+                .unwrap();
+        }
+
+        // Create the file
+        edf_expected.save().unwrap();
+
+        // ============== ASSERT ===============
+
+        let data_expected = fs::read(&path_expected).unwrap();
+        let data_actual = fs::read(&path_actual).unwrap();
+        assert_eq!(data_expected, data_actual);
+
+        // ============== CLEANUP ==============
+
+        remove_file(path_expected).unwrap();
+        remove_file(path_actual).unwrap();
+    }
+
+    #[test]
+    fn test_resume_session() {
+        let (path_actual, path_expected) = get_paths("resume_session");
+        let session_path = get_session_path(&path_actual);
+        let data_initial = fs::read(&path_actual).unwrap();
+
+        // ============== ACT ===============
+
+        let mut edf_actual = EDFFile::open(&path_actual).unwrap();
+        edf_actual
+            .insert_record(1, generate_default_record(&edf_actual, 30))
+            .unwrap();
+        edf_actual.remove_signal(1).unwrap();
+        edf_actual
+            .update_record(3, generate_custom_signal_record(&edf_actual, 31, vec![generate_default_signal1_data]))
+            .unwrap();
+        edf_actual
+            .append_record(generate_custom_signal_record(&edf_actual, 32, vec![generate_default_signal1_data]))
+            .unwrap();
+        edf_actual.undo().unwrap();
+        edf_actual
+            .header
+            .with_start_time(NaiveTime::from_hms_opt(10, 0, 0).unwrap());
+
+        // Store the session and resume it after re-opening the file
+        edf_actual.save_session().unwrap();
+        drop(edf_actual);
+        assert_eq!(data_initial, fs::read(&path_actual).unwrap());
+
+        // Opening the file does not apply the session until it is resumed explicitly
+        let mut edf_actual = EDFFile::open(&path_actual).unwrap();
+        assert!(edf_actual.has_session());
+        assert!(!edf_actual.can_undo());
+        assert!(edf_actual.resume_session().unwrap());
+        assert!(edf_actual.redo().unwrap());
+        edf_actual.save().unwrap();
+        assert!(!exists(&session_path).unwrap());
+
+        // ============== EXPECTED ===============
+
+        // Create new EDF file
+        let mut edf_expected = EDFFile::new(&path_expected).unwrap();
+        configure_default_header(&mut edf_expected.header);
+        edf_expected
+            .header
+            .with_start_time(NaiveTime::from_hms_opt(10, 0, 0).unwrap());
+
+        // Create signals
+        edf_expected
+            .insert_signal(0, generate_default_signal1())
+            .unwrap();
+        edf_expected
+            .insert_signal(1, generate_default_annotations())
+            .unwrap();
+
+        // Create records
+        for i in [0, 30, 1, 31, 3, 4, 32] {
+            edf_expected
+                .append_record(generate_custom_signal_record(
+                    &edf_expected,
+                    i,
+                    vec![generate_default_signal1_data],
+                ))
+                .unwrap();
+        }
+
+        // Create the file
+        edf_expected.save().unwrap();
+
+        // ============== ASSERT ===============
+
+        let data_expected = fs::read(&path_expected).unwrap();
+        let data_actual = fs::read(&path_actual).unwrap();
+        assert_eq!(data_expected, data_actual);
+
+        // Sessions of files which were changed afterwards must not be resumed
+        edf_actual.remove_record(0).unwrap();
+        edf_actual.save_session().unwrap();
+        fs::write(&path_actual, &data_initial).unwrap();
+        let mut edf_actual = EDFFile::open(&path_actual).unwrap();
+        assert!(matches!(edf_actual.resume_session(), Err(EDFError::SessionMismatch)));
+        assert!(!edf_actual.can_undo());
+        edf_actual.discard_session().unwrap();
+        assert!(!exists(&session_path).unwrap());
+
+        // ============== CLEANUP ==============
+
+        remove_file(path_expected).unwrap();
+        remove_file(path_actual).unwrap();
+    }
+
+    #[test]
+    fn test_plan_save() {
+        let (path_actual, _) = get_paths("plan_save");
+        let data_initial = fs::read(&path_actual).unwrap();
+
+        // ============== ACT ===============
+
+        let mut edf_actual = EDFFile::open(&path_actual).unwrap();
+        let plan_empty = edf_actual.plan_save().unwrap();
+        edf_actual.remove_record(1).unwrap();
+        let plan = edf_actual.plan_save().unwrap();
+
+        // ============== ASSERT ===============
+
+        let header_bytes = edf_actual.header.get_header_bytes() as u64;
+        let record_bytes = edf_actual.header.data_record_bytes() as u64;
+        assert_eq!(plan_empty.written_bytes, 0);
+        assert_eq!(plan_empty.file_size, data_initial.len() as u64);
+        assert_eq!(plan.record_instructions, vec![SaveInstruction::WriteHeader, SaveInstruction::Remove(1)]);
+        assert!(plan.signal_instructions.is_empty());
+        assert!(plan.rewrites_header);
+        assert!(plan.becomes_discontinuous);
+        assert_eq!(plan.moved_records, 3);
+        assert_eq!(plan.rewritten_records, 3);
+        assert_eq!(plan.pending_records, 0);
+        assert_eq!(plan.written_bytes, header_bytes + 3 * record_bytes);
+        assert_eq!(plan.initial_file_size, data_initial.len() as u64);
+        assert_eq!(plan.file_size, data_initial.len() as u64 - record_bytes);
+
+        // Planning must not modify the file or the pending changes
+        assert_eq!(data_initial, fs::read(&path_actual).unwrap());
+        assert!(edf_actual.header.is_continuous());
+        edf_actual.save().unwrap();
+        assert_eq!(fs::read(&path_actual).unwrap().len() as u64, plan.file_size);
+        assert!(!edf_actual.header.is_continuous());
+
+        // The atomic strategy always writes the entire file
+        edf_actual.set_save_strategy(SaveStrategy::Atomic);
+        edf_actual.remove_record(3).unwrap();
+        let plan = edf_actual.plan_save().unwrap();
+        assert_eq!(plan.written_bytes, plan.file_size);
+
+        // ============== CLEANUP ==============
+
+        remove_file(path_actual).unwrap();
+    }
+
+    #[test]
+    fn test_move_signal() {
+        let (path_actual, path_expected) = get_paths("move_signal");
+
+        // ============== ACT ===============
+
+        let mut edf_actual = EDFFile::open(&path_actual).unwrap();
+        edf_actual
+            .insert_record(1, generate_default_record(&edf_actual, 30))
+            .unwrap();
+        edf_actual.move_signal(2, 0).unwrap();
+        edf_actual.move_signal(2, 1).unwrap();
+        assert!(edf_actual.move_signal(3, 0).is_err());
+
+        // Moving a signal back and forth has no effect
+        edf_actual.move_signal(0, 2).unwrap();
+        edf_actual.move_signal(2, 0).unwrap();
+        edf_actual.move_signal(1, 2).unwrap();
+        assert!(edf_actual.undo().unwrap());
+
+        // Moves are kept when resuming a session
+        edf_actual.save_session().unwrap();
+        drop(edf_actual);
+        let mut edf_actual = EDFFile::open(&path_actual).unwrap();
+        assert!(edf_actual.resume_session().unwrap());
+        edf_actual
+            .append_record(generate_custom_signal_record(
+                &edf_actual,
+                31,
+                vec![generate_default_signal2_data, generate_default_signal1_data],
+            ))
+            .unwrap();
+
+        // Apply modifications
+        edf_actual.save().unwrap();
+
+        // ============== EXPECTED ===============
+
+        // Create new EDF file
+        let mut edf_expected = EDFFile::new(&path_expected).unwrap();
+        configure_default_header(&mut edf_expected.header);
+
+        // Create signals
+        edf_expected
+            .insert_signal(0, generate_default_annotations())
+            .unwrap();
+        edf_expected
+            .insert_signal(1, generate_default_signal2())
+            .unwrap();
+        edf_expected
+            .insert_signal(2, generate_default_signal1())
+            .unwrap();
+
+        // Create records
+        for i in [0, 30, 1, 2, 3, 4, 31] {
+            edf_expected
+                .append_record(generate_custom_signal_record(
+                    &edf_expected,
+                    i,
+                    vec![generate_default_signal2_data, generate_default_signal1_data],
+                ))
+                .unwrap();
+        }
+
+        // Create the file
+        edf_expected.save().unwrap();
+
+        // ============== ASSERT ===============
+
+        let data_expected = fs::read(&path_expected).unwrap();
+        let data_actual = fs::read(&path_actual).unwrap();
+        assert_eq!(data_expected, data_actual);
+
+        // ============== CLEANUP ==============
+
+        remove_file(path_expected).unwrap();
+        remove_file(path_actual).unwrap();
+    }
+
+    #[test]
+    fn test_resample_signal() {
+        let (path_actual, path_expected) = get_paths("resample_signal");
+
+        // ============== ACT ===============
+
+        let mut edf_actual = EDFFile::open(&path_actual).unwrap();
+        edf_actual.set_resample_method(ResampleMethod::Linear);
+        edf_actual
+            .insert_record(1, generate_default_record(&edf_actual, 30))
+            .unwrap();
+        edf_actual
+            .update_signal(0, generate_default_signal1().with_samples_count(50).clone())
+            .unwrap();
+
+        // The records stored on disk have to be resampled using the method in effect when updating the signal
+        edf_actual.set_resample_method(ResampleMethod::Resize);
+
+        // Apply modifications
+        edf_actual.save().unwrap();
+
+        // ============== EXPECTED ===============
+
+        // Create new EDF file
+        let mut edf_expected = EDFFile::new(&path_expected).unwrap();
+        configure_default_header(&mut edf_expected.header);
+
+        // Create signals
+        edf_expected
+            .insert_signal(0, generate_default_signal1().with_samples_count(50).clone())
+            .unwrap();
+        edf_expected
+            .insert_signal(1, generate_default_signal2())
+            .unwrap();
+        edf_expected
+            .insert_signal(2, generate_default_annotations())
+            .unwrap();
+
+        // Create records
+        for i in [0, 30, 1, 2, 3, 4] {
+            edf_expected
+                .append_record(generate_custom_signal_record(
+                    &edf_expected,
+                    i,
+                    vec![generate_default_signal1_data_downsampled, generate_default_signal2_data],
+                ))
+                .unwrap();
+        }
+
+        // Create the file
+        edf_expected.save().unwrap();
+
+        // ============== ASSERT ===============
+
+        let data_expected = fs::read(&path_expected).unwrap();
+        let data_actual = fs::read(&path_actual).unwrap();
+        assert_eq!(data_expected, data_actual);
+
+        // Polyphase resampling removes frequencies above the new Nyquist frequency while keeping the waveform
+        // otherwise intact. The samples at the edges of the data-record are affected by the edge handling
+        let resample = |samples: Vec<i16>, method: ResampleMethod| {
+            let mut record = Record::new(&vec![generate_default_signal1()], &EDFSpecifications::EDF);
+            record.set_samples(0, Samples::Values16Bit(samples)).unwrap();
+            record
+                .resample_signal(0, generate_default_signal1().with_samples_count(50), &method)
+                .unwrap();
+            match record.raw_signal_samples.remove(0) {
+                Samples::Values16Bit(samples) => samples[16..34].to_vec(),
+                Samples::Values24Bit(_) => unreachable!(),
+            }
+        };
+        let alternating = (0..100).map(|i| if i % 2 == 0 { 1000 } else { -1000 }).collect::<Vec<_>>();
+        assert_eq!(resample(alternating.clone(), ResampleMethod::Linear), vec![1000; 18]);
+        assert_eq!(resample(alternating, ResampleMethod::Polyphase), vec![0; 18]);
+        assert_eq!(
+            resample((0..100).collect(), ResampleMethod::Polyphase),
+            (16..34).map(|i| i * 2).collect::<Vec<_>>()
+        );
+
+        // Resampling a data-record using its neighbouring data-records has to match resampling the contiguous signal
+        let signal = generate_default_signal1();
+        let create_record = |samples: Vec<i16>| {
+            let mut record = Record::new(&vec![signal.clone()], &EDFSpecifications::EDF);
+            record.set_samples(0, Samples::Values16Bit(samples)).unwrap();
+            record
+        };
+        let stream = (0..300).map(|i| ((i as f64 / 7.0).sin() * 1000.0) as i16).collect::<Vec<_>>();
+        let mut contiguous = Record::new(&vec![signal.clone().with_samples_count(300).clone()], &EDFSpecifications::EDF);
+        contiguous.set_samples(0, Samples::Values16Bit(stream.clone())).unwrap();
+        contiguous
+            .resample_signal(0, signal.clone().with_samples_count(150), &ResampleMethod::Polyphase)
+            .unwrap();
+        let mut record = create_record(stream[100..200].to_vec());
+        record
+            .resample_signal_with_neighbours(
+                0,
+                signal.clone().with_samples_count(50),
+                &ResampleMethod::Polyphase,
+                Some(&create_record(stream[..100].to_vec())),
+                Some(&create_record(stream[200..].to_vec())),
+            )
+            .unwrap();
+        match (&record.raw_signal_samples[0], &contiguous.raw_signal_samples[0]) {
+            (Samples::Values16Bit(actual), Samples::Values16Bit(expected)) => assert_eq!(actual, &expected[50..100]),
+            _ => unreachable!(),
+        }
+
+        // ============== CLEANUP ==============
+
+        remove_file(path_expected).unwrap();
+        remove_file(path_actual).unwrap();
+    }
+
+    #[test]
+    fn test_resample_signal_removed_record() {
+        let path_actual = generate_test_edf("resample_signal_removed_record_actual");
+        let signal = generate_default_signal1();
+        let resampled_signal = signal.clone().with_samples_count(50).clone();
+
+        // ============== ACT ===============
+
+        let mut edf_actual = EDFFile::open(&path_actual).unwrap();
+        edf_actual.set_resample_method(ResampleMethod::Polyphase);
+        edf_actual.remove_record(2).unwrap();
+        edf_actual.update_signal(0, resampled_signal.clone()).unwrap();
+
+        // Read the records surrounding the removed record before and after saving
+        let read_samples = |edf: &mut EDFFile, index: usize| match edf.read_record_at(index).unwrap().unwrap().raw_signal_samples.remove(0) {
+            Samples::Values16Bit(samples) => samples,
+            Samples::Values24Bit(_) => unreachable!(),
+        };
+        let pending = [read_samples(&mut edf_actual, 1), read_samples(&mut edf_actual, 2)];
+        edf_actual.save().unwrap();
+        let mut edf_actual = EDFFile::open(&path_actual).unwrap();
+        let saved = [read_samples(&mut edf_actual, 1), read_samples(&mut edf_actual, 2)];
+
+        // ============== EXPECTED ===============
+
+        // The samples of the removed record must not be used to resample the records surrounding it
+        let resample = |index: usize, previous: Option<usize>, next: Option<usize>| {
+            let create_record = |index: usize| {
+                let mut record = Record::new(&vec![signal.clone()], &EDFSpecifications::EDF);
+                record.set_samples(0, generate_default_signal1_data(index)).unwrap();
+                record
+            };
+            let mut record = create_record(index);
+            record
+                .resample_signal_with_neighbours(
+                    0,
+                    &resampled_signal,
+                    &ResampleMethod::Polyphase,
+                    previous.map(create_record).as_ref(),
+                    next.map(create_record).as_ref(),
+                )
+                .unwrap();
+            match record.raw_signal_samples.remove(0) {
+                Samples::Values16Bit(samples) => samples,
+                Samples::Values24Bit(_) => unreachable!(),
+            }
+        };
+        let expected = [resample(1, Some(0), None), resample(3, None, Some(4))];
+
+        // ============== ASSERT ===============
+
+        assert_eq!(pending, expected);
+        assert_eq!(saved, expected);
+        assert_ne!(expected[0], resample(1, Some(0), Some(2)));
+        assert_ne!(expected[1], resample(3, Some(2), Some(4)));
+
+        // ============== CLEANUP ==============
+
+        remove_file(path_actual).unwrap();
+    }
+
+    #[test]
+    fn test_rescale_signal() {
+        let (path_actual, path_expected) = get_paths("rescale_signal");
+        let signal1 = generate_default_signal1().with_digital_range(-2048, 2048).clone();
+        let signal2 = generate_default_signal2().with_physical_range(-1024.0, 1024.0).clone();
+
+        // ============== ACT ===============
+
+        let mut edf_actual = EDFFile::open(&path_actual).unwrap();
+        edf_actual.set_rescale_mode(RescaleMode::KeepPhysical);
+        edf_actual
+            .insert_record(1, generate_default_record(&edf_actual, 30))
+            .unwrap();
+        edf_actual.update_signal(0, signal1.clone()).unwrap();
+        edf_actual.update_signal(1, signal2.clone()).unwrap();
+
+        // Apply modifications
+        edf_actual.save().unwrap();
+
+        // ============== EXPECTED ===============
+
+        // Create new EDF file
+        let mut edf_expected = EDFFile::new(&path_expected).unwrap();
+        configure_default_header(&mut edf_expected.header);
+
+        // Create signals
+        edf_expected.insert_signal(0, signal1).unwrap();
+        edf_expected.insert_signal(1, signal2).unwrap();
+        edf_expected
+            .insert_signal(2, generate_default_annotations())
+            .unwrap();
+
+        // Create records. The physical value of a single digital step doubles for signal 2, which
+        // causes samples exceeding the digital maximum to be clipped
+        let signal1_data = |i: usize| Samples::Values16Bit((0..100).map(|n| 2 * (25 * i as i16 + n)).collect());
+        let signal2_data = |i: usize| Samples::Values16Bit((0..127).map(|n| ((25 * i as i16 + n + 1) / 2).min(64)).collect());
+        for i in [0, 30, 1, 2, 3, 4] {
+            edf_expected
+                .append_record(generate_custom_signal_record(
+                    &edf_expected,
+                    i,
+                    vec![&signal1_data as &dyn Fn(usize) -> Samples, &signal2_data],
+                ))
+                .unwrap();
+        }
+
+        // Create the file
+        edf_expected.save().unwrap();
+
+        // ============== ASSERT ===============
+
+        let data_expected = fs::read(&path_expected).unwrap();
+        let data_actual = fs::read(&path_actual).unwrap();
+        assert_eq!(data_expected, data_actual);
+
+        // Only the data-records stored on disk are included in the statistics
+        assert_eq!(edf_actual.get_clipping_statistics(), &vec![
+            ClippingStatistics::default(),
+            ClippingStatistics { clipped_below: 0, clipped_above: 23 + 48 + 73 + 98 },
+            ClippingStatistics::default(),
+        ]);
+
+        // ============== CLEANUP ==============
+
+        remove_file(path_expected).unwrap();
+        remove_file(path_actual).unwrap();
+    }
+
+    #[test]
+    fn test_convert_specification() {
+        let (path_actual, path_expected) = get_paths("convert_specification");
+
+        // ============== ACT ===============
+
+        let mut edf_actual = EDFFile::open(&path_actual).unwrap();
+        assert!(matches!(edf_actual.convert_specification(EDFSpecifications::BDF), Err(EDFError::UnsupportedConversion)));
+        edf_actual
+            .insert_record(1, generate_default_record(&edf_actual, 30))
+            .unwrap();
+        edf_actual.convert_specification(EDFSpecifications::BDFPlus).unwrap();
+        assert_eq!(edf_actual.header.get_specification(), EDFSpecifications::BDFPlus);
+
+        // Apply modifications
+        edf_actual.save().unwrap();
+
+        // ============== EXPECTED ===============
+
+        // Create new BDF file
+        let mut edf_expected = EDFFile::new(&path_expected).unwrap();
+        configure_default_header(&mut edf_expected.header);
+        edf_expected.header.with_specification(EDFSpecifications::BDFPlus);
+
+        // Create signals with a digital range widened by a factor of 256
+        edf_expected
+            .insert_signal(0, generate_default_signal1().with_digital_range(-1024 * 256, 1024 * 256).clone())
+            .unwrap();
+        edf_expected
+            .insert_signal(1, generate_default_signal2().with_digital_range(-64 * 256, 64 * 256).clone())
+            .unwrap();
+        edf_expected
+            .insert_signal(2, SignalHeader::new_annotation(80, EDFSpecifications::BDFPlus).with_samples_count(107).clone())
+            .unwrap();
+
+        // Create records
+        let widen = |samples: Samples, max: i32| match samples {
+            Samples::Values16Bit(samples) => {
+                Samples::Values24Bit(samples.into_iter().map(|s| (s as i32).clamp(-max, max) * 256).collect())
+            }
+            Samples::Values24Bit(samples) => Samples::Values24Bit(samples),
+        };
+        let signal1_data = |i: usize| widen(generate_default_signal1_data(i), 1024);
+        let signal2_data = |i: usize| widen(generate_default_signal2_data(i), 64);
+        for i in [0, 30, 1, 2, 3, 4] {
+            edf_expected
+                .append_record(generate_custom_signal_record(
+                    &edf_expected,
+                    i,
+                    vec![&signal1_data as &dyn Fn(usize) -> Samples, &signal2_data],
+                ))
+                .unwrap();
+        }
+
+        // Create the file
+        edf_expected.save().unwrap();
+
+        // ============== ASSERT ===============
+
+        let data_expected = fs::read(&path_expected).unwrap();
+        let data_actual = fs::read(&path_actual).unwrap();
+        assert_eq!(data_expected, data_actual);
+        assert_eq!(edf_actual.get_clipping_statistics()[1].clipped_above, 515);
+
+        // Converting back narrows digital ranges exceeding 16 bit again and restores the initial samples
+        edf_actual.convert_specification(EDFSpecifications::EDFPlus).unwrap();
+        edf_actual.save().unwrap();
+        assert_eq!(edf_actual.get_clipping_statistics(), &vec![ClippingStatistics::default(); 3]);
+        assert_eq!(edf_actual.header.get_signals()[0], generate_default_signal1());
+        assert_eq!(edf_actual.header.get_signals()[1].digital_maximum, 64 * 256);
+        assert_eq!(edf_actual.header.get_signals()[2].label, "EDF Annotations");
+        assert_eq!(edf_actual.header.get_signals()[2].samples_count, 161);
+
+        edf_actual.seek_to_record(1).unwrap();
+        let record = edf_actual.read_record().unwrap().unwrap();
+        assert_eq!(record.raw_signal_samples[0], generate_default_signal1_data(30));
+
+        // ============== CLEANUP ==============
+
+        remove_file(path_expected).unwrap();
+        remove_file(path_actual).unwrap();
+    }
+
+    #[test]
+    fn test_convert_to_plus() {
+        let path_actual = generate_file_path("convert_to_plus_actual");
+        let path_expected = generate_file_path("convert_to_plus_expected");
+
+        // Create a plain EDF file with free text identifications
+        let mut edf_plain = EDFFile::new(&path_actual).unwrap();
+        configure_legacy_header(&mut edf_plain.header);
+        edf_plain.insert_signal(0, generate_default_signal1()).unwrap();
+        edf_plain.insert_signal(1, generate_default_signal2()).unwrap();
+        for i in 0..3 {
+            edf_plain.append_record(generate_plain_record(&edf_plain, i)).unwrap();
+        }
+        edf_plain.save().unwrap();
+
+        // ============== ACT ===============
+
+        let mut edf_actual = EDFFile::open(&path_actual).unwrap();
+        assert_eq!(edf_actual.header.get_specification(), EDFSpecifications::EDF);
+        edf_actual
+            .insert_record(1, generate_plain_record(&edf_actual, 30))
+            .unwrap();
+        edf_actual.convert_to_plus(80).unwrap();
+        assert!(matches!(edf_actual.convert_to_plus(80), Err(EDFError::UnsupportedConversion)));
+
+        // Records appended after upgrading keep their annotations next to the generated Time-keeping annotation
+        let mut appended = edf_actual.header.create_record();
+        appended.raw_signal_samples = vec![generate_default_signal1_data(5), generate_default_signal2_data(5)];
+        appended.annotations = vec![vec![AnnotationList::new(4.5, 0.0, vec!["Annotation 5".to_string()]).unwrap()]];
+        edf_actual.append_record(appended).unwrap();
+
+        // Apply modifications
+        edf_actual.save().unwrap();
+
+        // ============== EXPECTED ===============
+
+        // Create new EDF+ file with the identifications parsed into their subfields
+        let mut edf_expected = EDFFile::new(&path_expected).unwrap();
+        configure_legacy_header(&mut edf_expected.header);
+        edf_expected
+            .header
+            .with_specification(EDFSpecifications::EDFPlus)
+            .with_patient_id(PatientId {
+                code: Some("MCH-0234567".to_string()),
+                sex: Some(Sex::Female),
+                date: Some(NaiveDate::from_ymd_opt(1951, 5, 2).unwrap()),
+                name: Some("Haagse Harry".to_string()),
+                additional: Vec::new(),
+            })
+            .with_recording_id(RecordingId {
+                startdate: Some(NaiveDate::from_ymd_opt(2026, 2, 13).unwrap()),
+                additional: vec![Some("Sleep".to_string()), Some("lab".to_string()), Some("3".to_string())],
+                ..Default::default()
+            });
+
+        // Create signals
+        edf_expected
+            .insert_signal(0, generate_default_signal1())
+            .unwrap();
+        edf_expected
+            .insert_signal(1, generate_default_signal2())
+            .unwrap();
+        edf_expected
+            .insert_signal(2, SignalHeader::new_annotation(80, EDFSpecifications::EDFPlus))
+            .unwrap();
+
+        // Create records with Time-keeping annotations generated from their new index
+        for (i, data_idx) in [0, 30, 1, 2, 5].into_iter().enumerate() {
+            let mut record = edf_expected.header.create_record();
+            record.raw_signal_samples = vec![generate_default_signal1_data(data_idx), generate_default_signal2_data(data_idx)];
+            record.annotations = vec![vec![AnnotationList::new_time_keeping(i as f64)]];
+            if data_idx == 5 {
+                record.annotations[0].push(AnnotationList::new(4.5, 0.0, vec!["Annotation 5".to_string()]).unwrap());
+            }
+            edf_expected.append_record(record).unwrap();
+        }
+
+        // Create the file
+        edf_expected.save().unwrap();
+
+        // ============== ASSERT ===============
+
+        let data_expected = fs::read(&path_expected).unwrap();
+        let data_actual = fs::read(&path_actual).unwrap();
+        assert_eq!(data_expected, data_actual);
+
+        let edf_reopened = EDFFile::open(&path_actual).unwrap();
+        assert_eq!(edf_reopened.header.get_specification(), EDFSpecifications::EDFPlus);
+        assert!(edf_reopened.header.is_continuous());
+
+        // ============== CLEANUP ==============
+
+        remove_file(path_expected).unwrap();
+        remove_file(path_actual).unwrap();
+    }
+
+    #[test]
+    fn test_reblock() {
+        let (path_actual, path_expected) = get_paths("reblock");
+        let path_actual = generate_time_keeping_test_edf(&path_actual);
+        fs::copy(&path_actual, &path_expected).unwrap();
+
+        // ============== ACT ===============
+
+        let mut edf_actual = EDFFile::open(&path_actual).unwrap();
+        assert!(matches!(edf_actual.reblock(0.3), Err(EDFError::InvalidRecordDuration)));
+        assert!(matches!(edf_actual.reblock(0.00390625), Err(EDFError::InvalidRecordDuration)));
+        edf_actual.remove_record(0).unwrap();
+        assert!(matches!(edf_actual.reblock(5.0), Err(EDFError::PendingChanges)));
+        edf_actual.undo().unwrap();
+
+        // Combine all data-records into a single one
+        edf_actual.reblock(5.0).unwrap();
+
+        // ============== ASSERT ===============
+
+        assert_eq!(edf_actual.header.get_record_count(), Some(1));
+        assert_eq!(edf_actual.header.get_record_duration(), 5.0);
+        assert_eq!(edf_actual.header.get_signals()[0].samples_count, 500);
+        assert_eq!(edf_actual.header.get_signals()[1].samples_count, 635);
+        assert_eq!(edf_actual.header.get_signals()[2].samples_count, 800);
+
+        let record = edf_actual.read_record().unwrap().unwrap();
+        let mut signal1_expected = generate_default_signal1_data(0);
+        for i in 1..5 {
+            signal1_expected.extend(&generate_default_signal1_data(i)).unwrap();
+        }
+        assert_eq!(record.raw_signal_samples[0], signal1_expected);
+        assert_eq!(record.get_start_offset(), 0.0);
+        assert_eq!(
+            record.annotations[0].iter().filter(|tal| !tal.is_time_keeping()).map(|tal| tal.onset).collect::<Vec<_>>(),
+            vec![0.5, 1.5, 2.5, 3.5, 4.5]
+        );
+
+        // Splitting the data-records again results in the initial file
+        edf_actual.reblock(1.0).unwrap();
+        let data_expected = fs::read(&path_expected).unwrap();
+        let data_actual = fs::read(&path_actual).unwrap();
+        assert_eq!(data_expected, data_actual);
+
+        // ============== CLEANUP ==============
+
+        remove_file(path_expected).unwrap();
+        remove_file(path_actual).unwrap();
+    }
+
+    #[test]
+    fn test_fill_gaps() {
+        let (path_actual, path_expected) = get_paths("fill_gaps");
+        let path_actual = generate_time_keeping_test_edf(&path_actual);
+
+        // Create a gap of 2 data-records by removing records without shifting the subsequent onsets
+        let mut edf_actual = EDFFile::open(&path_actual).unwrap();
+        edf_actual.set_record_delete_strategy(RecordDeleteStrategy::Discontinuous);
+        edf_actual.remove_record(1).unwrap();
+        edf_actual.remove_record(1).unwrap();
+        edf_actual.save().unwrap();
+        assert!(!edf_actual.header.is_continuous());
+
+        // ============== ACT ===============
+
+        let options = GapFillOptions {
+            fill_value: GapFillValue::DigitalMinimum,
+            ..Default::default()
+        };
+        edf_actual.fill_gaps(&options).unwrap();
+
+        // ============== EXPECTED ===============
+
+        // Create new EDF file
+        let mut edf_expected = EDFFile::new(&path_expected).unwrap();
+        configure_default_header(&mut edf_expected.header);
+
+        // Create signals
+        edf_expected
+            .insert_signal(0, generate_default_signal1())
+            .unwrap();
+        edf_expected
+            .insert_signal(1, generate_default_signal2())
+            .unwrap();
+        edf_expected
+            .insert_signal(2, generate_default_annotations())
+            .unwrap();
+
+        // Create records with the gap filled by records containing the digital minimum of every signal
+        edf_expected
+            .append_record(generate_time_keeping_record(&edf_expected, 0, 0.0))
+            .unwrap();
+        for onset in [1.0, 2.0] {
+            let mut record = edf_expected.header.create_record();
+            record.raw_signal_samples = vec![Samples::Values16Bit(vec![-1024; 100]), Samples::Values16Bit(vec![-64; 127])];
+            record.annotations = vec![vec![AnnotationList::new_time_keeping(onset)]];
+            if onset == 1.0 {
+                record.annotations[0].push(AnnotationList::new(1.0, 2.0, vec!["Gap".to_string()]).unwrap());
+            }
+            edf_expected.append_record(record).unwrap();
+        }
+        edf_expected
+            .append_record(generate_time_keeping_record(&edf_expected, 3, 3.0))
+            .unwrap();
+        edf_expected
+            .append_record(generate_time_keeping_record(&edf_expected, 4, 4.0))
+            .unwrap();
+
+        // Create the file
+        edf_expected.save().unwrap();
+
+        // ============== ASSERT ===============
+
+        let data_expected = fs::read(&path_expected).unwrap();
+        let data_actual = fs::read(&path_actual).unwrap();
+        assert!(edf_actual.header.is_continuous());
+        assert_eq!(data_expected, data_actual);
+
+        // Gaps which are not a whole multiple of the data-record duration are rejected by default
+        remove_file(&path_actual).unwrap();
+        let mut edf_unaligned = EDFFile::new(&path_actual).unwrap();
+        configure_default_header(&mut edf_unaligned.header);
+        edf_unaligned.header.with_is_continuous(false);
+        edf_unaligned.insert_signal(0, generate_default_signal1()).unwrap();
+        edf_unaligned.insert_signal(1, generate_default_signal2()).unwrap();
+        edf_unaligned.insert_signal(2, generate_default_annotations()).unwrap();
+        for (i, onset) in [0.0, 1.0, 2.5].into_iter().enumerate() {
+            edf_unaligned.append_record(generate_time_keeping_record(&edf_unaligned, i, onset)).unwrap();
+        }
+        edf_unaligned.save().unwrap();
+        assert!(matches!(edf_unaligned.fill_gaps(&GapFillOptions::default()), Err(EDFError::UnalignedGap)));
+
+        // Padding extends the gap to a whole data-record and shifts the subsequent data-records
+        let options = GapFillOptions {
+            strategy: GapStrategy::Pad,
+            ..Default::default()
+        };
+        edf_unaligned.fill_gaps(&options).unwrap();
+        assert_eq!(edf_unaligned.header.get_record_count(), Some(4));
+        let onsets = (0..4)
+            .map(|i| edf_unaligned.read_record_at(i).unwrap().unwrap().get_start_offset())
+            .collect::<Vec<_>>();
+        assert_eq!(onsets, vec![0.0, 1.0, 2.0, 3.0]);
+
+        // Re-blocking fails without modifying the file in case the data-records are out of order
+        let create_reblock_file = |onsets: [f64; 3]| {
+            remove_file(&path_actual).unwrap();
+            let mut edf = EDFFile::new(&path_actual).unwrap();
+            configure_default_header(&mut edf.header);
+            edf.header.with_is_continuous(false);
+            edf.insert_signal(0, generate_default_signal1()).unwrap();
+            edf.insert_signal(1, generate_default_signal2().with_samples_count(128).clone()).unwrap();
+            edf.insert_signal(2, generate_default_annotations()).unwrap();
+            for onset in onsets {
+                let mut record = edf.header.create_record();
+                record.annotations = vec![vec![AnnotationList::new_time_keeping(onset)]];
+                edf.append_record(record).unwrap();
+            }
+            edf.save().unwrap();
+            edf
+        };
+        let options = GapFillOptions {
+            strategy: GapStrategy::Reblock,
+            ..Default::default()
+        };
+        let mut edf_reblock = create_reblock_file([0.0, 1.5, 0.5]);
+        let data_initial = fs::read(&path_actual).unwrap();
+        assert!(matches!(edf_reblock.fill_gaps(&options), Err(EDFError::RecordsOutOfOrder)));
+        assert_eq!(fs::read(&path_actual).unwrap(), data_initial);
+
+        // Otherwise the file is re-blocked to the largest duration the gap is a whole multiple of and the gap is filled
+        let mut edf_reblock = create_reblock_file([0.0, 1.0, 2.5]);
+        edf_reblock.fill_gaps(&options).unwrap();
+        assert_eq!(edf_reblock.header.get_record_duration(), 0.5);
+        assert_eq!(edf_reblock.header.get_record_count(), Some(7));
+        let onsets = (0..7)
+            .map(|i| edf_reblock.read_record_at(i).unwrap().unwrap().get_start_offset())
+            .collect::<Vec<_>>();
+        assert_eq!(onsets, vec![0.0, 0.5, 1.0, 1.5, 2.0, 2.5, 3.0]);
+
+        // ============== CLEANUP ==============
+
+        remove_file(path_expected).unwrap();
+        remove_file(path_actual).unwrap();
+    }
+
+    #[test]
+    fn test_sort_records() {
+        let (path_actual, path_expected) = get_paths("sort_records");
+
+        // Create a discontinuous file with the records at index 1 and 2 stored out of order
+        remove_file(&path_actual).unwrap();
+        let mut edf_unsorted = EDFFile::new(&path_actual).unwrap();
+        configure_default_header(&mut edf_unsorted.header);
+        edf_unsorted.header.with_is_continuous(false);
+        edf_unsorted.insert_signal(0, generate_default_signal1()).unwrap();
+        edf_unsorted.insert_signal(1, generate_default_signal2()).unwrap();
+        edf_unsorted.insert_signal(2, generate_default_annotations()).unwrap();
+        for i in [0, 2, 1, 3, 4] {
+            edf_unsorted.append_record(generate_time_keeping_record(&edf_unsorted, i, i as f64)).unwrap();
+        }
+        edf_unsorted.save().unwrap();
+
+        // ============== ACT ===============
+
+        let mut edf_actual = EDFFile::open(&path_actual).unwrap();
+        let report = edf_actual.check_record_order().unwrap();
+        assert_eq!(report.out_of_order, vec![2]);
+        assert_eq!(report.overlapping, vec![]);
+        assert!(!report.is_chronological());
+
+        assert_eq!(edf_actual.sort_records().unwrap(), 2);
+        assert!(edf_actual.check_record_order().unwrap().is_valid());
+
+        // Apply modifications
+        edf_actual.save().unwrap();
+
+        // ============== EXPECTED ===============
+
+        // Create new EDF file
+        let mut edf_expected = EDFFile::new(&path_expected).unwrap();
+        configure_default_header(&mut edf_expected.header);
+        edf_expected.header.with_is_continuous(false);
+
+        // Create signals
+        edf_expected
+            .insert_signal(0, generate_default_signal1())
+            .unwrap();
+        edf_expected
+            .insert_signal(1, generate_default_signal2())
+            .unwrap();
+        edf_expected
+            .insert_signal(2, generate_default_annotations())
+            .unwrap();
+
+        // Create records in chronological order
+        for i in 0..5 {
+            edf_expected
+                .append_record(generate_time_keeping_record(&edf_expected, i, i as f64))
+                .unwrap();
+        }
+
+        // Create the file
+        edf_expected.save().unwrap();
+
+        // ============== ASSERT ===============
+
+        let data_expected = fs::read(&path_expected).unwrap();
+        let data_actual = fs::read(&path_actual).unwrap();
+        assert_eq!(data_expected, data_actual);
+
+        // ============== CLEANUP ==============
+
+        remove_file(path_expected).unwrap();
+        remove_file(path_actual).unwrap();
+    }
+
+    #[test]
+    fn test_merge_files() {
+        let (path_actual, path_expected) = get_paths("merge_files");
+        let path_second = generate_file_path("merge_files_second");
+        let path_merged = generate_file_path("merge_files_merged");
+        for path in [&path_second, &path_merged] {
+            if exists(path).unwrap() {
+                remove_file(path).unwrap();
+            }
+        }
+
+        // Create the first part of the recording with 3 records
+        remove_file(&path_actual).unwrap();
+        let mut edf_first = EDFFile::new(&path_actual).unwrap();
+        configure_default_header(&mut edf_first.header);
+        edf_first.insert_signal(0, generate_default_signal1()).unwrap();
+        edf_first.insert_signal(1, generate_default_signal2()).unwrap();
+        edf_first.insert_signal(2, generate_default_annotations()).unwrap();
+        for i in 0..3 {
+            edf_first.append_record(generate_time_keeping_record(&edf_first, i, i as f64)).unwrap();
+        }
+        edf_first.save().unwrap();
+
+        // Create the second part of the recording starting 5 seconds after the first part
+        let mut edf_second = EDFFile::new(&path_second).unwrap();
+        configure_default_header(&mut edf_second.header);
+        edf_second.header.with_start_time(NaiveTime::from_hms_opt(17, 30, 5).unwrap());
+        edf_second.insert_signal(0, generate_default_signal1()).unwrap();
+        edf_second.insert_signal(1, generate_default_signal2()).unwrap();
+        edf_second.insert_signal(2, generate_default_annotations()).unwrap();
+        for i in 0..2 {
+            edf_second.append_record(generate_time_keeping_record(&edf_second, i + 5, i as f64)).unwrap();
+        }
+        edf_second.save().unwrap();
+
+        // ============== ACT ===============
+
+        let mut files = vec![EDFFile::open(&path_second).unwrap(), EDFFile::open(&path_actual).unwrap()];
+        assert!(check_merge_compatibility(&files).is_empty());
+        let merged = merge_files(&mut files, &path_merged).unwrap();
+        assert!(!merged.header.is_continuous());
+        assert_eq!(merged.header.get_record_count(), Some(5));
+
+        // Existing files are never overwritten
+        let merged_data = fs::read(&path_merged).unwrap();
+        assert!(matches!(merge_files(&mut files, &path_merged), Err(EDFError::FileAlreadyExists)));
+        assert_eq!(fs::read(&path_merged).unwrap(), merged_data);
+
+        // Files with different signals can not be merged
+        files[0].header.modify_signals()[1].samples_count = 100;
+        assert_eq!(
+            check_merge_compatibility(&files),
+            vec![MergeIncompatibility::SamplesCount { file: 1, signal: 1, expected: 100, actual: 127 }]
+        );
+        assert!(matches!(
+            merge_files(&mut files, generate_file_path("merge_files_invalid")),
+            Err(EDFError::IncompatibleFiles)
+        ));
+
+        // ============== EXPECTED ===============
+
+        // Create new EDF file
+        let mut edf_expected = EDFFile::new(&path_expected).unwrap();
+        configure_default_header(&mut edf_expected.header);
+        edf_expected.header.with_is_continuous(false);
+
+        // Create signals
+        edf_expected
+            .insert_signal(0, generate_default_signal1())
+            .unwrap();
+        edf_expected
+            .insert_signal(1, generate_default_signal2())
+            .unwrap();
+        edf_expected
+            .insert_signal(2, generate_default_annotations())
+            .unwrap();
+
+        // Create records at their offset relative to the start of the first part
+        for i in [0, 1, 2, 5, 6] {
+            edf_expected
+                .append_record(generate_time_keeping_record(&edf_expected, i, i as f64))
+                .unwrap();
+        }
+
+        // Create the file
+        edf_expected.save().unwrap();
+
+        // ============== ASSERT ===============
+
+        let data_expected = fs::read(&path_expected).unwrap();
+        let data_actual = fs::read(&path_merged).unwrap();
+        assert_eq!(data_expected, data_actual);
+
+        // ============== CLEANUP ==============
+
+        remove_file(path_expected).unwrap();
+        remove_file(path_actual).unwrap();
+        remove_file(path_second).unwrap();
+        remove_file(path_merged).unwrap();
+    }
+
+    #[test]
+    fn test_split() {
+        let (path_actual, path_expected) = get_paths("split");
+        let path_parts = generate_file_path("split_parts");
+        let path_first = generate_file_path("split_parts_1");
+        let path_second = generate_file_path("split_parts_2");
+        for path in [&path_first, &path_second] {
+            if exists(path).unwrap() {
+                remove_file(path).unwrap();
+            }
+        }
+        generate_time_keeping_test_edf(&path_actual);
+
+        // ============== ACT ===============
+
+        let mut edf_actual = EDFFile::open(&path_actual).unwrap();
+        assert!(matches!(
+            edf_actual.split(&SplitMode::RecordCount(0), &path_parts),
+            Err(EDFError::InvalidRecordCount)
+        ));
+
+        // Split the file at the record containing the annotation
+        let parts = edf_actual
+            .split(&SplitMode::Annotations(vec!["Annotation 2".to_string()]), &path_parts)
+            .unwrap();
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].header.get_record_count(), Some(2));
+        assert_eq!(parts[0].header.get_start_time(), NaiveTime::from_hms_opt(17, 30, 0).unwrap());
+        assert_eq!(parts[1].header.get_record_count(), Some(3));
+
+        // ============== EXPECTED ===============
+
+        // Create new EDF file starting at the first record of the second part
+        let mut edf_expected = EDFFile::new(&path_expected).unwrap();
+        configure_default_header(&mut edf_expected.header);
+        edf_expected.header.with_start_time(NaiveTime::from_hms_opt(17, 30, 2).unwrap());
+
+        // Create signals
+        edf_expected
+            .insert_signal(0, generate_default_signal1())
+            .unwrap();
+        edf_expected
+            .insert_signal(1, generate_default_signal2())
+            .unwrap();
+        edf_expected
+            .insert_signal(2, generate_default_annotations())
+            .unwrap();
+
+        // Create records with rebased onsets
+        for i in 2..5 {
+            edf_expected
+                .append_record(generate_time_keeping_record(&edf_expected, i, (i - 2) as f64))
+                .unwrap();
+        }
+
+        // Create the file
+        edf_expected.save().unwrap();
+
+        // ============== ASSERT ===============
+
+        let data_expected = fs::read(&path_expected).unwrap();
+        let data_actual = fs::read(&path_second).unwrap();
+        assert_eq!(data_expected, data_actual);
+
+        // ============== CLEANUP ==============
+
+        remove_file(path_expected).unwrap();
+        remove_file(path_actual).unwrap();
+        remove_file(path_first).unwrap();
+        remove_file(path_second).unwrap();
+    }
+
+    #[test]
+    fn test_crop() {
+        let (path_actual, path_expected) = get_paths("crop");
+
+        // Create a file with a single signal and an annotation in the middle of every record
+        remove_file(&path_actual).unwrap();
+        let mut edf_initial = EDFFile::new(&path_actual).unwrap();
+        configure_default_header(&mut edf_initial.header);
+        edf_initial.insert_signal(0, generate_default_signal1()).unwrap();
+        edf_initial.insert_signal(1, generate_default_annotations()).unwrap();
+        for i in 0..5 {
+            let mut record = edf_initial.header.create_record();
+            record.raw_signal_samples = vec![generate_default_signal1_data(i)];
+            record.annotations = vec![vec![
+                AnnotationList::new_time_keeping(i as f64),
+                AnnotationList::new(i as f64 + 0.5, 0.0, vec![format!("Annotation {}", i)]).unwrap(),
+            ]];
+            edf_initial.append_record(record).unwrap();
+        }
+        edf_initial.save().unwrap();
+
+        // ============== ACT ===============
+
+        let mut edf_actual = EDFFile::open(&path_actual).unwrap();
+        assert!(matches!(
+            edf_actual.crop(Duration::from_secs(3), Duration::from_secs(1)),
+            Err(EDFError::InvalidReadRange)
+        ));
+
+        // Both boundaries are located within a record, which requires re-blocking to 0.25 seconds
+        let progress = Arc::new(Mutex::new(Vec::new()));
+        let progress_handler = progress.clone();
+        edf_actual.set_progress_handler(move |p| progress_handler.lock().unwrap().push((p.processed, p.total)));
+        edf_actual.crop(Duration::from_millis(1250), Duration::from_millis(3500)).unwrap();
+        assert_eq!(edf_actual.header.get_record_duration(), 0.25);
+        assert_eq!(edf_actual.header.get_record_count(), Some(9));
+
+        // The progress is reported for every data-record written
+        assert_eq!(*progress.lock().unwrap(), (1..=9).map(|processed| (processed, 9)).collect::<Vec<_>>());
+
+        // ============== EXPECTED ===============
+
+        // Create new EDF file starting at the whole second the window starts in
+        let mut edf_expected = EDFFile::new(&path_expected).unwrap();
+        configure_default_header(&mut edf_expected.header);
+        edf_expected
+            .header
+            .with_start_time(NaiveTime::from_hms_opt(17, 30, 1).unwrap())
+            .with_record_duration(0.25);
+
+        // Create signals
+        let mut signal = generate_default_signal1();
+        signal.with_samples_count(25);
+        edf_expected.insert_signal(0, signal).unwrap();
+        edf_expected
+            .insert_signal(1, SignalHeader::new_annotation(20, EDFSpecifications::EDF))
+            .unwrap();
+
+        // Create the trimmed records, where the annotation at the end of the window is stored in the last record
+        let annotations = [(1, "Annotation 1", 0.5), (5, "Annotation 2", 1.5), (8, "Annotation 3", 2.5)];
+        for k in 0..9 {
+            let onset = 0.25 + k as f64 * 0.25;
+            let (initial_idx, offset) = (1 + (k + 1) / 4, (k + 1) % 4 * 25);
+            let first_value = 25 * initial_idx as i16 + offset as i16;
+            let mut record = edf_expected.header.create_record();
+            record.raw_signal_samples = vec![Samples::Values16Bit((first_value..first_value + 25).collect())];
+            record.annotations = vec![vec![AnnotationList::new_time_keeping(onset)]];
+            if let Some((_, text, onset)) = annotations.iter().find(|(record_idx, _, _)| *record_idx == k) {
+                record.annotations[0].push(AnnotationList::new(*onset, 0.0, vec![text.to_string()]).unwrap());
+            }
+            edf_expected.append_record(record).unwrap();
+        }
+
+        // Create the file
+        edf_expected.save().unwrap();
+
+        // ============== ASSERT ===============
+
+        let data_expected = fs::read(&path_expected).unwrap();
+        let data_actual = fs::read(&path_actual).unwrap();
+        assert_eq!(data_expected, data_actual);
+
+        // Cropping fails without modifying the file in case the re-blocked data-record duration can not be represented
+        remove_file(&path_actual).unwrap();
+        let mut edf_unrepresentable = EDFFile::new(&path_actual).unwrap();
+        configure_default_header(&mut edf_unrepresentable.header);
+        edf_unrepresentable.insert_signal(0, generate_default_signal1().with_samples_count(256).clone()).unwrap();
+        edf_unrepresentable.insert_signal(1, generate_default_annotations()).unwrap();
+        for i in 0..3 {
+            let mut record = edf_unrepresentable.header.create_record();
+            record.annotations = vec![vec![AnnotationList::new_time_keeping(i as f64)]];
+            edf_unrepresentable.append_record(record).unwrap();
+        }
+        edf_unrepresentable.save().unwrap();
+        let data_initial = fs::read(&path_actual).unwrap();
+        assert!(matches!(
+            edf_unrepresentable.crop(Duration::from_nanos(1_003_906_250), Duration::from_secs(3)),
+            Err(EDFError::InvalidRecordDuration)
+        ));
+        assert_eq!(fs::read(&path_actual).unwrap(), data_initial);
+
+        // ============== CLEANUP ==============
+
+        remove_file(path_expected).unwrap();
+        remove_file(path_actual).unwrap();
+    }
+
+    #[test]
+    fn test_extract_signals() {
+        let (path_actual, path_expected) = get_paths("extract_signals");
+        let path_extracted = generate_file_path("extract_signals_extracted");
+        if exists(&path_extracted).unwrap() {
+            remove_file(&path_extracted).unwrap();
+        }
+        generate_time_keeping_test_edf(&path_actual);
+
+        // ============== ACT ===============
+
+        let mut edf_actual = EDFFile::open(&path_actual).unwrap();
+        assert!(matches!(
+            edf_actual.extract_signals(&[SignalSelector::Label("EEG *".to_string())], &path_extracted),
+            Err(EDFError::ItemNotFound)
+        ));
+
+        let edf_extracted = edf_actual
+            .extract_signals(&[SignalSelector::Label("Sig*2".to_string())], &path_extracted)
+            .unwrap();
+        assert_eq!(edf_extracted.header.get_signals().len(), 2);
+        assert_eq!(edf_extracted.header.get_record_count(), Some(5));
+
+        // ============== EXPECTED ===============
+
+        // Create new EDF file
+        let mut edf_expected = EDFFile::new(&path_expected).unwrap();
+        configure_default_header(&mut edf_expected.header);
+
+        // Create signals
+        edf_expected
+            .insert_signal(0, generate_default_signal2())
+            .unwrap();
+        edf_expected
+            .insert_signal(1, generate_default_annotations())
+            .unwrap();
+
+        // Create records only containing the second signal
+        for i in 0..5 {
+            let mut record = generate_time_keeping_record(&edf_expected, i, i as f64);
+            record.raw_signal_samples.remove(0);
+            edf_expected.append_record(record).unwrap();
+        }
+
+        // Create the file
+        edf_expected.save().unwrap();
+
+        // ============== ASSERT ===============
+
+        let data_expected = fs::read(&path_expected).unwrap();
+        let data_actual = fs::read(&path_extracted).unwrap();
+        assert_eq!(data_expected, data_actual);
+
+        // ============== CLEANUP ==============
+
+        remove_file(path_expected).unwrap();
+        remove_file(path_actual).unwrap();
+        remove_file(path_extracted).unwrap();
+    }
+
+    #[test]
+    fn test_anonymize() {
+        let (path_actual, path_expected) = get_paths("anonymize");
+        generate_time_keeping_test_edf(&path_actual);
+
+        // ============== ACT ===============
+
+        let mut edf_actual = EDFFile::open(&path_actual).unwrap();
+        let mut options = AnonymizeOptions {
+            patient_name: IdentifierReplacement::Pseudonym,
+            date_shift: DateShift::Days(-10),
+            redact_patterns: vec!["ANNOTATION 3".to_string()],
+            ..Default::default()
+        };
+
+        // Pseudonyms could be reversed without a salt
+        assert!(matches!(edf_actual.anonymize(&options), Err(EDFError::MissingSalt)));
+        options.salt = "secret".to_string();
+        let report = edf_actual.anonymize(&options).unwrap();
+
+        // The pseudonym is derived from the name and the salt
+        let pseudonym = edf_actual.header.get_patient_id().name.clone().unwrap();
+        assert_eq!(pseudonym.len(), 16);
+        assert_eq!(
+            report.changes,
+            vec![
+                AnonymizationChange::HeaderField { field: "patient_id.code", replacement: None },
+                AnonymizationChange::HeaderField { field: "patient_id.name", replacement: Some(pseudonym.clone()) },
+                AnonymizationChange::HeaderField { field: "patient_id.date", replacement: None },
+                AnonymizationChange::HeaderField { field: "recording_id.admin_code", replacement: None },
+                AnonymizationChange::HeaderField { field: "recording_id.technician", replacement: None },
+                AnonymizationChange::StartDate { days: -10 },
+                AnonymizationChange::Annotation { record: 3, onset: 3.5, redactions: 1, replacement: "XXX".to_string() },
+            ]
+        );
+
+        // ============== EXPECTED ===============
+
+        // Create new EDF file
+        let mut edf_expected = EDFFile::new(&path_expected).unwrap();
+        configure_default_header(&mut edf_expected.header);
+        edf_expected
+            .header
+            .with_patient_id(PatientId {
+                code: None,
+                name: Some(pseudonym),
+                date: None,
+                sex: Some(Sex::Male),
+                additional: Vec::new(),
+            })
+            .with_recording_id(RecordingId {
+                admin_code: None,
+                equipment: Some("EQUIPMENT".to_string()),
+                technician: None,
+                startdate: Some(NaiveDate::from_ymd_opt(2026, 2, 3).unwrap()),
+                additional: Vec::new(),
+            })
+            .with_start_date(NaiveDate::from_ymd_opt(2026, 2, 3).unwrap());
+
+        // Create signals
+        edf_expected
+            .insert_signal(0, generate_default_signal1())
+            .unwrap();
+        edf_expected
+            .insert_signal(1, generate_default_signal2())
+            .unwrap();
+        edf_expected
+            .insert_signal(2, generate_default_annotations())
+            .unwrap();
+
+        // Create records with the annotation of the 4th record redacted
+        for i in 0..5 {
+            let mut record = generate_time_keeping_record(&edf_expected, i, i as f64);
+            if i == 3 {
+                record.annotations[0][1].annotations = vec!["XXX".to_string()];
+            }
+            edf_expected.append_record(record).unwrap();
+        }
+
+        // Create the file
+        edf_expected.save().unwrap();
+
+        // ============== ASSERT ===============
+
+        let data_expected = fs::read(&path_expected).unwrap();
+        let data_actual = fs::read(&path_actual).unwrap();
+        assert_eq!(data_expected, data_actual);
+
+        // ============== CLEANUP ==============
+
+        remove_file(path_expected).unwrap();
+        remove_file(path_actual).unwrap();
+    }
+
+    #[test]
+    fn test_repair() {
+        let (path_actual, path_expected) = get_paths("repair");
+
+        // Create a file where the third record is missing its Time-keeping annotation
+        remove_file(&path_actual).unwrap();
+        let mut edf_initial = EDFFile::new(&path_actual).unwrap();
+        configure_default_header(&mut edf_initial.header);
+        edf_initial.insert_signal(0, generate_default_signal1()).unwrap();
+        edf_initial.insert_signal(1, generate_default_signal2()).unwrap();
+        edf_initial.insert_signal(2, generate_default_annotations()).unwrap();
+        for i in 0..5 {
+            let mut record = generate_time_keeping_record(&edf_initial, i, i as f64);
+            if i == 2 {
+                record.annotations[0].remove(0);
+            }
+            edf_initial.append_record(record).unwrap();
+        }
+        edf_initial.save().unwrap();
+
+        // Simulate a crashed recording with an invalid header size, no record count and a partial last record
+        let mut data = fs::read(&path_actual).unwrap();
+        data[184..192].copy_from_slice(b"999     ");
+        data[236..244].copy_from_slice(b"-1      ");
+        data.truncate(data.len() - 300);
+        fs::write(&path_actual, data).unwrap();
+
+        // ============== ACT ===============
+
+        let mut edf_actual = EDFFile::open(&path_actual).unwrap();
+
+        // A cancelled repair keeps the header of the file as it is
+        let token = CancellationToken::new();
+        token.cancel();
+        edf_actual.set_cancellation_token(token);
+        assert!(matches!(edf_actual.repair(PartialRecordStrategy::Truncate), Err(EDFError::Cancelled)));
+        assert_eq!(edf_actual.header.header_bytes, 999);
+        edf_actual.clear_cancellation_token();
+
+        let report = edf_actual.repair(PartialRecordStrategy::Truncate).unwrap();
+        assert_eq!(
+            report.fixes,
+            vec![
+                RepairFix::HeaderBytes { stored: 999, actual: 1024 },
+                RepairFix::PartialRecord { record: 4, bytes: 474, strategy: PartialRecordStrategy::Truncate },
+                RepairFix::RecordCount { stored: None, actual: 4 },
+                RepairFix::TimeKeeping { record: 2, onset: 2.0 },
+            ]
+        );
+
+        // Repairing an intact file does not change anything
+        assert!(edf_actual.repair(PartialRecordStrategy::Truncate).unwrap().is_empty());
+
+        // ============== EXPECTED ===============
+
+        // Create new EDF file
+        let mut edf_expected = EDFFile::new(&path_expected).unwrap();
+        configure_default_header(&mut edf_expected.header);
+
+        // Create signals
+        edf_expected
+            .insert_signal(0, generate_default_signal1())
+            .unwrap();
+        edf_expected
+            .insert_signal(1, generate_default_signal2())
+            .unwrap();
+        edf_expected
+            .insert_signal(2, generate_default_annotations())
+            .unwrap();
+
+        // Create the complete records with all Time-keeping annotations
+        for i in 0..4 {
+            edf_expected
+                .append_record(generate_time_keeping_record(&edf_expected, i, i as f64))
+                .unwrap();
+        }
+
+        // Create the file
+        edf_expected.save().unwrap();
+
+        // ============== ASSERT ===============
+
+        let data_expected = fs::read(&path_expected).unwrap();
+        let data_actual = fs::read(&path_actual).unwrap();
+        assert_eq!(data_expected, data_actual);
+
+        // ============== CLEANUP ==============
+
+        remove_file(path_expected).unwrap();
+        remove_file(path_actual).unwrap();
+    }
+
+    #[test]
+    fn test_validate() {
+        let (path_actual, _) = get_paths("validate");
+
+        // Create a valid file where the third record is missing its Time-keeping annotation
+        remove_file(&path_actual).unwrap();
+        let mut edf_initial = EDFFile::new(&path_actual).unwrap();
+        configure_default_header(&mut edf_initial.header);
+        let mut signal2 = generate_default_signal2();
+        signal2.with_digital_range(-256, 256);
+        edf_initial.insert_signal(0, generate_default_signal1()).unwrap();
+        edf_initial.insert_signal(1, signal2).unwrap();
+        edf_initial.insert_signal(2, generate_default_annotations()).unwrap();
+        for i in 0..5 {
+            let mut record = generate_time_keeping_record(&edf_initial, i, i as f64);
+            if i == 2 {
+                record.annotations[0].remove(0);
+            }
+            edf_initial.append_record(record).unwrap();
+        }
+        edf_initial.save().unwrap();
+
+        let report = EDFFile::validate(&path_actual).unwrap();
+        assert_eq!(
+            report.violations,
+            vec![Violation {
+                severity: Severity::Error,
+                kind: ViolationKind::MissingTimeKeeping,
+                location: ViolationLocation { offset: 3026, record: Some(2), signal: Some(2) },
+            }]
+        );
+
+        // Corrupt the header and the data-records, where every data-record is 774 bytes in size
+        let mut data = fs::read(&path_actual).unwrap();
+        data[184..192].copy_from_slice(b"999     ");
+        data[256] = 0xE9;
+        data[1024 + 774 + 6..1024 + 774 + 8].copy_from_slice(&2000i16.to_le_bytes());
+        data[1024 + 3 * 774 + 454..1024 + 3 * 774 + 456].copy_from_slice(b"+1");
+        data[1024 + 3 * 774 + 454 + 10] = 0xFF;
+        data.truncate(data.len() - 300);
+        fs::write(&path_actual, data).unwrap();
+
+        // ============== ACT ===============
+
+        let report = EDFFile::validate(&path_actual).unwrap();
+
+        // ============== EXPECTED ===============
+
+        let location = |offset: u64, record: Option<usize>, signal: Option<usize>| ViolationLocation { offset, record, signal };
+        let violations_expected = vec![
+            (ViolationKind::HeaderBytes { stored: Some(999), expected: 1024 }, location(184, None, None)),
+            (ViolationKind::NonASCII { field: "label" }, location(256, None, Some(0))),
+            (ViolationKind::FileSize { expected: 4894, actual: 4594 }, location(0, None, None)),
+            (ViolationKind::SampleOutOfRange { count: 1, value: 2000 }, location(1804, Some(1), Some(0))),
+            (ViolationKind::MissingTimeKeeping, location(3026, Some(2), Some(2))),
+            (ViolationKind::InvalidUTF8, location(3805, Some(3), Some(2))),
+            (ViolationKind::NonMonotonicTimeKeeping { previous: 2.0, onset: 1.0 }, location(3800, Some(3), Some(2))),
+        ];
+
+        // ============== ASSERT ===============
+
+        assert!(!report.is_valid());
+        assert_eq!(report.warnings().count(), 0);
+        assert_eq!(
+            report.violations.into_iter().map(|violation| (violation.kind, violation.location)).collect::<Vec<_>>(),
+            violations_expected
+        );
+
+        // Data-records exceeding the size of the file are not read at all
+        let mut data = fs::read(&path_actual).unwrap();
+        data[236..244].copy_from_slice(b"99999999");
+        data[904..912].copy_from_slice(b"99999999");
+        fs::write(&path_actual, data).unwrap();
+        let report = EDFFile::validate(&path_actual).unwrap();
+        assert!(report.violations.iter().all(|violation| violation.location.record.is_none()));
+        assert!(report.errors().any(|violation| violation.kind == ViolationKind::FileSize { expected: 20_000_057_000_000_452, actual: 4594 }));
+
+        // ============== CLEANUP ==============
+
+        remove_file(path_actual).unwrap();
+    }
+
+    #[test]
+    fn test_save_progress_and_cancellation() {
+        let (path_actual, _) = get_paths("save_progress_and_cancellation");
+        let data_initial = fs::read(&path_actual).unwrap();
+
+        // ============== ACT ===============
+
+        let progress = Arc::new(Mutex::new(Vec::new()));
+        let progress_handler = progress.clone();
+        let token = CancellationToken::new();
+
+        let mut edf_actual = EDFFile::open(&path_actual).unwrap();
+        edf_actual.set_progress_handler(move |p| progress_handler.lock().unwrap().push(p.clone()));
+        edf_actual.set_cancellation_token(token.clone());
+        edf_actual.remove_record(0).unwrap();
+
+        // ============== ASSERT ===============
+
+        // Cancelled saves must not modify the file and must keep the pending changes
+        token.cancel();
+        for strategy in [SaveStrategy::InPlace, SaveStrategy::Atomic] {
+            edf_actual.set_save_strategy(strategy);
+            assert!(matches!(edf_actual.save(), Err(EDFError::Cancelled)));
+            assert_eq!(data_initial, fs::read(&path_actual).unwrap());
+        }
+        assert!(progress.lock().unwrap().is_empty());
+
+        // Cancelled reads must not move the read position
+        edf_actual.seek_to_record(0).unwrap();
+        assert!(matches!(edf_actual.read_seconds(1), Err(EDFError::Cancelled)));
+
+        token.reset();
+        let read = edf_actual.read_seconds(1).unwrap();
+        assert_eq!(read.raw_signal_samples[0][0].raw_signal_samples, generate_default_signal1_data(1));
+        assert_eq!(progress.lock().unwrap().last().unwrap(), &Progress {
+            operation: ProgressOperation::Read,
+            processed: 1_000_000_000,
+            total: 1_000_000_000,
+        });
+
+        // Saving reports the progress of every data-record
+        progress.lock().unwrap().clear();
+        edf_actual.save().unwrap();
+        let progress = progress.lock().unwrap();
+        assert_eq!(progress.len(), 4);
+        assert_eq!(progress.last().unwrap(), &Progress {
+            operation: ProgressOperation::Save,
+            processed: 4,
+            total: 4,
+        });
+        let record = edf_actual.read_record_at(0).unwrap().unwrap();
+        assert_eq!(record.raw_signal_samples[0], generate_default_signal1_data(1));
+
+        // ============== CLEANUP ==============
+
+        remove_file(path_actual).unwrap();
+    }
+
+    // =====================================
+    // =              HELPERS              =
+    // =====================================
+
+    fn get_paths(name: &str) -> (String, String) {
+        let name_actual = name.to_string() + "_actual";
+        let name_expected = name.to_string() + "_expected";
+        let path_actual = generate_test_edf(&name_actual);
+
+        // Clean previous test file
+        let path_expected = generate_file_path(&name_expected);
+        if exists(&path_expected).unwrap() {
+            remove_file(&path_expected).unwrap();
+        }
+
+        (path_actual, path_expected)
+    }
+
+    fn generate_default_signal1() -> SignalHeader {
+        let mut signal1 = SignalHeader::new();
+        signal1
+            .with_label("Signal1".to_string())
+            .with_transducer("Unknown1".to_string())
+            .with_physical_dimension("uV".to_string())
+            .with_prefilter("".to_string())
+            .with_physical_range(-1234.0, 1233.0)
+            .with_digital_range(-1024, 1024)
+            .with_samples_count(100);
+
+        signal1
+    }
+
+    fn generate_default_signal2() -> SignalHeader {
+        let mut signal2 = SignalHeader::new();
+        signal2
+            .with_label("Signal2".to_string())
+            .with_transducer("Unknown2".to_string())
+            .with_physical_dimension("uV".to_string())
+            .with_prefilter("HP:0.1Hz LP:75Hz".to_string())
+            .with_physical_range(-512.0, 512.0)
+            .with_digital_range(-64, 64)
+            .with_samples_count(127);
+
+        signal2
+    }
+
+    fn generate_upsampled_signal2() -> SignalHeader {
+        let mut signal2 = generate_default_signal2();
+        signal2.with_samples_count(210);
+        signal2
+    }
+
+    fn generate_downsampled_signal2() -> SignalHeader {
+        let mut signal2 = generate_default_signal2();
+        signal2.with_samples_count(61);
+        signal2
+    }
+
+    fn generate_default_annotations() -> SignalHeader {
+        SignalHeader::new_annotation(80, EDFSpecifications::EDF)
+    }
+
+    fn generate_custom_signal_record<T: Fn(usize) -> Samples>(
+        edf: &EDFFile,
+        index: usize,
+        samples: Vec<T>,
+    ) -> Record {
+        let mut record = edf.header.create_record();
+        record.raw_signal_samples = samples.iter().map(|genr| genr(index)).collect();
+        record.annotations = vec![vec![
+            AnnotationList::new(0.0, 0.0, vec![format!("GlobalAnnotation {}", index)]).unwrap(),
+        ]];
+
+        record
+    }
+
+    fn generate_default_record(edf: &EDFFile, index: usize) -> Record {
+        let mut record = edf.header.create_record();
+        record.raw_signal_samples = vec![
+            generate_default_signal1_data(index),
+            generate_default_signal2_data(index),
+        ];
+        record.annotations = vec![vec![
+            AnnotationList::new(0.0, 0.0, vec![format!("GlobalAnnotation {}", index)]).unwrap(),
+        ]];
+
+        record
+    }
+
+    fn generate_time_keeping_record(edf: &EDFFile, index: usize, onset: f64) -> Record {
+        let mut record = edf.header.create_record();
+        record.raw_signal_samples = vec![
+            generate_default_signal1_data(index),
+            generate_default_signal2_data(index),
+        ];
+        record.annotations = vec![vec![
+            AnnotationList::new_time_keeping(onset),
+            AnnotationList::new(onset + 0.5, 0.0, vec![format!("Annotation {}", index)]).unwrap(),
+        ]];
+
+        record
+    }
+
+    fn generate_default_signal2_data_upsampled(index: usize) -> Samples {
+        let mut default_data = generate_default_signal2_data(index);
+        assert!(default_data.extend(&Samples::Values16Bit(repeat_n(0, 210 - default_data.len()).collect())).is_ok());
+        default_data
+    }
+
+    fn generate_default_signal2_data_downsampled(index: usize) -> Samples {
+        Samples::Values16Bit(((0 + 25 * index as i16)..(61 + 25 * index as i16)).collect())
+    }
+
+    fn generate_default_signal1_data_downsampled(index: usize) -> Samples {
+        Samples::Values16Bit((0..50).map(|i| 25 * index as i16 + 2 * i).collect())
+    }
+
+    fn generate_default_signal2_data(index: usize) -> Samples {
+        Samples::Values16Bit(((0 + 25 * index as i16)..(127 + 25 * index as i16)).collect())
+    }
+
+    fn generate_default_signal1_data(index: usize) -> Samples {
+        Samples::Values16Bit(((0 + 25 * index as i16)..(100 + 25 * index as i16)).collect())
+    }
+
+    fn configure_default_header(edf_header: &mut EDFHeader) {
+        edf_header
+            .with_specification(EDFSpecifications::EDFPlus)
+            .with_is_continuous(true)
+            .with_patient_id(PatientId {
+                code: Some("PAT-CODE1".to_string()),
+                name: Some("Pat-NAME".to_string()),
+                date: Some(NaiveDate::from_ymd_opt(2001, 07, 11).unwrap()),
+                sex: Some(Sex::Male),
+                additional: Vec::new(),
+            })
+            .with_recording_id(RecordingId {
+                admin_code: Some("REC-CODE1".to_string()),
+                equipment: Some("EQUIPMENT".to_string()),
+                technician: Some("TECHNICIAN".to_string()),
+                startdate: Some(NaiveDate::from_ymd_opt(2026, 02, 13).unwrap()),
+                additional: Vec::new(),
+            })
+            .with_start_date(NaiveDate::from_ymd_opt(2026, 02, 13).unwrap())
+            .with_start_time(NaiveTime::from_hms_opt(17, 30, 0).unwrap())
+            .with_record_duration(1.0);
+    }
+
+    fn configure_legacy_header(edf_header: &mut EDFHeader) {
+        edf_header
+            .with_specification(EDFSpecifications::EDF)
+            .with_patient_id(PatientId {
+                name: Some("MCH-0234567 F 02-MAY-1951 Haagse_Harry".to_string()),
+                ..Default::default()
+            })
+            .with_recording_id(RecordingId {
+                admin_code: Some("Sleep lab 3".to_string()),
+                ..Default::default()
+            })
+            .with_start_date(NaiveDate::from_ymd_opt(2026, 2, 13).unwrap())
+            .with_start_time(NaiveTime::from_hms_opt(17, 30, 0).unwrap())
+            .with_record_duration(1.0);
+    }
+
+    fn generate_plain_record(edf: &EDFFile, index: usize) -> Record {
+        let mut record = edf.header.create_record();
+        record.raw_signal_samples = vec![
+            generate_default_signal1_data(index),
+            generate_default_signal2_data(index),
+        ];
+
+        record
+    }
+
+    fn generate_file_path(name: &str) -> String {
+        format!("code_tests/test_{}.edf", name)
+    }
+
+    // =====================================
+    // =         DEFAULT TEST FILE         =
+    // =====================================
+
+    fn generate_test_edf(name: &str) -> String {
+        // Clean previous test file
+        let path = generate_file_path(name);
+        if exists(&path).unwrap() {
+            remove_file(&path).unwrap();
+        }
+
+        // Create new EDF file
+        let mut edf = EDFFile::new(&path).unwrap();
+
+        // Configure header with defaults
+        configure_default_header(&mut edf.header);
+
+        // Insert the 2 default signals and the annotations
+        edf.insert_signal(0, generate_default_signal1()).unwrap();
+        edf.insert_signal(1, generate_default_signal2()).unwrap();
+        edf.insert_signal(2, generate_default_annotations())
+            .unwrap();
+
+        // Insert 5 default records
+        edf.append_record(generate_default_record(&edf, 0)).unwrap();
+        edf.append_record(generate_default_record(&edf, 1)).unwrap();
+        edf.append_record(generate_default_record(&edf, 2)).unwrap();
+        edf.append_record(generate_default_record(&edf, 3)).unwrap();
+        edf.append_record(generate_default_record(&edf, 4)).unwrap();
 
         // Save the file
         edf.save().unwrap();
@@ -1825,6 +3975,34 @@ mod file_edit_tests {
         // Return the path to the file
         path
     }
+
+    fn generate_time_keeping_test_edf(path: &str) -> String {
+        // Clean the previous test file
+        remove_file(path).unwrap();
+
+        // Create new EDF file
+        let mut edf = EDFFile::new(path).unwrap();
+
+        // Configure header with defaults
+        configure_default_header(&mut edf.header);
+
+        // Insert the 2 default signals and the annotations
+        edf.insert_signal(0, generate_default_signal1()).unwrap();
+        edf.insert_signal(1, generate_default_signal2()).unwrap();
+        edf.insert_signal(2, generate_default_annotations())
+            .unwrap();
+
+        // Insert 5 records with a Time-keeping annotation each
+        for i in 0..5 {
+            edf.append_record(generate_time_keeping_record(&edf, i, i as f64)).unwrap();
+        }
+
+        // Save the file
+        edf.save().unwrap();
+
+        // Return the path to the file
+        path.to_string()
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,508 @@
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+use std::str::FromStr;
+
+use chrono::NaiveTime;
+
+use crate::EDFSpecifications;
+use crate::error::edf_error::EDFError;
+use crate::file::{i24_from_le_bytes, round_nanos};
+use crate::headers::annotation_list::AnnotationList;
+use crate::headers::edf_header::EDFHeader;
+use crate::headers::patient::PatientId;
+use crate::headers::recording::RecordingId;
+use crate::headers::signal_header::SignalHeader;
+
+/// The name and size of every field of the general header
+const GENERAL_FIELDS: [(&str, usize); 10] = [
+    ("version", 8),
+    ("patient_id", 80),
+    ("recording_id", 80),
+    ("start_date", 8),
+    ("start_time", 8),
+    ("header_bytes", 8),
+    ("reserved", 44),
+    ("record_count", 8),
+    ("record_duration", 8),
+    ("signal_count", 4),
+];
+
+/// The name and size of every field of the signal headers. Every field is stored for all signals, before the next field follows
+const SIGNAL_FIELDS: [(&str, usize); 10] = [
+    ("label", 16),
+    ("transducer", 80),
+    ("physical_dimension", 8),
+    ("physical_minimum", 8),
+    ("physical_maximum", 8),
+    ("digital_minimum", 8),
+    ("digital_maximum", 8),
+    ("prefilter", 80),
+    ("samples_count", 8),
+    ("reserved", 32),
+];
+
+/// The largest size of a single data-record recommended by the EDF and EDF+ specifications
+const EDF_MAX_RECORD_BYTES: usize = 61440;
+
+/// The severity of a violation of the specification
+#[derive(Debug, Clone, PartialEq)]
+pub enum Severity {
+    /// The file does not conform to the specification and might not be readable by other applications
+    Error,
+
+    /// The file conforms to the specification, but violates one of its recommendations or is only partially supported by
+    /// other applications
+    Warning,
+}
+
+/// The kind of violation of the specification
+#[derive(Debug, Clone, PartialEq)]
+pub enum ViolationKind {
+    /// The value of a header field does not have the format required by the specification
+    InvalidField { field: &'static str, value: String },
+
+    /// A header field contains characters which are not printable ASCII characters
+    NonASCII { field: &'static str },
+
+    /// The size of the header does not match the amount of signals
+    HeaderBytes { stored: Option<usize>, expected: usize },
+
+    /// The size of the file does not match the size of the header and the amount of data-records. The expected size
+    /// is `u64::MAX` in case it exceeds the range of `u64`
+    FileSize { expected: u64, actual: u64 },
+
+    /// The amount of data-records is unknown (-1), which is only allowed while recording
+    UnknownRecordCount,
+
+    /// The data-records of an EDF or EDF+ file are larger than 61440 bytes
+    RecordSize { bytes: usize },
+
+    /// The given amount of samples of a signal within a single data-record are outside of the digital range of the
+    /// signal, where the value is the first of these samples
+    SampleOutOfRange { count: usize, value: i32 },
+
+    /// The EDF+ or BDF+ file does not contain any annotation signal
+    MissingAnnotationSignal,
+
+    /// The file contains more than one annotation signal. This is allowed by the specification, but many applications
+    /// only read the first annotation signal
+    DuplicateAnnotationSignal,
+
+    /// The data-record does not start with a Time-keeping annotation
+    MissingTimeKeeping,
+
+    /// The onset of the Time-keeping annotation is not larger than the onset of the preceding data-record
+    NonMonotonicTimeKeeping { previous: f64, onset: f64 },
+
+    /// The file is marked as continuous, but the onset of the data-record does not directly follow the preceding one
+    Discontinuous { expected: f64, onset: f64 },
+
+    /// A Time-stamped Annotations List (TAL) does not have the syntax required by the specification
+    InvalidTAL,
+
+    /// The annotations of a Time-stamped Annotations List (TAL) are not valid UTF-8
+    InvalidUTF8,
+}
+
+/// The location of a violation within the file
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ViolationLocation {
+    /// The byte offset of the violation relative to the start of the file
+    pub offset: u64,
+
+    /// The index of the data-record in case the violation is part of a data-record
+    pub record: Option<usize>,
+
+    /// The index of the signal in case the violation is part of a signal header or the samples of a signal
+    pub signal: Option<usize>,
+}
+
+/// A single violation of the specification found while validating a file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+    pub severity: Severity,
+    pub kind: ViolationKind,
+    pub location: ViolationLocation,
+}
+
+/// The report of all violations of the specification found while validating a file
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ValidationReport {
+    pub violations: Vec<Violation>,
+}
+
+impl ValidationReport {
+    /// Returns whether or not the file conforms to the specification, meaning there are no errors. Warnings are allowed
+    pub fn is_valid(&self) -> bool {
+        self.errors().next().is_none()
+    }
+
+    /// Returns all violations with the severity `Severity::Error`
+    pub fn errors(&self) -> impl Iterator<Item = &Violation> {
+        self.violations.iter().filter(|violation| violation.severity == Severity::Error)
+    }
+
+    /// Returns all violations with the severity `Severity::Warning`
+    pub fn warnings(&self) -> impl Iterator<Item = &Violation> {
+        self.violations.iter().filter(|violation| violation.severity == Severity::Warning)
+    }
+
+    fn push(&mut self, severity: Severity, kind: ViolationKind, offset: u64, record: Option<usize>, signal: Option<usize>) {
+        self.violations.push(Violation {
+            severity,
+            kind,
+            location: ViolationLocation { offset, record, signal },
+        });
+    }
+
+    fn push_invalid(&mut self, field: &'static str, value: &str, offset: u64, signal: Option<usize>) {
+        let kind = ViolationKind::InvalidField { field, value: value.to_string() };
+        self.push(Severity::Error, kind, offset, None, signal);
+    }
+}
+
+/// Validates the file at the given path against the specification it claims to follow (based on the reserved field of the
+/// header) and returns every violation found. The header is checked field by field and every data-record stored on disk is
+/// checked for samples outside of the digital range and, for EDF+ and BDF+ files, the syntax of its annotations and its
+/// Time-keeping annotation. Only failing to read the file results in an error.
+pub(crate) fn validate_file(path: &Path) -> Result<ValidationReport, EDFError> {
+    let file = File::open(path).map_err(EDFError::FileReadError)?;
+    let file_size = file.metadata().map_err(EDFError::FileReadError)?.len();
+    let mut reader = BufReader::new(file);
+    let mut report = ValidationReport::default();
+
+    if file_size < 256 {
+        report.push(Severity::Error, ViolationKind::FileSize { expected: 256, actual: file_size }, 0, None, None);
+        return Ok(report);
+    }
+    let mut general = vec![0; 256];
+    reader.read_exact(&mut general).map_err(EDFError::FileReadError)?;
+
+    // Split the general header into its fields
+    let mut fields = Vec::with_capacity(GENERAL_FIELDS.len());
+    let mut offset = 0;
+    for (field, size) in GENERAL_FIELDS {
+        fields.push((field, offset as u64, &general[offset..offset + size]));
+        offset += size;
+    }
+
+    let (specification, is_continuous) = EDFHeader::parse_reserved(&text(fields[6].2));
+    let is_bdf = matches!(specification, EDFSpecifications::BDF | EDFSpecifications::BDFPlus);
+
+    // The version of BDF files starts with the byte 255, which is the only non-ASCII character allowed in the header
+    let version_valid = if is_bdf { general[0] == 255 && &general[1..8] == b"BIOSEMI" } else { text(fields[0].2) == "0" };
+    if !version_valid {
+        report.push_invalid("version", &text(fields[0].2), 0, None);
+    }
+    for (field, offset, bytes) in fields.iter().skip(1) {
+        check_ascii(&mut report, field, bytes, *offset, None);
+    }
+
+    let value = |idx: usize| text(fields[idx].2);
+    if PatientId::deserialize(value(1), &specification).is_err() {
+        report.push_invalid("patient_id", &value(1), fields[1].1, None);
+    }
+    if RecordingId::deserialize(value(2), &specification).is_err() {
+        report.push_invalid("recording_id", &value(2), fields[2].1, None);
+    }
+    let start_date = fields[3].2.iter().map(|c| *c as char).collect::<String>();
+    if EDFHeader::parse_old_start_date(&start_date).is_err() {
+        report.push_invalid("start_date", &start_date, fields[3].1, None);
+    }
+    let start_time = fields[4].2.iter().map(|c| *c as char).collect::<String>();
+    if NaiveTime::parse_from_str(&start_time, "%H.%M.%S").is_err() {
+        report.push_invalid("start_time", &start_time, fields[4].1, None);
+    }
+    let header_bytes = usize::from_str(&value(5)).ok();
+    let record_count = match value(7).as_str() {
+        "-1" => {
+            report.push(Severity::Warning, ViolationKind::UnknownRecordCount, fields[7].1, None, None);
+            None
+        }
+        count => {
+            let count = usize::from_str(count).ok();
+            if count.is_none() {
+                report.push_invalid("record_count", &value(7), fields[7].1, None);
+            }
+            count
+        }
+    };
+    let record_duration = f64::from_str(&value(8)).ok().filter(|duration| *duration >= 0.0);
+    if record_duration.is_none() {
+        report.push_invalid("record_duration", &value(8), fields[8].1, None);
+    }
+
+    // The signal headers can not be located without the amount of signals
+    let Some(signal_count) = usize::from_str(&value(9)).ok().filter(|count| *count > 0) else {
+        report.push_invalid("signal_count", &value(9), fields[9].1, None);
+        return Ok(report);
+    };
+
+    let expected_header_bytes = 256 * (signal_count + 1);
+    if header_bytes != Some(expected_header_bytes) {
+        let kind = ViolationKind::HeaderBytes { stored: header_bytes, expected: expected_header_bytes };
+        report.push(Severity::Error, kind, fields[5].1, None, None);
+    }
+    if file_size < expected_header_bytes as u64 {
+        let kind = ViolationKind::FileSize { expected: expected_header_bytes as u64, actual: file_size };
+        report.push(Severity::Error, kind, 0, None, None);
+        return Ok(report);
+    }
+
+    let mut signal_bytes = vec![0; 256 * signal_count];
+    reader.read_exact(&mut signal_bytes).map_err(EDFError::FileReadError)?;
+    let (signals, records_readable) = validate_signals(&mut report, &signal_bytes, &specification);
+
+    // Annotation signals are only part of the extended specifications
+    let annotation_signals = signals.iter().enumerate().filter(|(_, signal)| signal.is_annotation()).map(|(idx, _)| idx).collect::<Vec<_>>();
+    if specification.is_plus() {
+        if annotation_signals.is_empty() {
+            report.push(Severity::Error, ViolationKind::MissingAnnotationSignal, 256, None, None);
+        }
+        for signal in annotation_signals.iter().skip(1) {
+            let offset = 256 + 16 * *signal as u64;
+            report.push(Severity::Warning, ViolationKind::DuplicateAnnotationSignal, offset, None, Some(*signal));
+        }
+    }
+    if record_duration == Some(0.0) && signals.iter().any(|signal| !signal.is_annotation()) {
+        report.push_invalid("record_duration", &value(8), fields[8].1, None);
+    }
+    if !records_readable {
+        return Ok(report);
+    }
+
+    let sample_bytes = specification.sample_bytes();
+    let record_bytes = signals.iter().map(|signal| signal.samples_count * sample_bytes).sum::<usize>();
+    if !is_bdf && record_bytes > EDF_MAX_RECORD_BYTES {
+        report.push(Severity::Warning, ViolationKind::RecordSize { bytes: record_bytes }, fields[8].1, None, None);
+    }
+    if record_bytes == 0 {
+        return Ok(report);
+    }
+
+    // Check the size of the file based on the stored amount of data-records or the amount of complete data-records in
+    // case the amount is unknown
+    let data_size = file_size - expected_header_bytes as u64;
+    let complete_records = (data_size / record_bytes as u64) as usize;
+    let expected_records = record_count.unwrap_or(complete_records);
+    let expected_size = (expected_records as u64)
+        .checked_mul(record_bytes as u64)
+        .and_then(|size| size.checked_add(expected_header_bytes as u64))
+        .unwrap_or(u64::MAX);
+    if expected_size != file_size {
+        report.push(Severity::Error, ViolationKind::FileSize { expected: expected_size, actual: file_size }, 0, None, None);
+    }
+
+    // The size of a data-record is taken from the header, therefore the buffer is only allocated in case the file
+    // actually contains a complete data-record
+    if complete_records == 0 || expected_records == 0 {
+        return Ok(report);
+    }
+    let mut record = vec![0; record_bytes];
+    let mut previous_onset = None;
+    for record_idx in 0..complete_records.min(expected_records) {
+        reader.read_exact(&mut record).map_err(EDFError::FileReadError)?;
+        let record_offset = (expected_header_bytes + record_idx * record_bytes) as u64;
+
+        let mut start = 0;
+        for (signal_idx, signal) in signals.iter().enumerate() {
+            let end = start + signal.samples_count * sample_bytes;
+            let data = &record[start..end];
+            let offset = record_offset + start as u64;
+            start = end;
+
+            if !specification.is_plus() || !signal.is_annotation() {
+                check_samples(&mut report, signal, data, sample_bytes, offset, record_idx, signal_idx);
+                continue;
+            }
+
+            let tals = check_tals(&mut report, data, offset, record_idx, signal_idx);
+            if annotation_signals.first() != Some(&signal_idx) {
+                continue;
+            }
+
+            // Only the first annotation signal is required to start with the Time-keeping annotation
+            // A data-record without a Time-keeping annotation is assumed to directly follow the preceding one
+            let Some(onset) = tals.first().filter(|tal| tal.is_time_keeping()).map(|tal| tal.onset) else {
+                report.push(Severity::Error, ViolationKind::MissingTimeKeeping, offset, Some(record_idx), Some(signal_idx));
+                previous_onset = previous_onset.map(|previous| round_nanos(previous + record_duration.unwrap_or_default()));
+                continue;
+            };
+            if let Some(previous) = previous_onset {
+                let expected = round_nanos(previous + record_duration.unwrap_or_default());
+                if onset <= previous {
+                    let kind = ViolationKind::NonMonotonicTimeKeeping { previous, onset };
+                    report.push(Severity::Error, kind, offset, Some(record_idx), Some(signal_idx));
+                } else if is_continuous && round_nanos(onset) != expected {
+                    let kind = ViolationKind::Discontinuous { expected, onset };
+                    report.push(Severity::Error, kind, offset, Some(record_idx), Some(signal_idx));
+                }
+            }
+            previous_onset = Some(onset);
+        }
+    }
+
+    Ok(report)
+}
+
+/// Validates every field of the given signal headers and returns the parsed signals together with whether or not the
+/// fields required to locate the samples of every signal within a data-record are valid
+fn validate_signals(report: &mut ValidationReport, bytes: &[u8], specification: &EDFSpecifications) -> (Vec<SignalHeader>, bool) {
+    let signal_count = bytes.len() / 256;
+    let mut signals = vec![SignalHeader::default(); signal_count];
+    let mut records_readable = true;
+
+    let mut field_start = 0;
+    for (field, size) in SIGNAL_FIELDS {
+        for (idx, signal) in signals.iter_mut().enumerate() {
+            let field_bytes = &bytes[field_start + idx * size..field_start + (idx + 1) * size];
+            let offset = (256 + field_start + idx * size) as u64;
+            check_ascii(report, field, field_bytes, offset, Some(idx));
+
+            let value = text(field_bytes);
+            let valid = match field {
+                "label" => {
+                    signal.label = value.clone();
+                    true
+                }
+                "physical_minimum" => f64::from_str(&value).map(|v| signal.physical_minimum = v).is_ok(),
+                "physical_maximum" => f64::from_str(&value).map(|v| signal.physical_maximum = v).is_ok(),
+                "digital_minimum" => i32::from_str(&value).map(|v| signal.digital_minimum = v).is_ok(),
+                "digital_maximum" => i32::from_str(&value).map(|v| signal.digital_maximum = v).is_ok(),
+                "samples_count" => {
+                    let parsed = usize::from_str(&value).map(|v| signal.samples_count = v).is_ok();
+                    records_readable &= parsed;
+                    parsed
+                }
+                _ => true,
+            };
+            if !valid {
+                report.push_invalid(field, &value, offset, Some(idx));
+            }
+        }
+        field_start += size * signal_count;
+    }
+
+    // Check the ranges of every signal, where the ranges of annotation signals are arbitrary but have to be valid as well
+    let (digital_min, digital_max) = specification.digital_range();
+    let field_offset = |field: usize, idx: usize| {
+        let start = SIGNAL_FIELDS[..field].iter().map(|(_, size)| size * signal_count).sum::<usize>();
+        (256 + start + idx * SIGNAL_FIELDS[field].1) as u64
+    };
+    for (idx, signal) in signals.iter().enumerate() {
+        if signal.physical_minimum == signal.physical_maximum {
+            report.push_invalid("physical_maximum", &signal.physical_maximum.to_string(), field_offset(4, idx), Some(idx));
+        }
+        if signal.digital_minimum < digital_min || signal.digital_minimum > digital_max {
+            report.push_invalid("digital_minimum", &signal.digital_minimum.to_string(), field_offset(5, idx), Some(idx));
+        }
+        let maximum_in_range = (digital_min..=digital_max).contains(&signal.digital_maximum);
+        if !maximum_in_range || signal.digital_maximum <= signal.digital_minimum {
+            report.push_invalid("digital_maximum", &signal.digital_maximum.to_string(), field_offset(6, idx), Some(idx));
+        }
+    }
+
+    (signals, records_readable)
+}
+
+/// Adds a violation in case the given header field contains characters which are not printable ASCII characters
+fn check_ascii(report: &mut ValidationReport, field: &'static str, bytes: &[u8], offset: u64, signal: Option<usize>) {
+    if let Some(idx) = bytes.iter().position(|c| !matches!(c, 0x20..=0x7E)) {
+        report.push(Severity::Error, ViolationKind::NonASCII { field }, offset + idx as u64, None, signal);
+    }
+}
+
+/// Adds a violation in case any of the given samples of a signal is outside of the digital range of the signal
+fn check_samples(
+    report: &mut ValidationReport,
+    signal: &SignalHeader,
+    data: &[u8],
+    sample_bytes: usize,
+    offset: u64,
+    record: usize,
+    signal_idx: usize,
+) {
+    let min = signal.digital_minimum.min(signal.digital_maximum);
+    let max = signal.digital_minimum.max(signal.digital_maximum);
+    let mut outside = data
+        .chunks_exact(sample_bytes)
+        .map(|bytes| match bytes {
+            [low, high] => i16::from_le_bytes([*low, *high]) as i32,
+            bytes => i24_from_le_bytes([bytes[0], bytes[1], bytes[2]]),
+        })
+        .enumerate()
+        .filter(|(_, value)| *value < min || *value > max);
+
+    if let Some((idx, value)) = outside.next() {
+        let kind = ViolationKind::SampleOutOfRange { count: outside.count() + 1, value };
+        report.push(Severity::Error, kind, offset + (idx * sample_bytes) as u64, Some(record), Some(signal_idx));
+    }
+}
+
+/// Adds a violation for every Time-stamped Annotations List (TAL) of the given annotation signal which does not have a
+/// valid syntax. Returns all valid TALs
+fn check_tals(report: &mut ValidationReport, data: &[u8], offset: u64, record: usize, signal: usize) -> Vec<AnnotationList> {
+    let mut tals = Vec::new();
+    let mut pos = 0;
+    while pos < data.len() && data[pos] != 0 {
+        let tal_offset = offset + pos as u64;
+
+        // Every TAL ends with the bytes HEX 14 and HEX 00
+        let Some(end) = data[pos..].windows(2).position(|bytes| bytes == [0x14, 0x00]).map(|idx| pos + idx + 2) else {
+            report.push(Severity::Error, ViolationKind::InvalidTAL, tal_offset, Some(record), Some(signal));
+            return tals;
+        };
+        let tal = &data[pos..end];
+        pos = end;
+
+        let header_len = tal.iter().position(|c| *c == 0x14).unwrap_or_default();
+        if !is_valid_tal_header(&tal[..header_len]) {
+            report.push(Severity::Error, ViolationKind::InvalidTAL, tal_offset, Some(record), Some(signal));
+            continue;
+        }
+        if std::str::from_utf8(&tal[(header_len + 1).min(tal.len() - 2)..tal.len() - 2]).is_err() {
+            report.push(Severity::Error, ViolationKind::InvalidUTF8, tal_offset, Some(record), Some(signal));
+            continue;
+        }
+        match AnnotationList::deserialize(tal) {
+            Ok(tal) => tals.push(tal),
+            Err(_) => report.push(Severity::Error, ViolationKind::InvalidTAL, tal_offset, Some(record), Some(signal)),
+        }
+    }
+
+    // The remaining bytes following the last TAL have to be NUL bytes
+    if let Some(idx) = data[pos..].iter().position(|c| *c != 0) {
+        report.push(Severity::Error, ViolationKind::InvalidTAL, offset + (pos + idx) as u64, Some(record), Some(signal));
+    }
+
+    tals
+}
+
+/// Returns whether or not the given TAL header consists of a signed onset, optionally followed by the byte HEX 15 and
+/// an unsigned duration (e.g. `+30.5` or `-0.2\x1510`)
+fn is_valid_tal_header(header: &[u8]) -> bool {
+    let mut parts = header.split(|c| *c == 0x15);
+    let onset = parts.next().unwrap_or_default();
+    let duration = parts.next();
+
+    matches!(onset.first(), Some(b'+' | b'-'))
+        && is_tal_number(&onset[1..])
+        && duration.is_none_or(is_tal_number)
+        && parts.next().is_none()
+}
+
+/// Returns whether or not the given value is an unsigned decimal number using a dot as decimal separator
+fn is_tal_number(value: &[u8]) -> bool {
+    let mut parts = value.splitn(2, |c| *c == b'.');
+    let whole = parts.next().unwrap_or_default();
+
+    !whole.is_empty()
+        && whole.iter().all(u8::is_ascii_digit)
+        && parts.next().is_none_or(|fraction| !fraction.is_empty() && fraction.iter().all(u8::is_ascii_digit))
+}
+
+/// Returns the given header field as text without its trailing spaces
+fn text(bytes: &[u8]) -> String {
+    bytes.iter().map(|c| *c as char).collect::<String>().trim_ascii_end().to_string()
+}